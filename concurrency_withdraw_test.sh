@@ -0,0 +1,95 @@
+#!/bin/bash
+
+# 併發下分測試
+# 對同一個玩家錢包同時送出大量 GameWallet/Withdraw
+# 驗證錢包餘額不會變成負數 且扣除的金額與成功的下分次數一致
+# 需要安裝 ghz, grpcurl, jq
+
+# Set your gRPC service details
+# proto 的路徑預設為與本專案同層的 protos repo 可用環境變數 PROTO_PATH 覆寫
+PROTO_PATH="${PROTO_PATH:-$(dirname "$0")/../protos/protos/wallet}"
+PROTO_FILE="game_wallet.proto"
+SERVICE_ADDRESS="localhost:1691"
+
+# Set wallet details
+CLIENT_ID="7135148985370546176"
+USER_ID="7188781879867346945"
+CURRENCY="USD"
+WALLET_SOURCE="1"
+
+# Set test parameters
+TOTAL_REQUESTS=200
+CONCURRENCY=40
+WITHDRAW_AMOUNT="10"
+DEPOSIT_AMOUNT="500"
+# 每次測試使用不同的交易單號區間
+TXN_BASE=$(date +%s%N)
+
+get_balance() {
+    grpcurl -plaintext \
+        -import-path "$PROTO_PATH" \
+        -proto $PROTO_FILE \
+        -d "{
+        \"client_id\": \"$CLIENT_ID\",
+        \"user_id\": \"$USER_ID\",
+        \"currency\": \"$CURRENCY\",
+        \"wallet_source\": \"$WALLET_SOURCE\"
+    }" \
+        $SERVICE_ADDRESS game_wallet.GameWallet.Get | jq -r '.balance'
+}
+
+# 先上分 確保餘額只夠部分下分請求成功
+grpcurl -plaintext \
+    -import-path "$PROTO_PATH" \
+    -proto $PROTO_FILE \
+    -d "{
+    \"client_id\": \"$CLIENT_ID\",
+    \"user_id\": \"$USER_ID\",
+    \"currency\": \"$CURRENCY\",
+    \"wallet_source\": \"$WALLET_SOURCE\",
+    \"amount\": \"$DEPOSIT_AMOUNT\",
+    \"effective_bet\": \"$DEPOSIT_AMOUNT\",
+    \"rollover_rate\": \"1\",
+    \"transaction_id\": \"$TXN_BASE\"
+}" \
+    $SERVICE_ADDRESS game_wallet.GameWallet.Deposit >/dev/null || exit 1
+
+BEFORE_BALANCE=$(get_balance)
+echo "before balance: $BEFORE_BALANCE"
+
+# 併發下分 總金額大於餘額
+RESULT=$(ghz --insecure \
+    --import-paths "$PROTO_PATH" \
+    --proto $PROTO_FILE \
+    --call game_wallet.GameWallet.Withdraw \
+    -d "{
+    \"client_id\": \"$CLIENT_ID\",
+    \"user_id\": \"$USER_ID\",
+    \"currency\": \"$CURRENCY\",
+    \"wallet_source\": \"$WALLET_SOURCE\",
+    \"amount\": \"$WITHDRAW_AMOUNT\",
+    \"transaction_id\": \"{{add $TXN_BASE .RequestNumber 1}}\"
+}" \
+    -n $TOTAL_REQUESTS \
+    -c $CONCURRENCY \
+    --format json \
+    $SERVICE_ADDRESS)
+
+SUCCESS_COUNT=$(echo "$RESULT" | jq -r '.statusCodeDistribution.OK // 0')
+AFTER_BALANCE=$(get_balance)
+echo "success withdraw: $SUCCESS_COUNT"
+echo "after balance: $AFTER_BALANCE"
+
+# 驗證結果
+awk -v before="$BEFORE_BALANCE" -v after="$AFTER_BALANCE" \
+    -v count="$SUCCESS_COUNT" -v amount="$WITHDRAW_AMOUNT" 'BEGIN {
+    if (after < 0) {
+        print "FAIL: balance is negative"
+        exit 1
+    }
+    if (before - count * amount != after) {
+        printf "FAIL: expected balance %s but got %s\n", before - count * amount, after
+        exit 1
+    }
+    print "PASS"
+}'
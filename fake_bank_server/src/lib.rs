@@ -0,0 +1,48 @@
+//! 假的 bank server 依照 seed 檔回傳 client 的幣別
+//! 讓錢包服務可以在沒有 bank server 的環境下啟動 也可以在整合測試中使用
+pub mod seed;
+pub mod service;
+#[cfg(test)]
+mod spawn_test;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use protos::client_currency::client_currency_server::ClientCurrencyServer;
+use protos::currency::currency_server::CurrencyServer;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+pub use seed::Seed;
+
+/// 啟動假的 bank server
+/// 回傳前已綁定 port 之後的連線會排隊等待服務處理 呼叫端不需要另外等待服務就緒
+/// ### 參數
+/// - `addr`: SocketAddr - 監聽的位址 port 為 0 時使用隨機 port
+/// - `seed`: Seed - 假資料
+/// ### 回傳
+/// - `SocketAddr` - 實際監聽的位址 整合測試使用隨機 port 時需要
+/// - `JoinHandle` - 服務的工作 服務異常結束時回傳錯誤 abort 可以停止服務
+pub async fn spawn(
+    addr: SocketAddr,
+    seed: Seed,
+) -> std::io::Result<(SocketAddr, JoinHandle<Result<(), tonic::transport::Error>>)> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    let seed = Arc::new(seed);
+
+    let handle = tokio::spawn(
+        Server::builder()
+            .add_service(CurrencyServer::new(service::FakeCurrencyService::new(
+                seed.clone(),
+            )))
+            .add_service(ClientCurrencyServer::new(
+                service::FakeClientCurrencyService::new(seed),
+            ))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    Ok((local_addr, handle))
+}
@@ -0,0 +1,26 @@
+use fake_bank_server::Seed;
+use kgs_tracing::info;
+
+/// 環境變數
+/// - `Fake_Bank_Seed`: seed 檔路徑 預設為 seed.json
+/// - `Fake_Bank_Port`: 監聽的 port 預設與 Bank_Server_Port 相同為 1689
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 本機使用 不輸出到 loki 與 otlp
+    kgs_tracing::TelemetryBuilder::new("fake_bank_server").build();
+
+    let seed_path = std::env::var("Fake_Bank_Seed").unwrap_or_else(|_| "seed.json".to_string());
+    let port = std::env::var("Fake_Bank_Port").unwrap_or_else(|_| "1689".to_string());
+
+    let seed = Seed::from_file(&seed_path)?;
+    let (addr, server) =
+        fake_bank_server::spawn(format!("0.0.0.0:{}", port).parse()?, seed).await?;
+    info!("fake bank server start on {:?}, seed: {}", addr, seed_path);
+
+    // 服務異常結束時回傳錯誤 不要停在等待關閉訊號
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result?,
+        result = server => result??,
+    }
+    Ok(())
+}
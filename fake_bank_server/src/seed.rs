@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+/// 假資料 從 json 檔載入
+#[derive(Deserialize, Debug, Clone)]
+pub struct Seed {
+    pub currencies: Vec<SeedCurrency>,
+    pub clients: Vec<SeedClient>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SeedCurrency {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SeedClient {
+    pub client_id: i64,
+    pub business_id: i64,
+    pub currencies: Vec<SeedClientCurrency>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SeedClientCurrency {
+    pub currency_id: i64,
+    pub status: i32, // 0:停用 1:啟用
+}
+
+/// client 可使用的幣別
+#[derive(Debug, Clone)]
+pub struct ClientCurrency {
+    pub id: i64,
+    pub name: String,
+    pub status: i32,
+}
+
+impl Seed {
+    pub fn from_file(path: &str) -> Result<Seed, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 取得 client 的幣別 business_id 為 0 時不檢查
+    pub fn get_client_currencies(&self, business_id: i64, client_id: i64) -> Vec<ClientCurrency> {
+        self.clients
+            .iter()
+            .filter(|client| client.client_id == client_id)
+            .filter(|client| business_id == 0 || client.business_id == business_id)
+            .flat_map(|client| client.currencies.iter())
+            .filter_map(|client_currency| {
+                self.currencies
+                    .iter()
+                    .find(|currency| currency.id == client_currency.currency_id)
+                    .map(|currency| ClientCurrency {
+                        id: currency.id,
+                        name: currency.name.clone(),
+                        status: client_currency.status,
+                    })
+            })
+            .collect()
+    }
+}
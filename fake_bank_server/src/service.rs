@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+use protos::client_currency::client_currency_server::ClientCurrency;
+use protos::client_currency::{CurrencyModel, GetByIdRequest, GetByNameRequest};
+use protos::currency::currency_server::Currency;
+use protos::currency::{GetRequest, GetResponse};
+use tonic::{Request, Response, Status};
+
+use crate::seed::{self, Seed};
+
+/// 實作 currency_server::Currency
+#[derive(Debug)]
+pub struct FakeCurrencyService {
+    seed: Arc<Seed>,
+}
+
+impl FakeCurrencyService {
+    pub fn new(seed: Arc<Seed>) -> Self {
+        Self { seed }
+    }
+}
+
+#[tonic::async_trait]
+impl Currency for FakeCurrencyService {
+    async fn get(&self, request: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
+        let request = request.into_inner();
+
+        // 空的列表表示不篩選
+        let currency = self
+            .seed
+            .get_client_currencies(request.business, request.client)
+            .into_iter()
+            .filter(|currency| {
+                request.currency.is_empty() || request.currency.contains(&currency.name)
+            })
+            .filter(|currency| {
+                request.status.is_empty() || request.status.contains(&currency.status)
+            })
+            .map(|currency| protos::currency::CurrencyModel {
+                id: currency.id,
+                name: currency.name,
+                status: currency.status,
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(Response::new(GetResponse {
+            currency,
+            ..Default::default()
+        }))
+    }
+}
+
+/// 實作 client_currency_server::ClientCurrency
+#[derive(Debug)]
+pub struct FakeClientCurrencyService {
+    seed: Arc<Seed>,
+}
+
+impl FakeClientCurrencyService {
+    pub fn new(seed: Arc<Seed>) -> Self {
+        Self { seed }
+    }
+
+    fn find(
+        &self,
+        client_id: i64,
+        predicate: impl Fn(&seed::ClientCurrency) -> bool,
+    ) -> Result<Response<CurrencyModel>, Status> {
+        self.seed
+            .get_client_currencies(0, client_id)
+            .into_iter()
+            .find(|currency| predicate(currency))
+            .map(|currency| {
+                Response::new(CurrencyModel {
+                    id: currency.id,
+                    name: currency.name,
+                    status: currency.status,
+                    ..Default::default()
+                })
+            })
+            .ok_or_else(|| kgs_err::grpc::error::error_response(KgsStatus::DataNotFound))
+    }
+}
+
+#[tonic::async_trait]
+impl ClientCurrency for FakeClientCurrencyService {
+    async fn get_by_id(
+        &self,
+        request: Request<GetByIdRequest>,
+    ) -> Result<Response<CurrencyModel>, Status> {
+        let request = request.into_inner();
+        self.find(request.client_id, |currency| {
+            currency.id == request.currency_id
+        })
+    }
+
+    async fn get_by_name(
+        &self,
+        request: Request<GetByNameRequest>,
+    ) -> Result<Response<CurrencyModel>, Status> {
+        let request = request.into_inner();
+        self.find(request.client_id, |currency| currency.name == request.name)
+    }
+}
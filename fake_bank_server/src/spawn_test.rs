@@ -0,0 +1,90 @@
+//! 啟動假的 bank server 並以 gRPC client 查詢幣別
+
+use protos::client_currency::client_currency_client::ClientCurrencyClient;
+use protos::client_currency::{GetByIdRequest, GetByNameRequest};
+use protos::currency::currency_client::CurrencyClient;
+use protos::currency::GetRequest;
+
+use crate::seed::{SeedClient, SeedClientCurrency, SeedCurrency};
+use crate::Seed;
+
+fn seed() -> Seed {
+    Seed {
+        currencies: vec![
+            SeedCurrency {
+                id: 1,
+                name: "USD".to_string(),
+            },
+            SeedCurrency {
+                id: 2,
+                name: "TWD".to_string(),
+            },
+        ],
+        clients: vec![SeedClient {
+            client_id: 1,
+            business_id: 1,
+            currencies: vec![
+                SeedClientCurrency {
+                    currency_id: 1,
+                    status: 1,
+                },
+                SeedClientCurrency {
+                    currency_id: 2,
+                    status: 0,
+                },
+            ],
+        }],
+    }
+}
+
+#[tokio::test]
+async fn spawn_and_lookup_currency() {
+    let (addr, server) = crate::spawn("127.0.0.1:0".parse().unwrap(), seed())
+        .await
+        .unwrap();
+    let endpoint = format!("http://{}", addr);
+
+    let mut client_currency = ClientCurrencyClient::connect(endpoint.clone())
+        .await
+        .unwrap();
+    let currency = client_currency
+        .get_by_id(GetByIdRequest {
+            client_id: 1,
+            currency_id: 1,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(currency.name, "USD");
+    assert_eq!(currency.status, 1);
+
+    // seed 沒有的幣別
+    let status = client_currency
+        .get_by_name(GetByNameRequest {
+            client_id: 1,
+            name: "USDT".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_ne!(status.code(), tonic::Code::Ok);
+
+    let mut currency_client = CurrencyClient::connect(endpoint).await.unwrap();
+    let response = currency_client
+        .get(GetRequest {
+            client: 1,
+            business: 1,
+            currency: vec![],
+            status: vec![1],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let names: Vec<String> = response
+        .currency
+        .into_iter()
+        .map(|currency| currency.name)
+        .collect();
+    assert_eq!(names, vec!["USD".to_string()]);
+
+    server.abort();
+}
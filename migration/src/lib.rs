@@ -5,6 +5,22 @@ mod m20240816_054942_create_user_wallet_table;
 mod m20240816_060441_create_wallet_transaction_table;
 mod m20240816_061738_create_rollover_main_table;
 mod m20240816_062915_crate_rollover_record_table;
+mod m20261018_020000_add_wallet_transaction_source_unique_index;
+mod m20261018_030000_add_user_wallet_hold_amount;
+mod m20261018_040000_add_wallet_transaction_query_index;
+mod m20261018_050000_create_wallet_outbox_table;
+mod m20261018_060000_create_reconciliation_report_table;
+mod m20261018_070000_create_wallet_limit_table;
+mod m20261018_080000_add_version_columns;
+mod m20261018_090000_add_wallet_transaction_payment_reference;
+mod m20261018_100000_add_wallet_source_to_game_source_unique_index;
+mod m20261018_110000_create_bonus_grant_table;
+mod m20261018_120000_add_bonus_grant_campaign_columns;
+mod m20261018_130000_create_rollover_contribution_table;
+mod m20261018_140000_add_wallet_transaction_hold_id;
+mod m20261018_150000_add_bonus_grant_forfeit_retry;
+mod m20261018_160000_add_transfer_to_source_unique_index;
+mod m20261018_170000_add_wallet_transaction_combined_leg;
 
 pub struct Migrator;
 
@@ -17,6 +33,22 @@ impl MigratorTrait for Migrator {
             Box::new(m20240816_060441_create_wallet_transaction_table::Migration),
             Box::new(m20240816_061738_create_rollover_main_table::Migration),
             Box::new(m20240816_062915_crate_rollover_record_table::Migration),
+            Box::new(m20261018_020000_add_wallet_transaction_source_unique_index::Migration),
+            Box::new(m20261018_030000_add_user_wallet_hold_amount::Migration),
+            Box::new(m20261018_040000_add_wallet_transaction_query_index::Migration),
+            Box::new(m20261018_050000_create_wallet_outbox_table::Migration),
+            Box::new(m20261018_060000_create_reconciliation_report_table::Migration),
+            Box::new(m20261018_070000_create_wallet_limit_table::Migration),
+            Box::new(m20261018_080000_add_version_columns::Migration),
+            Box::new(m20261018_090000_add_wallet_transaction_payment_reference::Migration),
+            Box::new(m20261018_100000_add_wallet_source_to_game_source_unique_index::Migration),
+            Box::new(m20261018_110000_create_bonus_grant_table::Migration),
+            Box::new(m20261018_120000_add_bonus_grant_campaign_columns::Migration),
+            Box::new(m20261018_130000_create_rollover_contribution_table::Migration),
+            Box::new(m20261018_140000_add_wallet_transaction_hold_id::Migration),
+            Box::new(m20261018_150000_add_bonus_grant_forfeit_retry::Migration),
+            Box::new(m20261018_160000_add_transfer_to_source_unique_index::Migration),
+            Box::new(m20261018_170000_add_wallet_transaction_combined_leg::Migration),
         ]
     }
 }
@@ -0,0 +1,27 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 遊戲上下分的原始交易(parent_id = 0) 同一個玩家的來源交易 ID 與動作只能出現一次
+/// rollback 與 update 產生的交易 parent_id 不為 0 不受此限制
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 錢包新增凍結金額 出款審核中的金額會先凍結 不可再被使用
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWallet::Table)
+                    .add_column_if_not_exists(decimal(UserWallet::HoldAmount).not_null().default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        let comment = r#"
+            COMMENT ON COLUMN user_wallet.hold_amount IS '凍結金額';
+        "#;
+        manager.get_connection().execute_unprepared(comment).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWallet::Table)
+                    .drop_column(UserWallet::HoldAmount)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserWallet {
+    Table,
+    HoldAmount,
+}
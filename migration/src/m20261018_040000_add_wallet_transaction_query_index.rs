@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 交易紀錄查詢以交易 ID 作為游標由新到舊分頁
+/// 上層交易的查詢需要 parent_id 的索引
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE INDEX IF NOT EXISTS idx_wallet_transaction_client_user_id
+            ON wallet_transaction (client_id, user_id, id DESC);
+            CREATE INDEX IF NOT EXISTS idx_wallet_transaction_parent_id
+            ON wallet_transaction (parent_id)
+            WHERE parent_id <> 0;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_client_user_id;
+            DROP INDEX IF EXISTS idx_wallet_transaction_parent_id;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
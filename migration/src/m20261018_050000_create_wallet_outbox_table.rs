@@ -0,0 +1,92 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        self.create_table(manager).await?;
+        self.create_index(manager).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WalletOutbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WalletOutbox {
+    Table,
+    Id,
+    ClientId,
+    UserId,
+    AggregateId,
+    EventType,
+    EventVersion,
+    Payload,
+    Status,
+    RetryCount,
+    CreateAt,
+    PublishAt,
+}
+
+impl Migration {
+    async fn create_table(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletOutbox::Table)
+                    .if_not_exists()
+                    .col(big_integer(WalletOutbox::Id).primary_key())
+                    .col(big_integer(WalletOutbox::ClientId).not_null())
+                    .col(big_integer(WalletOutbox::UserId).not_null())
+                    .col(big_integer(WalletOutbox::AggregateId).not_null())
+                    .col(string(WalletOutbox::EventType).not_null())
+                    .col(integer(WalletOutbox::EventVersion).not_null())
+                    .col(text(WalletOutbox::Payload).not_null())
+                    .col(integer(WalletOutbox::Status).not_null())
+                    .col(integer(WalletOutbox::RetryCount).not_null().default(0))
+                    .col(
+                        timestamp(WalletOutbox::CreateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_null(WalletOutbox::PublishAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        let comment = r#"
+            COMMENT ON TABLE wallet_outbox IS '錢包事件發送箱';
+            COMMENT ON COLUMN wallet_outbox.id IS 'ID 同時作為事件ID';
+            COMMENT ON COLUMN wallet_outbox.client_id IS '用戶的client ID';
+            COMMENT ON COLUMN wallet_outbox.user_id IS '用戶ID';
+            COMMENT ON COLUMN wallet_outbox.aggregate_id IS '事件對應的資料ID';
+            COMMENT ON COLUMN wallet_outbox.event_type IS '事件類型';
+            COMMENT ON COLUMN wallet_outbox.event_version IS '事件版本';
+            COMMENT ON COLUMN wallet_outbox.payload IS '事件內容(JSON)';
+            COMMENT ON COLUMN wallet_outbox.status IS '狀態 0:待發送 1:已發送';
+            COMMENT ON COLUMN wallet_outbox.retry_count IS '發送失敗次數';
+            COMMENT ON COLUMN wallet_outbox.create_at IS '建立時間';
+            COMMENT ON COLUMN wallet_outbox.publish_at IS '發送時間';
+        "#;
+
+        manager.get_connection().execute_unprepared(comment).await?;
+        Ok(())
+    }
+
+    async fn create_index(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        // 只有待發送的事件需要被查詢
+        let sql = r#"
+            CREATE INDEX IF NOT EXISTS idx_wallet_outbox_pending
+            ON wallet_outbox (id)
+            WHERE status = 0;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
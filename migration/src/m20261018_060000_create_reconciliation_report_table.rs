@@ -0,0 +1,106 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        self.create_table(manager).await?;
+        self.create_index(manager).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReconciliationReport::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReconciliationReport {
+    Table,
+    Id,
+    ClientId,
+    UserId,
+    CurrencyId,
+    WalletSourceId,
+    WalletAmount,
+    LedgerAmount,
+    DriftAmount,
+    BrokenChainCount,
+    FirstBrokenTxnId,
+    Status,
+    AdjustmentTxnId,
+    ApproveBy,
+    CreateAt,
+    UpdateAt,
+}
+
+impl Migration {
+    async fn create_table(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReconciliationReport::Table)
+                    .if_not_exists()
+                    .col(big_integer(ReconciliationReport::Id).primary_key())
+                    .col(big_integer(ReconciliationReport::ClientId).not_null())
+                    .col(big_integer(ReconciliationReport::UserId).not_null())
+                    .col(big_integer(ReconciliationReport::CurrencyId).not_null())
+                    .col(big_integer(ReconciliationReport::WalletSourceId).not_null())
+                    .col(decimal(ReconciliationReport::WalletAmount).not_null())
+                    .col(decimal(ReconciliationReport::LedgerAmount).not_null())
+                    .col(decimal(ReconciliationReport::DriftAmount).not_null())
+                    .col(integer(ReconciliationReport::BrokenChainCount).not_null())
+                    .col(big_integer_null(ReconciliationReport::FirstBrokenTxnId))
+                    .col(integer(ReconciliationReport::Status).not_null())
+                    .col(big_integer_null(ReconciliationReport::AdjustmentTxnId))
+                    .col(big_integer_null(ReconciliationReport::ApproveBy))
+                    .col(
+                        timestamp(ReconciliationReport::CreateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(ReconciliationReport::UpdateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let comment = r#"
+            COMMENT ON TABLE reconciliation_report IS '錢包對帳報告';
+            COMMENT ON COLUMN reconciliation_report.id IS 'ID';
+            COMMENT ON COLUMN reconciliation_report.client_id IS '用戶的client ID';
+            COMMENT ON COLUMN reconciliation_report.user_id IS '用戶ID';
+            COMMENT ON COLUMN reconciliation_report.currency_id IS '幣別ID';
+            COMMENT ON COLUMN reconciliation_report.wallet_source_id IS '錢包來源ID';
+            COMMENT ON COLUMN reconciliation_report.wallet_amount IS '對帳時的錢包金額';
+            COMMENT ON COLUMN reconciliation_report.ledger_amount IS '對帳時依交易紀錄計算的金額';
+            COMMENT ON COLUMN reconciliation_report.drift_amount IS '差額 錢包金額 - 交易紀錄金額';
+            COMMENT ON COLUMN reconciliation_report.broken_chain_count IS '前後金額不連續的交易紀錄數量';
+            COMMENT ON COLUMN reconciliation_report.first_broken_txn_id IS '第一筆前後金額不連續的交易紀錄ID';
+            COMMENT ON COLUMN reconciliation_report.status IS '狀態 0:待審核 1:已調整';
+            COMMENT ON COLUMN reconciliation_report.adjustment_txn_id IS '調整的交易紀錄ID';
+            COMMENT ON COLUMN reconciliation_report.approve_by IS '審核者ID';
+            COMMENT ON COLUMN reconciliation_report.create_at IS '建立時間';
+            COMMENT ON COLUMN reconciliation_report.update_at IS '更新時間';
+        "#;
+
+        manager.get_connection().execute_unprepared(comment).await?;
+        Ok(())
+    }
+
+    async fn create_index(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE INDEX IF NOT EXISTS idx_reconciliation_report_client_id
+            ON reconciliation_report (client_id, id DESC);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        self.create_wallet_limit_table(manager).await?;
+        self.create_wallet_action_limit_table(manager).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WalletActionLimit::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WalletLimit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WalletLimit {
+    Table,
+    Id,
+    ClientId,
+    CurrencyId,
+    MaxBalance,
+    DailyWithdrawLimit,
+    WeeklyWithdrawLimit,
+    CreateAt,
+    UpdateAt,
+}
+
+#[derive(DeriveIden)]
+enum WalletActionLimit {
+    Table,
+    Id,
+    ClientId,
+    CurrencyId,
+    Action,
+    MinAmount,
+    MaxAmount,
+    CreateAt,
+    UpdateAt,
+}
+
+impl Migration {
+    async fn create_wallet_limit_table(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletLimit::Table)
+                    .if_not_exists()
+                    .col(big_integer(WalletLimit::Id).auto_increment().primary_key())
+                    .col(big_integer(WalletLimit::ClientId).not_null())
+                    .col(big_integer(WalletLimit::CurrencyId).not_null())
+                    .col(decimal_null(WalletLimit::MaxBalance))
+                    .col(decimal_null(WalletLimit::DailyWithdrawLimit))
+                    .col(decimal_null(WalletLimit::WeeklyWithdrawLimit))
+                    .col(
+                        timestamp(WalletLimit::CreateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(WalletLimit::UpdateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS uidx_wallet_limit_client_currency
+            ON wallet_limit (client_id, currency_id);
+
+            COMMENT ON TABLE wallet_limit IS '錢包限額 每個client 每個幣別一筆';
+            COMMENT ON COLUMN wallet_limit.id IS 'ID';
+            COMMENT ON COLUMN wallet_limit.client_id IS '用戶的client ID';
+            COMMENT ON COLUMN wallet_limit.currency_id IS '幣別ID';
+            COMMENT ON COLUMN wallet_limit.max_balance IS '錢包餘額上限 NULL 表示不限制';
+            COMMENT ON COLUMN wallet_limit.daily_withdraw_limit IS '最近24小時出金總額上限 NULL 表示不限制';
+            COMMENT ON COLUMN wallet_limit.weekly_withdraw_limit IS '最近7天出金總額上限 NULL 表示不限制';
+            COMMENT ON COLUMN wallet_limit.create_at IS '建立時間';
+            COMMENT ON COLUMN wallet_limit.update_at IS '更新時間';
+        "#;
+
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn create_wallet_action_limit_table(
+        &self,
+        manager: &SchemaManager<'_>,
+    ) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletActionLimit::Table)
+                    .if_not_exists()
+                    .col(
+                        big_integer(WalletActionLimit::Id)
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(big_integer(WalletActionLimit::ClientId).not_null())
+                    .col(big_integer(WalletActionLimit::CurrencyId).not_null())
+                    .col(integer(WalletActionLimit::Action).not_null())
+                    .col(decimal_null(WalletActionLimit::MinAmount))
+                    .col(decimal_null(WalletActionLimit::MaxAmount))
+                    .col(
+                        timestamp(WalletActionLimit::CreateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(WalletActionLimit::UpdateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS uidx_wallet_action_limit_client_currency_action
+            ON wallet_action_limit (client_id, currency_id, action);
+
+            COMMENT ON TABLE wallet_action_limit IS '單筆交易限額 每個client 每個幣別 每種錢包操作一筆';
+            COMMENT ON COLUMN wallet_action_limit.id IS 'ID';
+            COMMENT ON COLUMN wallet_action_limit.client_id IS '用戶的client ID';
+            COMMENT ON COLUMN wallet_action_limit.currency_id IS '幣別ID';
+            COMMENT ON COLUMN wallet_action_limit.action IS '交易行為 1:遊戲存款 2:遊戲提款 3:支付存款 4:支付提款 5:支付提款退回 6:轉出 7:轉入';
+            COMMENT ON COLUMN wallet_action_limit.min_amount IS '單筆金額下限 NULL 表示不限制';
+            COMMENT ON COLUMN wallet_action_limit.max_amount IS '單筆金額上限 NULL 表示不限制';
+            COMMENT ON COLUMN wallet_action_limit.create_at IS '建立時間';
+            COMMENT ON COLUMN wallet_action_limit.update_at IS '更新時間';
+        "#;
+
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
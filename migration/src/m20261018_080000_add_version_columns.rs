@@ -0,0 +1,69 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 錢包與流水主表新增版本號 樂觀鎖模式下更新時比對版本號 避免覆蓋其他交易的修改
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWallet::Table)
+                    .add_column_if_not_exists(
+                        big_integer(UserWallet::Version).not_null().default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RolloverMain::Table)
+                    .add_column_if_not_exists(
+                        big_integer(RolloverMain::Version).not_null().default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let comment = r#"
+            COMMENT ON COLUMN user_wallet.version IS '版本號 每次更新加一';
+            COMMENT ON COLUMN rollover_main.version IS '版本號 每次更新加一';
+        "#;
+        manager.get_connection().execute_unprepared(comment).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RolloverMain::Table)
+                    .drop_column(RolloverMain::Version)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWallet::Table)
+                    .drop_column(UserWallet::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserWallet {
+    Table,
+    Version,
+}
+
+#[derive(DeriveIden)]
+enum RolloverMain {
+    Table,
+    Version,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 出入金交易記錄金流訂單的外部參考 同一個 client 的金流訂單 ID 只能出現一次
+/// 只有出入金的原始交易有金流訂單參考 退回與回滾產生的交易為 NULL 不受此限制
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletTransaction::Table)
+                    .add_column_if_not_exists(string_null(WalletTransaction::PaymentOrderId))
+                    .add_column_if_not_exists(string_null(WalletTransaction::PaymentChannel))
+                    .add_column_if_not_exists(string_null(WalletTransaction::PaymentOperator))
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_payment_order_unique
+            ON wallet_transaction (client_id, payment_order_id)
+            WHERE payment_order_id IS NOT NULL;
+
+            COMMENT ON COLUMN wallet_transaction.payment_order_id IS '金流訂單 ID';
+            COMMENT ON COLUMN wallet_transaction.payment_channel IS '金流渠道';
+            COMMENT ON COLUMN wallet_transaction.payment_operator IS '操作人員';
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_payment_order_unique;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletTransaction::Table)
+                    .drop_column(WalletTransaction::PaymentOrderId)
+                    .drop_column(WalletTransaction::PaymentChannel)
+                    .drop_column(WalletTransaction::PaymentOperator)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WalletTransaction {
+    Table,
+    PaymentOrderId,
+    PaymentChannel,
+    PaymentOperator,
+}
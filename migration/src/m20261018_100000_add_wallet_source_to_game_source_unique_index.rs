@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 合併錢包模式下 同一筆遊戲交易會分別從本金與獎金錢包扣款 每個錢包各有一筆原始交易
+/// 遊戲原始交易的唯一索引加入錢包來源 同一個錢包的來源交易 ID 與動作仍只能出現一次
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, wallet_source_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        self.create_table(manager).await?;
+        self.create_index(manager).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BonusGrant::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BonusGrant {
+    Table,
+    Id,
+    ClientId,
+    UserId,
+    CurrencyId,
+    WalletTxnId,
+    Amount,
+    ExpireAt,
+    Status,
+    ForfeitAmount,
+    ForfeitTxnId,
+    CreateAt,
+    UpdateAt,
+}
+
+impl Migration {
+    async fn create_table(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BonusGrant::Table)
+                    .if_not_exists()
+                    .col(big_integer(BonusGrant::Id).primary_key())
+                    .col(big_integer(BonusGrant::ClientId).not_null())
+                    .col(big_integer(BonusGrant::UserId).not_null())
+                    .col(big_integer(BonusGrant::CurrencyId).not_null())
+                    .col(big_integer(BonusGrant::WalletTxnId).not_null())
+                    .col(decimal(BonusGrant::Amount).not_null())
+                    .col(timestamp_null(BonusGrant::ExpireAt))
+                    .col(integer(BonusGrant::Status).not_null())
+                    .col(decimal_null(BonusGrant::ForfeitAmount))
+                    .col(big_integer_null(BonusGrant::ForfeitTxnId))
+                    .col(
+                        timestamp(BonusGrant::CreateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(BonusGrant::UpdateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let comment = r#"
+            COMMENT ON TABLE bonus_grant IS '獎金發放紀錄';
+            COMMENT ON COLUMN bonus_grant.id IS 'ID';
+            COMMENT ON COLUMN bonus_grant.client_id IS '用戶的client ID';
+            COMMENT ON COLUMN bonus_grant.user_id IS '用戶ID';
+            COMMENT ON COLUMN bonus_grant.currency_id IS '幣別ID';
+            COMMENT ON COLUMN bonus_grant.wallet_txn_id IS '發放獎金的交易紀錄ID';
+            COMMENT ON COLUMN bonus_grant.amount IS '發放金額';
+            COMMENT ON COLUMN bonus_grant.expire_at IS '到期時間 NULL表示不會到期';
+            COMMENT ON COLUMN bonus_grant.status IS '狀態 0:有效 1:已轉入本金錢包 2:到期沒收';
+            COMMENT ON COLUMN bonus_grant.forfeit_amount IS '到期沒收的金額';
+            COMMENT ON COLUMN bonus_grant.forfeit_txn_id IS '到期沒收的交易紀錄ID';
+            COMMENT ON COLUMN bonus_grant.create_at IS '建立時間';
+            COMMENT ON COLUMN bonus_grant.update_at IS '更新時間';
+        "#;
+
+        manager.get_connection().execute_unprepared(comment).await?;
+        Ok(())
+    }
+
+    async fn create_index(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        // 背景工作只查詢有效且會到期的獎金
+        let sql = r#"
+            CREATE INDEX IF NOT EXISTS idx_bonus_grant_expire_at
+            ON bonus_grant (status, expire_at)
+            WHERE expire_at IS NOT NULL;
+
+            CREATE INDEX IF NOT EXISTS idx_bonus_grant_user
+            ON bonus_grant (client_id, user_id, currency_id, status);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
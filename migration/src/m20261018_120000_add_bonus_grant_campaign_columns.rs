@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 獎金發放紀錄活動 ID 流水倍率 以及對應的流水紀錄
+/// 由入金發放的獎金沒有活動 ID 為 NULL
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BonusGrant::Table)
+                    .add_column_if_not_exists(string_null(BonusGrant::CampaignId))
+                    .add_column_if_not_exists(
+                        decimal(BonusGrant::RolloverRate).not_null().default(0),
+                    )
+                    .add_column_if_not_exists(big_integer_null(BonusGrant::RolloverRecordId))
+                    .add_column_if_not_exists(
+                        big_integer(BonusGrant::CreateBy).not_null().default(0),
+                    )
+                    .add_column_if_not_exists(big_integer_null(BonusGrant::CancelTxnId))
+                    .add_column_if_not_exists(big_integer_null(BonusGrant::CancelBy))
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            CREATE INDEX IF NOT EXISTS idx_bonus_grant_campaign_id
+            ON bonus_grant (client_id, campaign_id)
+            WHERE campaign_id IS NOT NULL;
+
+            COMMENT ON COLUMN bonus_grant.campaign_id IS '活動 ID';
+            COMMENT ON COLUMN bonus_grant.rollover_rate IS '流水倍率';
+            COMMENT ON COLUMN bonus_grant.rollover_record_id IS '發放獎金的流水紀錄ID';
+            COMMENT ON COLUMN bonus_grant.create_by IS '發放者ID';
+            COMMENT ON COLUMN bonus_grant.cancel_txn_id IS '取消獎金的交易紀錄ID';
+            COMMENT ON COLUMN bonus_grant.cancel_by IS '取消者ID';
+            COMMENT ON COLUMN bonus_grant.status IS '狀態 0:有效 1:已轉入本金錢包 2:到期沒收 3:已取消';
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_bonus_grant_campaign_id;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BonusGrant::Table)
+                    .drop_column(BonusGrant::CampaignId)
+                    .drop_column(BonusGrant::RolloverRate)
+                    .drop_column(BonusGrant::RolloverRecordId)
+                    .drop_column(BonusGrant::CreateBy)
+                    .drop_column(BonusGrant::CancelTxnId)
+                    .drop_column(BonusGrant::CancelBy)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BonusGrant {
+    Table,
+    CampaignId,
+    RolloverRate,
+    RolloverRecordId,
+    CreateBy,
+    CancelTxnId,
+    CancelBy,
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 遊戲上分計算達成流水時 依照遊戲類別或遊戲的貢獻比例計算
+/// 流水紀錄保存計算當下的貢獻比例 既有的紀錄為 1
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RolloverContribution::Table)
+                    .if_not_exists()
+                    .col(
+                        big_integer(RolloverContribution::Id)
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(big_integer(RolloverContribution::ClientId).not_null())
+                    .col(big_integer(RolloverContribution::WalletSourceId).not_null())
+                    .col(string(RolloverContribution::GameCategory).not_null())
+                    .col(string(RolloverContribution::GameId).not_null().default(""))
+                    .col(decimal(RolloverContribution::Weight).not_null())
+                    .col(
+                        timestamp(RolloverContribution::CreateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(RolloverContribution::UpdateAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RolloverRecord::Table)
+                    .add_column_if_not_exists(
+                        decimal(RolloverRecord::ContributionWeight)
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS uidx_rollover_contribution_game
+            ON rollover_contribution (client_id, wallet_source_id, game_category, game_id);
+
+            ALTER TABLE rollover_contribution
+            ADD CONSTRAINT chk_rollover_contribution_weight CHECK (weight >= 0 AND weight <= 1);
+
+            COMMENT ON TABLE rollover_contribution IS '遊戲流水貢獻比例 每個client 每個錢包來源 每個遊戲類別或遊戲一筆';
+            COMMENT ON COLUMN rollover_contribution.id IS 'ID';
+            COMMENT ON COLUMN rollover_contribution.client_id IS '用戶的client ID';
+            COMMENT ON COLUMN rollover_contribution.wallet_source_id IS '錢包來源ID';
+            COMMENT ON COLUMN rollover_contribution.game_category IS '遊戲類別';
+            COMMENT ON COLUMN rollover_contribution.game_id IS '遊戲ID 空字串表示整個遊戲類別';
+            COMMENT ON COLUMN rollover_contribution.weight IS '貢獻比例 1 表示 100% 0 表示不計入流水';
+            COMMENT ON COLUMN rollover_contribution.create_at IS '建立時間';
+            COMMENT ON COLUMN rollover_contribution.update_at IS '更新時間';
+            COMMENT ON COLUMN rollover_record.contribution_weight IS '計算當下的遊戲流水貢獻比例';
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RolloverRecord::Table)
+                    .drop_column(RolloverRecord::ContributionWeight)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RolloverContribution::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RolloverContribution {
+    Table,
+    Id,
+    ClientId,
+    WalletSourceId,
+    GameCategory,
+    GameId,
+    Weight,
+    CreateAt,
+    UpdateAt,
+}
+
+#[derive(DeriveIden)]
+enum RolloverRecord {
+    Table,
+    ContributionWeight,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 凍結單號另外存放 不再使用來源交易 ID 避免與其他出金的來源交易 ID 衝突
+/// 同一個玩家的凍結單號只能出現一次 非凍結產生的交易為 NULL 不受此限制
+///
+/// 既有的凍結紀錄以來源交易 ID 作為凍結單號 凍結中與已取消的出金一定是凍結產生的
+/// 已確認的凍結在確認時才更新 update_at 與建立時間不同 一般出金的兩個時間相同
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletTransaction::Table)
+                    .add_column_if_not_exists(big_integer_null(WalletTransaction::HoldId))
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            UPDATE wallet_transaction
+            SET hold_id = transaction_source_id
+            WHERE parent_id = 0
+                AND action = 4
+                AND payment_order_id IS NULL
+                AND (status <> 1 OR update_at <> create_at);
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_hold_unique
+            ON wallet_transaction (client_id, user_id, hold_id)
+            WHERE hold_id IS NOT NULL;
+
+            COMMENT ON COLUMN wallet_transaction.hold_id IS '凍結單號';
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_hold_unique;
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletTransaction::Table)
+                    .drop_column(WalletTransaction::HoldId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WalletTransaction {
+    Table,
+    HoldId,
+}
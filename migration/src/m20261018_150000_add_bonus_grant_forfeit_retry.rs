@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 獎金沒收失敗時紀錄失敗次數與下次重試的時間
+/// 沒收持續失敗的獎金延後處理 不會一直佔用每批的名額 讓其他到期的獎金無法沒收
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BonusGrant::Table)
+                    .add_column_if_not_exists(
+                        integer(BonusGrant::ForfeitRetryCount).not_null().default(0),
+                    )
+                    .add_column_if_not_exists(timestamp_null(BonusGrant::NextForfeitAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            COMMENT ON COLUMN bonus_grant.forfeit_retry_count IS '沒收失敗次數';
+            COMMENT ON COLUMN bonus_grant.next_forfeit_at IS '沒收失敗後下次重試的時間';
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BonusGrant::Table)
+                    .drop_column(BonusGrant::ForfeitRetryCount)
+                    .drop_column(BonusGrant::NextForfeitAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BonusGrant {
+    Table,
+    ForfeitRetryCount,
+    NextForfeitAt,
+}
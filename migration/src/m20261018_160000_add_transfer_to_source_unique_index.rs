@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 轉帳使用上游傳入的交易單號作為來源交易 ID 重送的請求不能再轉帳一次
+/// 原始交易的唯一索引加入轉出與轉入 轉入的 parent_id 指向轉出 實際上只限制轉出
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, wallet_source_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2, 6, 7);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, wallet_source_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
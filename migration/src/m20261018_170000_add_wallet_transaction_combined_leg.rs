@@ -0,0 +1,84 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 合併錢包的遊戲交易會分別從本金與獎金錢包扣款 每個錢包各有一筆原始交易 以 combined_leg 標記
+/// 遊戲與轉帳原始交易的唯一索引恢復為不含錢包來源 只排除合併錢包的交易
+/// 合併錢包的交易另外建立唯一索引 同一個錢包的來源交易 ID 與動作仍只能出現一次
+///
+/// 既有的合併錢包交易 同一個來源交易 ID 與動作在其他錢包也有原始交易
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletTransaction::Table)
+                    .add_column_if_not_exists(
+                        boolean(WalletTransaction::CombinedLeg)
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let sql = r#"
+            UPDATE wallet_transaction AS leg
+            SET combined_leg = TRUE
+            WHERE leg.parent_id = 0
+                AND leg.action IN (1, 2)
+                AND EXISTS (
+                    SELECT 1 FROM wallet_transaction AS other
+                    WHERE other.parent_id = 0
+                        AND other.client_id = leg.client_id
+                        AND other.user_id = leg.user_id
+                        AND other.transaction_source_id = leg.transaction_source_id
+                        AND other.action = leg.action
+                        AND other.wallet_source_id <> leg.wallet_source_id
+                );
+
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2, 6, 7) AND NOT combined_leg;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_combined_leg_unique
+            ON wallet_transaction (client_id, user_id, wallet_source_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2) AND combined_leg;
+
+            COMMENT ON COLUMN wallet_transaction.combined_leg IS '合併錢包的交易';
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX IF EXISTS idx_wallet_transaction_combined_leg_unique;
+            DROP INDEX IF EXISTS idx_wallet_transaction_game_source_unique;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_wallet_transaction_game_source_unique
+            ON wallet_transaction (client_id, user_id, wallet_source_id, transaction_source_id, action)
+            WHERE parent_id = 0 AND action IN (1, 2, 6, 7);
+        "#;
+        manager.get_connection().execute_unprepared(sql).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletTransaction::Table)
+                    .drop_column(WalletTransaction::CombinedLeg)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WalletTransaction {
+    Table,
+    CombinedLeg,
+}
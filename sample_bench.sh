@@ -1,7 +1,8 @@
 #!/bin/bash
 
 # Set your gRPC service details
-PROTO_FILE="/Users/jason/kgs_lib/protos/protos/wallet/player_wallet.proto"
+# 服務有開啟 reflection 不需要指定 proto 檔
+# 金流訂單 ID 不可重複 每個請求使用不同的 UUID
 SERVICE_METHOD="player_wallet.PlayerWallet.Deposit"
 SERVICE_ADDRESS="localhost:1691"
 
@@ -11,7 +12,6 @@ CONCURRENCY=40
 
 # Run the test
 ghz --insecure \
-    --proto $PROTO_FILE \
     --call $SERVICE_METHOD \
     -d '
     {
@@ -19,6 +19,9 @@ ghz --insecure \
     "amount": "100",
     "client_id": "7135148985370546176",
     "currency": "USD",
+    "payment_order_id": "bench-{{.UUID}}",
+    "payment_channel": "bench",
+    "operator": "bench",
     "player_id": "7188781879867346945",
     "rollover_rate": "1",
     "transaction_id": "1",
@@ -3,6 +3,8 @@ use std::fmt::Debug;
 use bigdecimal::BigDecimal;
 use kgs_err::models::status::Status as KgsStatus;
 
+use crate::domain;
+
 pub trait GetWalletInfoTrait: Sync + Send + Debug {
     fn get_user_id(&self) -> i64;
     fn get_client_id(&self) -> i64;
@@ -28,3 +30,23 @@ pub trait GetUpdateAmount: Sync + Send + Debug {
     /// 獲取新的金額 可能有正負號
     fn get_new_amount(&self) -> Result<BigDecimal, KgsStatus>;
 }
+
+pub trait GetPaymentReference: Sync + Send + Debug {
+    /// 獲取金流訂單參考 金流訂單 ID 必填
+    fn get_payment_reference(&self) -> Result<domain::PaymentReference, KgsStatus>;
+}
+
+pub trait GetBonusExpireAt: Sync + Send + Debug {
+    /// 獲取獎金到期時間 未指定時回傳 None
+    fn get_bonus_expire_at(&self) -> Result<Option<chrono::NaiveDateTime>, KgsStatus>;
+}
+
+pub trait GetGameInfo: Sync + Send + Debug {
+    /// 獲取遊戲資訊 未提供遊戲類別時回傳 None
+    fn get_game_info(&self) -> Option<domain::GameInfo>;
+}
+
+pub trait GetBonusTerms: Sync + Send + Debug {
+    /// 獲取發放獎金的條件 活動 ID 必填
+    fn get_bonus_terms(&self) -> Result<domain::BonusTerms, KgsStatus>;
+}
@@ -81,6 +81,7 @@ impl WalletMapperTrait for WalletMapper {
             currency: user_wallet.currency_name,
             wallet_source_id: user_wallet.wallet_source_id,
             wallet_source_name: user_wallet.wallet_source_name,
+            available_amount: user_wallet.available_amount().to_string(),
             amount: user_wallet.amount.to_string(),
             requirement_rollover: rollover_main.requirement_rollover.to_string(),
             achievement_rollover: rollover_main.achievement_rollover.to_string(),
@@ -5,6 +5,7 @@ use kgs_err::models::status::Status as KgsStatus;
 use kgs_tracing::warn;
 
 use crate::application::dto::common::*;
+use crate::domain;
 
 impl GetWalletInfoTrait for protos::game_wallet::BalanceRequest {
     fn get_user_id(&self) -> i64 {
@@ -86,6 +87,19 @@ impl GetRolloverRate for protos::game_wallet::DepositRequest {
     }
 }
 
+impl GetGameInfo for protos::game_wallet::DepositRequest {
+    fn get_game_info(&self) -> Option<domain::GameInfo> {
+        if self.game_category.is_empty() {
+            return None;
+        }
+
+        Some(domain::GameInfo {
+            game_category: self.game_category.clone(),
+            game_id: self.game_id.clone(),
+        })
+    }
+}
+
 impl GetWalletInfoTrait for protos::game_wallet::WithdrawRequest {
     fn get_user_id(&self) -> i64 {
         self.user_id
@@ -200,3 +214,95 @@ impl GetRolloverRate for protos::game_wallet::UpdateRequest {
         Ok(rollover_rate)
     }
 }
+
+impl GetGameInfo for protos::game_wallet::UpdateRequest {
+    fn get_game_info(&self) -> Option<domain::GameInfo> {
+        if self.game_category.is_empty() {
+            return None;
+        }
+
+        Some(domain::GameInfo {
+            game_category: self.game_category.clone(),
+            game_id: self.game_id.clone(),
+        })
+    }
+}
+
+/// 批次結算的錢包資訊 玩家在每個項目中 這裡的 user_id 不使用
+impl GetWalletInfoTrait for protos::game_wallet::BatchSettleRequest {
+    fn get_user_id(&self) -> i64 {
+        0
+    }
+    fn get_client_id(&self) -> i64 {
+        self.client_id
+    }
+    fn get_currency_name(&self) -> &str {
+        &self.currency
+    }
+    fn get_wallet_source_id(&self) -> i64 {
+        self.wallet_source
+    }
+}
+
+impl GetAmountTrait for protos::game_wallet::BatchSettleItem {
+    fn get_amount(&self) -> Result<BigDecimal, KgsStatus> {
+        // Parse amount
+        let amount = BigDecimal::from_str(&self.amount).map_err(|e| {
+            warn!("轉換金額失敗: {}", e);
+            KgsStatus::InvalidArgument
+        })?;
+
+        // Check amount is greater than 0
+        if amount <= BigDecimal::zero() {
+            warn!("金額 小於等於0");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(amount)
+    }
+}
+
+impl GetEffectiveBet for protos::game_wallet::BatchSettleItem {
+    fn get_effective_bet(&self) -> Result<BigDecimal, KgsStatus> {
+        let effective_bet = BigDecimal::from_str(&self.effective_bet).map_err(|e| {
+            warn!("轉換有效投注失敗: {}", e);
+            KgsStatus::InvalidArgument
+        })?;
+
+        if effective_bet <= BigDecimal::zero() {
+            warn!("有效投注 小於等於0");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(effective_bet)
+    }
+}
+
+impl GetRolloverRate for protos::game_wallet::BatchSettleItem {
+    fn get_rollover_rate(&self) -> Result<BigDecimal, KgsStatus> {
+        let rollover_rate = BigDecimal::from_str(&self.rollover_rate).map_err(|e| {
+            warn!("轉換流水倍率失敗: {}", e);
+            KgsStatus::InvalidArgument
+        })?;
+
+        if rollover_rate <= BigDecimal::zero() {
+            warn!("流水倍率 小於等於0");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(rollover_rate)
+    }
+}
+
+impl GetGameInfo for protos::game_wallet::BatchSettleItem {
+    fn get_game_info(&self) -> Option<domain::GameInfo> {
+        if self.game_category.is_empty() {
+            return None;
+        }
+
+        Some(domain::GameInfo {
+            game_category: self.game_category.clone(),
+            game_id: self.game_id.clone(),
+        })
+    }
+}
@@ -1,5 +1,6 @@
 mod common;
 mod game_wallet;
+mod reconciliation;
 mod user_wallet;
 
 pub use common::*;
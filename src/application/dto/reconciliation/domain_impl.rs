@@ -0,0 +1,37 @@
+use crate::application::dto::common::ToProtoTrait;
+
+use crate::domain::*;
+
+impl ToProtoTrait for ReconciliationReport {
+    type ProtoType = protos::reconciliation::ReconciliationReportModel;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            id: self.id,
+            client_id: self.client_id,
+            player_id: self.user_id,
+            currency_id: self.currency_id,
+            wallet_source_id: self.wallet_source_id,
+            wallet_amount: self.wallet_amount.to_string(),
+            ledger_amount: self.ledger_amount.to_string(),
+            drift_amount: self.drift_amount.to_string(),
+            broken_chain_count: self.broken_chain_count,
+            first_broken_txn_id: self.first_broken_txn_id,
+            status: self.status,
+            adjustment_txn_id: self.adjustment_txn_id,
+            approve_by: self.approve_by,
+            create_at: self.create_at.and_utc().timestamp_millis(),
+            update_at: self.update_at.and_utc().timestamp_millis(),
+        }
+    }
+}
+
+impl ToProtoTrait for Vec<ReconciliationReport> {
+    type ProtoType = protos::reconciliation::ReconcileResponse;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            report_list: self.into_iter().map(|report| report.to_proto()).collect(),
+        }
+    }
+}
@@ -0,0 +1 @@
+mod domain_impl;
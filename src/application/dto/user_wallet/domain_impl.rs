@@ -12,6 +12,7 @@ impl ToProtoTrait for UserWalletWithRollover {
             currency: self.currency_name,
             wallet_source_id: self.wallet_source_id,
             wallet_source_name: self.wallet_source_name,
+            available_amount: (&self.amount - &self.hold_amount).to_string(),
             amount: self.amount.to_string(),
             requirement_rollover: self.requirement_rollover.to_string(),
             achievement_rollover: self.achievement_rollover.to_string(),
@@ -31,3 +32,106 @@ impl ToProtoTrait for Vec<UserWalletWithRollover> {
         }
     }
 }
+
+impl ToProtoTrait for WalletTransactionWithParents {
+    type ProtoType = protos::player_wallet::WalletTransactionModel;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            id: self.wallet_txn.id,
+            parent_id: self.wallet_txn.parent_id,
+            client_id: self.wallet_txn.client_id,
+            player_id: self.wallet_txn.user_id,
+            currency_id: self.wallet_txn.currency_id,
+            wallet_source_id: self.wallet_txn.wallet_source_id,
+            action: self.wallet_txn.action,
+            transaction_source_id: self.wallet_txn.transaction_source_id,
+            before_amount: self.wallet_txn.before_amount.to_string(),
+            change_amount: self.wallet_txn.change_amount.to_string(),
+            after_amount: self.wallet_txn.after_amount.to_string(),
+            status: self.wallet_txn.status,
+            create_at: self.wallet_txn.create_at.and_utc().timestamp_millis(),
+            update_at: self.wallet_txn.update_at.and_utc().timestamp_millis(),
+            parent_ids: self.parent_ids,
+        }
+    }
+}
+
+impl ToProtoTrait for WalletTransactionPage {
+    type ProtoType = protos::player_wallet::GetTransactionListResponse;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            transaction_list: self
+                .wallet_txn_list
+                .into_iter()
+                .map(|wallet_txn| wallet_txn.to_proto())
+                .collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl ToProtoTrait for RolloverRecordWithTransaction {
+    type ProtoType = protos::player_wallet::RolloverRecordModel;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            id: self.rollover_record.id,
+            main_id: self.rollover_record.main_id,
+            client_id: self.rollover_record.client_id,
+            player_id: self.rollover_record.user_id,
+            requirement_rollover: self.rollover_record.requirement_rollover.to_string(),
+            requirement_rollover_rate: self.rollover_record.requirement_rollover_rate.to_string(),
+            achievement_rollover: self.rollover_record.achievement_rollover.to_string(),
+            achievement_rollover_rate: self.rollover_record.achievement_rollover_rate.to_string(),
+            create_by: self.rollover_record.create_by,
+            create_at: self.rollover_record.create_at.and_utc().timestamp_millis(),
+            wallet_txn_id: self.rollover_record.wallet_txn_id,
+            wallet_txn_action: self.wallet_txn_action,
+            wallet_txn_status: self.wallet_txn_status,
+            transaction_source_id: self.transaction_source_id,
+            wallet_txn_change_amount: self
+                .wallet_txn_change_amount
+                .map(|amount| amount.to_string()),
+            total_requirement_rollover: self.total_requirement_rollover.to_string(),
+            total_achievement_rollover: self.total_achievement_rollover.to_string(),
+        }
+    }
+}
+
+impl ToProtoTrait for Vec<RolloverRecordWithTransaction> {
+    type ProtoType = protos::player_wallet::GetRolloverRecordListResponse;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            record_list: self.into_iter().map(|record| record.to_proto()).collect(),
+        }
+    }
+}
+
+impl ToProtoTrait for BonusGrant {
+    type ProtoType = protos::player_wallet::BonusGrantModel;
+
+    fn to_proto(self) -> Self::ProtoType {
+        Self::ProtoType {
+            id: self.id,
+            client_id: self.client_id,
+            player_id: self.user_id,
+            currency_id: self.currency_id,
+            campaign_id: self.campaign_id.unwrap_or_default(),
+            wallet_txn_id: self.wallet_txn_id,
+            amount: self.amount.to_string(),
+            rollover_rate: self.rollover_rate.to_string(),
+            expire_at: self
+                .expire_at
+                .map(|expire_at| expire_at.and_utc().timestamp_millis()),
+            status: self.status,
+            cancel_txn_id: self.cancel_txn_id,
+            create_by: self.create_by,
+            cancel_by: self.cancel_by,
+            create_at: self.create_at.and_utc().timestamp_millis(),
+            update_at: self.update_at.and_utc().timestamp_millis(),
+        }
+    }
+}
@@ -5,6 +5,7 @@ use kgs_err::models::status::Status as KgsStatus;
 use kgs_tracing::warn;
 
 use crate::application::dto::common::*;
+use crate::domain;
 
 impl GetWalletInfoTrait for protos::player_wallet::PlayerWalletRequest {
     fn get_user_id(&self) -> i64 {
@@ -74,3 +75,185 @@ impl GetRolloverRate for protos::player_wallet::PlayerWalletChangeRequest {
         }
     }
 }
+
+impl GetPaymentReference for protos::player_wallet::PlayerWalletChangeRequest {
+    fn get_payment_reference(&self) -> Result<domain::PaymentReference, KgsStatus> {
+        if self.payment_order_id.is_empty() {
+            warn!("金流訂單 ID 不可為空");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(domain::PaymentReference {
+            order_id: self.payment_order_id.clone(),
+            channel: self.payment_channel.clone(),
+            operator: self.operator.clone(),
+        })
+    }
+}
+
+impl GetBonusExpireAt for protos::player_wallet::PlayerWalletChangeRequest {
+    fn get_bonus_expire_at(&self) -> Result<Option<chrono::NaiveDateTime>, KgsStatus> {
+        // unix timestamp 毫秒
+        self.bonus_expire_at
+            .map(|expire_at| {
+                chrono::DateTime::from_timestamp_millis(expire_at)
+                    .map(|expire_at| expire_at.naive_utc())
+                    .ok_or_else(|| {
+                        warn!("獎金到期時間格式錯誤: {}", expire_at);
+                        KgsStatus::InvalidArgument
+                    })
+            })
+            .transpose()
+    }
+}
+
+/// 轉帳請求的錢包資訊為轉出錢包
+impl GetWalletInfoTrait for protos::player_wallet::PlayerTransferRequest {
+    fn get_user_id(&self) -> i64 {
+        self.player_id
+    }
+    fn get_client_id(&self) -> i64 {
+        self.client_id
+    }
+    fn get_currency_name(&self) -> &str {
+        &self.currency
+    }
+    fn get_wallet_source_id(&self) -> i64 {
+        self.from_wallet_source_id
+    }
+}
+
+impl GetAmountTrait for protos::player_wallet::PlayerTransferRequest {
+    fn get_amount(&self) -> Result<BigDecimal, KgsStatus> {
+        // Parse amount
+        let amount = BigDecimal::from_str(&self.amount).map_err(|e| {
+            warn!("轉換金額失敗: {}", e);
+            KgsStatus::InvalidArgument
+        })?;
+
+        // Check amount is greater than 0
+        if amount <= BigDecimal::zero() {
+            warn!("金額 小於等於0");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(amount)
+    }
+}
+
+impl GetWalletInfoTrait for protos::player_wallet::PlayerHoldRequest {
+    fn get_user_id(&self) -> i64 {
+        self.player_id
+    }
+    fn get_client_id(&self) -> i64 {
+        self.client_id
+    }
+    fn get_currency_name(&self) -> &str {
+        &self.currency
+    }
+    fn get_wallet_source_id(&self) -> i64 {
+        self.wallet_source_id
+    }
+}
+
+impl GetAmountTrait for protos::player_wallet::PlayerHoldRequest {
+    fn get_amount(&self) -> Result<BigDecimal, KgsStatus> {
+        // Parse amount
+        let amount = BigDecimal::from_str(&self.amount).map_err(|e| {
+            warn!("轉換金額失敗: {}", e);
+            KgsStatus::InvalidArgument
+        })?;
+
+        // Check amount is greater than 0
+        if amount <= BigDecimal::zero() {
+            warn!("金額 小於等於0");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(amount)
+    }
+}
+
+/// 發放獎金固定發放至獎金錢包
+impl GetWalletInfoTrait for protos::player_wallet::GrantBonusRequest {
+    fn get_user_id(&self) -> i64 {
+        self.player_id
+    }
+    fn get_client_id(&self) -> i64 {
+        self.client_id
+    }
+    fn get_currency_name(&self) -> &str {
+        &self.currency
+    }
+    fn get_wallet_source_id(&self) -> i64 {
+        crate::enums::WalletSource::Bonus.to_id()
+    }
+}
+
+impl GetAmountTrait for protos::player_wallet::GrantBonusRequest {
+    fn get_amount(&self) -> Result<BigDecimal, KgsStatus> {
+        // Parse amount
+        let amount = BigDecimal::from_str(&self.amount).map_err(|e| {
+            warn!("轉換金額失敗: {}", e);
+            KgsStatus::InvalidArgument
+        })?;
+
+        // Check amount is greater than 0
+        if amount <= BigDecimal::zero() {
+            warn!("金額 小於等於0");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        Ok(amount)
+    }
+}
+
+impl GetRolloverRate for protos::player_wallet::GrantBonusRequest {
+    fn get_rollover_rate(&self) -> Result<BigDecimal, KgsStatus> {
+        match self.rollover_rate.as_ref() {
+            Some(rollover_rate_str) => {
+                let rollover_rate = BigDecimal::from_str(rollover_rate_str).map_err(|e| {
+                    warn!("轉換流水比率失敗{}", e);
+                    KgsStatus::InvalidArgument
+                })?;
+
+                if rollover_rate <= BigDecimal::zero() {
+                    warn!("流水比率:{} 小於等於0 ", &rollover_rate);
+                    return Err(KgsStatus::InvalidArgument);
+                }
+
+                Ok(rollover_rate)
+            }
+            None => Ok(BigDecimal::zero()),
+        }
+    }
+}
+
+impl GetBonusTerms for protos::player_wallet::GrantBonusRequest {
+    fn get_bonus_terms(&self) -> Result<domain::BonusTerms, KgsStatus> {
+        if self.campaign_id.is_empty() {
+            warn!("活動 ID 不可為空");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // unix timestamp 毫秒
+        let expire_at = self
+            .expire_at
+            .map(|expire_at| {
+                chrono::DateTime::from_timestamp_millis(expire_at)
+                    .map(|expire_at| expire_at.naive_utc())
+                    .ok_or_else(|| {
+                        warn!("獎金到期時間格式錯誤: {}", expire_at);
+                        KgsStatus::InvalidArgument
+                    })
+            })
+            .transpose()?;
+
+        Ok(domain::BonusTerms {
+            campaign_id: Some(self.campaign_id.clone()),
+            rollover_rate: self.get_rollover_rate()?,
+            expire_at,
+            create_by: self.operator_id,
+        })
+    }
+}
@@ -2,8 +2,9 @@ use std::{fmt::Debug, sync::Arc};
 
 use crate::domain::*;
 
+use crate::enums;
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{tracing, warn};
 
 #[tonic::async_trait]
 pub trait QueryMapperTrait: Send + Sync + Debug {
@@ -11,6 +12,16 @@ pub trait QueryMapperTrait: Send + Sync + Debug {
         &self,
         proto: protos::player_wallet::GetPlayerWalletListRequest,
     ) -> Result<SelectWalletsQuery, KgsStatus>;
+
+    async fn to_select_wallet_transaction_query(
+        &self,
+        proto: protos::player_wallet::GetTransactionListRequest,
+    ) -> Result<SelectWalletTransactionsQuery, KgsStatus>;
+
+    async fn to_select_rollover_record_query(
+        &self,
+        proto: protos::player_wallet::GetRolloverRecordListRequest,
+    ) -> Result<SelectRolloverRecordsQuery, KgsStatus>;
 }
 
 #[derive(Debug)]
@@ -49,4 +60,84 @@ impl QueryMapperTrait for QueryMapper {
             page_size,
         })
     }
+
+    #[tracing::instrument]
+    async fn to_select_wallet_transaction_query(
+        &self,
+        proto: protos::player_wallet::GetTransactionListRequest,
+    ) -> Result<SelectWalletTransactionsQuery, KgsStatus> {
+        let page_size = proto.page_size.unwrap_or(25).clamp(1, 100) as u64;
+
+        let currency_list = self
+            .currency_service
+            .get_enable_currencies(proto.client_id, proto.currencies)
+            .await?;
+        let currency_ids = currency_list.into_iter().map(|c| c.id).collect();
+
+        // 檢查錢包操作與狀態是否存在
+        for action in proto.actions.iter() {
+            enums::WalletAction::from_i32(*action)?;
+        }
+        for status in proto.statuses.iter() {
+            enums::WalletStatus::from_i32(*status)?;
+        }
+
+        // 時間為 unix timestamp 毫秒
+        let to_naive_date_time = |timestamp: i64| {
+            chrono::DateTime::from_timestamp_millis(timestamp)
+                .map(|date_time| date_time.naive_utc())
+                .ok_or_else(|| {
+                    warn!("轉換時間失敗: {}", timestamp);
+                    KgsStatus::InvalidArgument
+                })
+        };
+        let start_time = proto.start_time.map(to_naive_date_time).transpose()?;
+        let end_time = proto.end_time.map(to_naive_date_time).transpose()?;
+
+        Ok(SelectWalletTransactionsQuery {
+            client_id: proto.client_id,
+            player_ids: proto.player_ids,
+            currency_ids,
+            wallet_source_ids: proto.wallet_sources,
+            actions: proto.actions,
+            statuses: proto.statuses,
+            start_time,
+            end_time,
+            cursor: proto.cursor,
+            page_size,
+        })
+    }
+
+    #[tracing::instrument]
+    async fn to_select_rollover_record_query(
+        &self,
+        proto: protos::player_wallet::GetRolloverRecordListRequest,
+    ) -> Result<SelectRolloverRecordsQuery, KgsStatus> {
+        let page = proto.page.unwrap_or_default().max(1) as u64;
+        let page_size = proto.page_size.unwrap_or(25).clamp(1, 100) as u64;
+
+        // 有指定流水主表 ID 時 不需要玩家 幣別 錢包來源
+        let rollover_main = match proto.main_id {
+            Some(main_id) => RolloverMainFilter::MainId(main_id),
+            None => {
+                let currency = self
+                    .currency_service
+                    .get_enable_currency(proto.client_id, &proto.currency)
+                    .await?;
+
+                RolloverMainFilter::Wallet {
+                    user_id: proto.player_id,
+                    currency_id: currency.id,
+                    wallet_source_id: proto.wallet_source_id,
+                }
+            }
+        };
+
+        Ok(SelectRolloverRecordsQuery {
+            client_id: proto.client_id,
+            rollover_main,
+            page,
+            page_size,
+        })
+    }
 }
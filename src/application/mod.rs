@@ -2,5 +2,9 @@ mod dto;
 mod service;
 
 pub use dto::*;
+pub use service::BonusExpiryService;
 pub use service::GameWalletService;
+pub use service::HealthService;
+pub use service::OutboxRelayService;
+pub use service::ReconciliationService;
 pub use service::UserWalletService;
@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use database_manager::transactional;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+use tokio::sync::watch;
+
+use crate::domain;
+
+#[derive(Debug)]
+pub struct BonusExpiryService {
+    bonus_grant_service: Arc<dyn domain::BonusGrantServiceTrait>,
+    batch_size: u64,
+}
+
+impl BonusExpiryService {
+    pub fn new(
+        bonus_grant_service: Arc<dyn domain::BonusGrantServiceTrait>,
+        batch_size: u64,
+    ) -> Self {
+        Self {
+            bonus_grant_service,
+            batch_size,
+        }
+    }
+
+    /// 定時沒收到期的獎金
+    /// ### 參數
+    /// - `interval`: Duration - 檢查間隔
+    /// - `shutdown`: watch::Receiver<bool> - 服務關閉通知
+    pub async fn run(self: Arc<Self>, interval: Duration, mut shutdown: watch::Receiver<bool>) {
+        info!("bonus expiry scheduler start, interval: {:?}", interval);
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+
+            let now = chrono::Utc::now().naive_utc();
+            let grants = match self.get_expired_list(now).await {
+                Ok(grants) => grants,
+                Err(e) => {
+                    warn!("get expired bonus grant list failed: {:?}", e);
+                    continue;
+                }
+            };
+
+            // 每筆獎金使用各自的交易 避免單一獎金失敗影響其他獎金
+            // 超過批次數量的獎金留到下次處理 沒收失敗的獎金延後重試 不佔用下一批的名額
+            for grant in grants {
+                if *shutdown.borrow() {
+                    break;
+                }
+                if let Err(e) = self.forfeit(&grant, now).await {
+                    warn!(
+                        "forfeit bonus grant failed, bonus_grant_id: {}, {:?}",
+                        grant.id, e
+                    );
+                    match self.defer_forfeit(&grant, now).await {
+                        Ok(next_forfeit_at) => info!(
+                            "defer bonus grant forfeit, bonus_grant_id: {}, next_forfeit_at: {}",
+                            grant.id, next_forfeit_at
+                        ),
+                        Err(e) => warn!(
+                            "defer bonus grant forfeit failed, bonus_grant_id: {}, {:?}",
+                            grant.id, e
+                        ),
+                    }
+                }
+            }
+        }
+
+        info!("bonus expiry scheduler stopped");
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn get_expired_list(
+        &self,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus> {
+        self.bonus_grant_service
+            .get_expired_list(now, self.batch_size)
+            .await
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn forfeit(
+        &self,
+        grant: &domain::BonusGrant,
+        now: chrono::NaiveDateTime,
+    ) -> Result<(), KgsStatus> {
+        self.bonus_grant_service.forfeit(grant, now).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn defer_forfeit(
+        &self,
+        grant: &domain::BonusGrant,
+        now: chrono::NaiveDateTime,
+    ) -> Result<chrono::NaiveDateTime, KgsStatus> {
+        self.bonus_grant_service.defer_forfeit(grant, now).await
+    }
+}
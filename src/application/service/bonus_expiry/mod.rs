@@ -0,0 +1,3 @@
+mod bonus_expiry;
+
+pub use bonus_expiry::BonusExpiryService;
@@ -0,0 +1,462 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+
+use crate::domain::*;
+use crate::enums;
+
+/// 合併錢包 遊戲端把本金與獎金錢包視為同一個錢包
+/// - 餘額為兩個錢包可用金額的總和
+/// - 下注依照扣款順序從兩個錢包扣款 每個錢包各有一筆交易紀錄
+/// - 派彩依照同一筆交易下注時各錢包扣款的比例入帳 沒有對應的下注時全部入本金錢包
+/// - 回滾每個錢包中尚未回滾的原始交易
+///
+/// 所有方法都需要在交易中呼叫
+#[derive(Debug)]
+pub struct CombinedWallet {
+    deduct_order: DeductOrder,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+    bonus_release_service: Arc<dyn BonusReleaseServiceTrait>,
+    wallet_limit_service: Arc<dyn WalletLimitServiceTrait>,
+}
+
+impl CombinedWallet {
+    pub fn new(
+        deduct_order: DeductOrder,
+        wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        rollover_service: Arc<dyn RolloverServiceTrait>,
+        bonus_release_service: Arc<dyn BonusReleaseServiceTrait>,
+        wallet_limit_service: Arc<dyn WalletLimitServiceTrait>,
+    ) -> Self {
+        Self {
+            deduct_order,
+            wallet_source_repo,
+            wallet_service,
+            rollover_service,
+            bonus_release_service,
+            wallet_limit_service,
+        }
+    }
+
+    /// 合併後的可用金額
+    #[tracing::instrument]
+    pub async fn get_balance(&self, wallet_info: &WalletInfo) -> Result<BigDecimal, KgsStatus> {
+        let wallet_infos = self.wallet_infos(wallet_info).await?;
+
+        let mut balance = BigDecimal::zero();
+        for wallet_info in wallet_infos.iter() {
+            let user_wallet = self
+                .wallet_service
+                .get_or_create_new_one(wallet_info)
+                .await?;
+            balance += user_wallet.available_amount();
+        }
+
+        Ok(balance)
+    }
+
+    /// 遊戲下注 依照扣款順序從兩個錢包扣款
+    /// ### 回傳
+    /// - `String` - 下注後合併的可用金額
+    #[tracing::instrument]
+    pub async fn withdraw(
+        &self,
+        wallet_info: &WalletInfo,
+        transaction_id: i64,
+        amount: BigDecimal,
+    ) -> Result<String, KgsStatus> {
+        // 重送的請求 回傳目前的餘額
+        let origin_legs = self
+            .get_root_legs(
+                wallet_info,
+                transaction_id,
+                &enums::WalletAction::GameWithdraw,
+            )
+            .await?;
+        if !origin_legs.is_empty() {
+            check_resend(&origin_legs, wallet_info, transaction_id, &amount)?;
+            return Ok(self.get_balance(wallet_info).await?.to_string());
+        }
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(wallet_info, &enums::WalletAction::GameWithdraw, &amount)
+            .await?;
+
+        // 鎖定兩個錢包後 檢查合併的可用金額是否足夠
+        let wallet_infos = self.wallet_infos(wallet_info).await?;
+        let user_wallets = self.lock_wallets(&wallet_infos).await?;
+        let total = user_wallets
+            .iter()
+            .fold(BigDecimal::zero(), |total, user_wallet| {
+                total + user_wallet.available_amount()
+            });
+        if total < amount {
+            return Err(KgsStatus::WalletAmountNotEnough);
+        }
+
+        // 依照扣款順序扣款 前一個錢包不足的部分由下一個錢包扣
+        let mut remaining = amount;
+        for wallet_source in self.deduct_order.wallet_sources() {
+            if remaining <= BigDecimal::zero() {
+                break;
+            }
+
+            let index = find_index(&wallet_infos, wallet_source.to_id())?;
+            let available = user_wallets[index].available_amount();
+            let leg_amount = if available < remaining {
+                available
+            } else {
+                remaining.clone()
+            };
+            if leg_amount <= BigDecimal::zero() {
+                continue;
+            }
+            remaining -= &leg_amount;
+
+            let leg_info = &wallet_infos[index];
+            let (user_wallet, wallet_txn) = self
+                .wallet_service
+                .change_combined_leg_amount(
+                    leg_info,
+                    transaction_id,
+                    leg_amount.clone(),
+                    &enums::WalletAction::GameWithdraw,
+                )
+                .await?;
+            self.rollover_service
+                .change_rollover(
+                    user_wallet.id,
+                    leg_info,
+                    wallet_txn.id,
+                    leg_amount,
+                    BigDecimal::zero(),
+                    enums::WalletAction::GameWithdraw,
+                    None,
+                    wallet_info.user_id,
+                )
+                .await?;
+        }
+
+        Ok(self.get_balance(wallet_info).await?.to_string())
+    }
+
+    /// 遊戲派彩 依照同一筆交易下注時各錢包扣款的比例入帳 有效投注也依照相同比例計算流水
+    /// 各錢包依照各自錢包來源的遊戲流水貢獻比例計入達成流水
+    /// ### 回傳
+    /// - `String` - 派彩後合併的可用金額
+    #[tracing::instrument]
+    pub async fn deposit(
+        &self,
+        wallet_info: &WalletInfo,
+        transaction_id: i64,
+        amount: BigDecimal,
+        effective_bet: BigDecimal,
+        rollover_rate: BigDecimal,
+        game: Option<&GameInfo>,
+    ) -> Result<String, KgsStatus> {
+        // 重送的請求 回傳目前的餘額
+        let origin_legs = self
+            .get_root_legs(
+                wallet_info,
+                transaction_id,
+                &enums::WalletAction::GameDeposit,
+            )
+            .await?;
+        if !origin_legs.is_empty() {
+            check_resend(&origin_legs, wallet_info, transaction_id, &amount)?;
+            return Ok(self.get_balance(wallet_info).await?.to_string());
+        }
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(wallet_info, &enums::WalletAction::GameDeposit, &amount)
+            .await?;
+
+        let wallet_infos = self.wallet_infos(wallet_info).await?;
+        self.lock_wallets(&wallet_infos).await?;
+
+        // 找出下注時各錢包扣款的金額作為分配比例 沒有對應的下注時全部入本金錢包
+        let bet_legs = self
+            .get_root_legs(
+                wallet_info,
+                transaction_id,
+                &enums::WalletAction::GameWithdraw,
+            )
+            .await?;
+        let (wallet_source_ids, weights): (Vec<i64>, Vec<BigDecimal>) = if bet_legs.is_empty() {
+            (
+                vec![enums::WalletSource::Normal.to_id()],
+                vec![BigDecimal::from(1)],
+            )
+        } else {
+            bet_legs
+                .iter()
+                .map(|leg| (leg.wallet_source_id, leg.change_amount.clone()))
+                .unzip()
+        };
+        let leg_amounts = split_by_weights(&amount, &weights);
+        let leg_effective_bets = split_by_weights(&effective_bet, &weights);
+
+        for ((wallet_source_id, leg_amount), leg_effective_bet) in wallet_source_ids
+            .into_iter()
+            .zip(leg_amounts)
+            .zip(leg_effective_bets)
+        {
+            if leg_amount <= BigDecimal::zero() {
+                continue;
+            }
+
+            let leg_info = &wallet_infos[find_index(&wallet_infos, wallet_source_id)?];
+            let (user_wallet, wallet_txn) = self
+                .wallet_service
+                .change_combined_leg_amount(
+                    leg_info,
+                    transaction_id,
+                    leg_amount,
+                    &enums::WalletAction::GameDeposit,
+                )
+                .await?;
+            self.rollover_service
+                .change_rollover(
+                    user_wallet.id,
+                    leg_info,
+                    wallet_txn.id,
+                    leg_effective_bet,
+                    rollover_rate.clone(),
+                    enums::WalletAction::GameDeposit,
+                    game,
+                    wallet_info.user_id,
+                )
+                .await?;
+        }
+
+        // 獎金錢包流水達成時轉入本金錢包
+        self.release_bonus_if_achieved(&wallet_infos, wallet_info.user_id)
+            .await?;
+
+        Ok(self.get_balance(wallet_info).await?.to_string())
+    }
+
+    /// 回滾指定來源交易在各錢包中尚未回滾的原始交易
+    /// ### 回傳
+    /// - `String` - 回滾後合併的可用金額
+    #[tracing::instrument]
+    pub async fn rollback(
+        &self,
+        wallet_info: &WalletInfo,
+        source_txn_ids: &[i64],
+    ) -> Result<String, KgsStatus> {
+        // 把所有需要rollback的交易紀錄找出來
+        // 如果有任何一筆來源交易找不到，就回傳錯誤 不進行rollback
+        let mut legs = vec![];
+        for source_txn_id in source_txn_ids {
+            let wallet_txn_list = self
+                .wallet_service
+                .get_transaction_list_by_source_id(
+                    wallet_info.client_id,
+                    wallet_info.user_id,
+                    *source_txn_id,
+                )
+                .await?;
+            let pending_legs: Vec<WalletTransaction> = wallet_txn_list
+                .iter()
+                .filter(|wallet_txn| {
+                    wallet_txn.parent_id == 0
+                        && (wallet_txn.action == enums::WalletAction::GameDeposit.to_id()
+                            || wallet_txn.action == enums::WalletAction::GameWithdraw.to_id())
+                        && !wallet_txn_list
+                            .iter()
+                            .any(|child| child.parent_id == wallet_txn.id)
+                })
+                .cloned()
+                .collect();
+            if pending_legs.is_empty() {
+                warn!("找不到需要回滾的交易 source_txn_id: {}", source_txn_id);
+                return Err(KgsStatus::DataNotFound);
+            }
+            legs.extend(pending_legs);
+        }
+
+        let wallet_infos = self.wallet_infos(wallet_info).await?;
+        self.lock_wallets(&wallet_infos).await?;
+
+        let normal_index = find_index(&wallet_infos, enums::WalletSource::Normal.to_id())?;
+        for leg in legs.iter() {
+            let leg_info = &wallet_infos[find_index(&wallet_infos, leg.wallet_source_id)?];
+
+            // 獎金錢包不足以扣回派彩時 與獎金錢包的回滾策略相同 改由本金錢包扣回
+            let rollback_info = if leg.wallet_source_id == enums::WalletSource::Bonus.to_id()
+                && leg.action == enums::WalletAction::GameDeposit.to_id()
+                && !self
+                    .wallet_service
+                    .is_wallet_amount_enough(leg_info, &leg.change_amount)
+                    .await?
+            {
+                &wallet_infos[normal_index]
+            } else {
+                leg_info
+            };
+
+            // rollback 錢包金額
+            let (user_wallet, rollback_wallet_txn) = self
+                .wallet_service
+                .rollback_transaction(rollback_info, leg)
+                .await?;
+
+            // rollback 流水
+            self.rollover_service
+                .rollback_rollover(
+                    user_wallet.id,
+                    rollback_info,
+                    rollback_wallet_txn.parent_id,
+                    rollback_wallet_txn.id,
+                    wallet_info.user_id,
+                )
+                .await?;
+        }
+
+        // 獎金錢包流水達成時轉入本金錢包
+        self.release_bonus_if_achieved(&wallet_infos, wallet_info.user_id)
+            .await?;
+
+        Ok(self.get_balance(wallet_info).await?.to_string())
+    }
+}
+
+impl CombinedWallet {
+    /// 取得本金與獎金錢包的錢包資訊 依照錢包來源 ID 排序 鎖定時依照此順序
+    async fn wallet_infos(&self, wallet_info: &WalletInfo) -> Result<Vec<WalletInfo>, KgsStatus> {
+        let mut wallet_infos = vec![];
+        for wallet_source in [enums::WalletSource::Normal, enums::WalletSource::Bonus] {
+            let wallet_source = self.wallet_source_repo.get(wallet_source.to_id()).await?;
+            wallet_infos.push(WalletInfo {
+                client_id: wallet_info.client_id,
+                user_id: wallet_info.user_id,
+                currency: wallet_info.currency.clone(),
+                wallet_source,
+            });
+        }
+
+        Ok(wallet_infos)
+    }
+
+    /// 依照錢包來源 ID 的順序鎖定錢包 與轉帳的鎖定順序一致 避免死結
+    async fn lock_wallets(
+        &self,
+        wallet_infos: &[WalletInfo],
+    ) -> Result<Vec<UserWallet>, KgsStatus> {
+        let mut user_wallets = vec![];
+        for wallet_info in wallet_infos {
+            user_wallets.push(
+                self.wallet_service
+                    .get_or_create_for_update(wallet_info)
+                    .await?,
+            );
+        }
+
+        Ok(user_wallets)
+    }
+
+    /// 取得來源交易在各錢包中的原始交易
+    async fn get_root_legs(
+        &self,
+        wallet_info: &WalletInfo,
+        transaction_id: i64,
+        action: &enums::WalletAction,
+    ) -> Result<Vec<WalletTransaction>, KgsStatus> {
+        let wallet_txn_list = self
+            .wallet_service
+            .get_transaction_list_by_source_id(
+                wallet_info.client_id,
+                wallet_info.user_id,
+                transaction_id,
+            )
+            .await?;
+
+        Ok(wallet_txn_list
+            .into_iter()
+            .filter(|wallet_txn| wallet_txn.parent_id == 0 && wallet_txn.action == action.to_id())
+            .collect())
+    }
+
+    /// 獎金錢包流水達成時轉入本金錢包
+    async fn release_bonus_if_achieved(
+        &self,
+        wallet_infos: &[WalletInfo],
+        change_by: i64,
+    ) -> Result<(), KgsStatus> {
+        let bonus_index = find_index(wallet_infos, enums::WalletSource::Bonus.to_id())?;
+        self.bonus_release_service
+            .release_if_achieved(&wallet_infos[bonus_index], change_by)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// 找出指定錢包來源的錢包資訊位置
+fn find_index(wallet_infos: &[WalletInfo], wallet_source_id: i64) -> Result<usize, KgsStatus> {
+    wallet_infos
+        .iter()
+        .position(|wallet_info| wallet_info.wallet_source.id == wallet_source_id)
+        .ok_or_else(|| {
+            warn!("合併錢包不包含錢包來源: {}", wallet_source_id);
+            KgsStatus::InvalidArgument
+        })
+}
+
+/// 重送的請求 各錢包原始交易的金額加總需要與本次請求相同
+fn check_resend(
+    origin_legs: &[WalletTransaction],
+    wallet_info: &WalletInfo,
+    transaction_id: i64,
+    amount: &BigDecimal,
+) -> Result<(), KgsStatus> {
+    let origin_amount = origin_legs
+        .iter()
+        .fold(BigDecimal::zero(), |total, leg| total + &leg.change_amount);
+
+    if origin_legs
+        .iter()
+        .any(|leg| leg.currency_id != wallet_info.currency.id)
+        || &origin_amount != amount
+    {
+        warn!(
+            "交易單號重複但內容不同 source_txn_id: {}, origin amount: {}, amount: {}",
+            transaction_id, origin_amount, amount
+        );
+        return Err(KgsStatus::DuplicateTransactionAmountError);
+    }
+
+    Ok(())
+}
+
+/// 依照比例分配金額 除了最後一筆以外無條件捨去到原金額的小數位數 最後一筆補足差額
+fn split_by_weights(amount: &BigDecimal, weights: &[BigDecimal]) -> Vec<BigDecimal> {
+    let (_, scale) = amount.as_bigint_and_exponent();
+    let total_weight = weights
+        .iter()
+        .fold(BigDecimal::zero(), |total, weight| total + weight);
+
+    let mut remaining = amount.clone();
+    let mut amounts = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().enumerate() {
+        if index == weights.len() - 1 {
+            amounts.push(remaining.clone());
+            break;
+        }
+
+        let share = (amount * weight / &total_weight).with_scale(scale);
+        remaining -= &share;
+        amounts.push(share);
+    }
+
+    amounts
+}
@@ -0,0 +1,193 @@
+//! 同一個錢包併發下分的測試 需要可連線的 Postgres
+//!
+//! 依照 .env 的 Wallet_DB_* 設定連線 並先執行 migration
+//! `cargo test -- --ignored concurrency` 執行
+
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use context::common::context::{Context, FutureExt};
+use kgs_err::models::status::Status as KgsStatus;
+use migration::{Migrator, MigratorTrait};
+use protos::game_wallet::*;
+
+use super::GameWalletService;
+use crate::application;
+use crate::config;
+use crate::domain::*;
+use crate::infrastructure::memory_impl::MemoryCurrencyService;
+use crate::infrastructure::sea_orm_impl::repository::*;
+use crate::infrastructure::{database, snowflake};
+
+const CLIENT_ID: i64 = 1;
+const CURRENCY: &str = "TWD";
+const OPENING_AMOUNT: i64 = 1000;
+const WITHDRAW_AMOUNT: i64 = 30;
+const WITHDRAW_COUNT: usize = 50;
+
+/// 連線資料庫並執行 migration 回傳帶有資料庫連線的 Context
+async fn build_context() -> Context {
+    let wallet_db_config = config::get_wallet_db();
+    let migration_db = database::connect_migration(wallet_db_config)
+        .await
+        .expect("連線 migration 資料庫失敗");
+    Migrator::up(&migration_db, None)
+        .await
+        .expect("執行 migration 失敗");
+
+    let db = database::build_wallet_db(
+        wallet_db_config,
+        &wallet_db_config.wallet_db_host,
+        &wallet_db_config.wallet_db_port,
+    )
+    .await
+    .expect("連線錢包資料庫失敗");
+
+    Context::current().with_value(db)
+}
+
+/// 以 sea_orm 的 repository 組裝 GameWalletService 幣別使用假的 CurrencyService
+fn build_game_wallet_service(lock_strategy: LockStrategy) -> GameWalletService {
+    let user_wallet_repo: Arc<dyn UserWalletRepositoryTrait> = Arc::new(UserWalletRepository);
+    let wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait> = Arc::new(WalletSourceRepository);
+    let wallet_txn_repo: Arc<dyn WalletTransactionRepositoryTrait> =
+        Arc::new(WalletTransactionRepository);
+    let main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait> = Arc::new(RolloverMainRepository);
+    let rollover_record_repo: Arc<dyn RolloverRecordRepositoryTrait> =
+        Arc::new(RolloverRecordRepository);
+    let outbox_repo: Arc<dyn WalletOutboxRepositoryTrait> = Arc::new(WalletOutboxRepository);
+    let wallet_limit_repo: Arc<dyn WalletLimitRepositoryTrait> = Arc::new(WalletLimitRepository);
+    let bonus_grant_repo: Arc<dyn BonusGrantRepositoryTrait> = Arc::new(BonusGrantRepository);
+    let rollover_contribution_repo: Arc<dyn RolloverContributionRepositoryTrait> =
+        Arc::new(RolloverContributionRepository);
+    let currency_service: Arc<dyn CurrencyServiceTrait> =
+        Arc::new(MemoryCurrencyService::new(vec![Currency {
+            id: 1,
+            name: CURRENCY.to_string(),
+        }]));
+
+    let wallet_service: Arc<dyn WalletServiceTrait> = Arc::new(WalletService::new(
+        wallet_txn_repo.clone(),
+        user_wallet_repo.clone(),
+        outbox_repo.clone(),
+        lock_strategy,
+    ));
+    let wallet_limit_service: Arc<dyn WalletLimitServiceTrait> = Arc::new(WalletLimitService::new(
+        wallet_limit_repo.clone(),
+        wallet_txn_repo.clone(),
+    ));
+    let rollover_service: Arc<dyn RolloverServiceTrait> = Arc::new(RolloverService::new(
+        main_rollover_repo.clone(),
+        rollover_record_repo.clone(),
+        rollover_contribution_repo.clone(),
+    ));
+    let bonus_release_service: Arc<dyn BonusReleaseServiceTrait> =
+        Arc::new(BonusReleaseService::new(
+            wallet_service.clone(),
+            rollover_service.clone(),
+            main_rollover_repo.clone(),
+            wallet_source_repo.clone(),
+            bonus_grant_repo.clone(),
+            None,
+        ));
+    let wallet_mapper: Arc<dyn application::WalletMapperTrait> = Arc::new(
+        application::WalletMapper::new(currency_service.clone(), wallet_source_repo.clone()),
+    );
+
+    GameWalletService::new(
+        wallet_source_repo,
+        wallet_service,
+        rollover_service,
+        bonus_release_service,
+        wallet_mapper,
+        wallet_limit_service,
+        None,
+    )
+}
+
+/// 同時送出多筆下分 成功的筆數不可超過餘額 且最終餘額等於開始金額減去成功的下分
+async fn assert_concurrent_withdraw(lock_strategy: LockStrategy) {
+    let context = build_context().await;
+    let service = Arc::new(build_game_wallet_service(lock_strategy));
+    let user_id = snowflake::generate_id().await;
+
+    // 開始金額
+    service
+        .deposit(DepositRequest {
+            client_id: CLIENT_ID,
+            user_id,
+            currency: CURRENCY.to_string(),
+            wallet_source: 1,
+            transaction_id: snowflake::generate_id().await,
+            amount: OPENING_AMOUNT.to_string(),
+            effective_bet: "1".to_string(),
+            rollover_rate: "1".to_string(),
+            ..Default::default()
+        })
+        .with_context(context.clone())
+        .await
+        .expect("上分失敗");
+
+    let mut handles = Vec::with_capacity(WITHDRAW_COUNT);
+    for _ in 0..WITHDRAW_COUNT {
+        let service = service.clone();
+        let transaction_id = snowflake::generate_id().await;
+        handles.push(tokio::spawn(
+            async move {
+                service
+                    .withdraw(WithdrawRequest {
+                        client_id: CLIENT_ID,
+                        user_id,
+                        currency: CURRENCY.to_string(),
+                        wallet_source: 1,
+                        transaction_id,
+                        amount: WITHDRAW_AMOUNT.to_string(),
+                        ..Default::default()
+                    })
+                    .await
+            }
+            .with_context(context.clone()),
+        ));
+    }
+
+    let mut success_count: i64 = 0;
+    for handle in handles {
+        match handle.await.expect("下分的工作異常結束") {
+            Ok(_) => success_count += 1,
+            // 餘額不足與樂觀鎖重試用盡都是預期中的失敗
+            Err(KgsStatus::WalletAmountNotEnough) | Err(KgsStatus::ConcurrentUpdateConflict) => {}
+            Err(err) => panic!("下分失敗: {}", err),
+        }
+    }
+
+    let balance = service
+        .get_balance(BalanceRequest {
+            client_id: CLIENT_ID,
+            user_id,
+            currency: CURRENCY.to_string(),
+            wallet_source: 1,
+            ..Default::default()
+        })
+        .with_context(context.clone())
+        .await
+        .expect("查詢餘額失敗")
+        .balance
+        .parse::<BigDecimal>()
+        .expect("餘額格式錯誤");
+
+    let expected = BigDecimal::from(OPENING_AMOUNT - WITHDRAW_AMOUNT * success_count);
+    assert!(balance >= BigDecimal::from(0), "餘額為負: {}", balance);
+    assert_eq!(balance, expected, "成功下分 {} 筆", success_count);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+#[ignore = "需要 Postgres 依照 .env 的 Wallet_DB_* 設定連線"]
+async fn concurrent_withdraw_pessimistic() {
+    assert_concurrent_withdraw(LockStrategy::Pessimistic).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+#[ignore = "需要 Postgres 依照 .env 的 Wallet_DB_* 設定連線"]
+async fn concurrent_withdraw_optimistic() {
+    assert_concurrent_withdraw(LockStrategy::Optimistic { max_retries: 10 }).await;
+}
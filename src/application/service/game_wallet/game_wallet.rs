@@ -4,35 +4,61 @@ use std::sync::Arc;
 use bigdecimal::{BigDecimal, Zero};
 use database_manager::transactional;
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{tracing, warn};
 use protos::game_wallet::*;
 
+use super::combined_wallet::CombinedWallet;
 use super::rollback_strategy::RollbackStrategyFactory;
 use super::update_strategy::UpdateStrategyFactory;
 use crate::application::dto::{self, *};
 use crate::domain;
 use crate::enums::WalletAction;
 
+/// 單次批次結算的項目上限
+const MAX_BATCH_SETTLE_ITEMS: usize = 500;
+
 #[derive(Debug)]
 pub struct GameWalletService {
     wallet_source_repo: Arc<dyn domain::WalletSourceRepositoryTrait>,
     wallet_service: Arc<dyn domain::WalletServiceTrait>,
     rollover_service: Arc<dyn domain::RolloverServiceTrait>,
+    bonus_release_service: Arc<dyn domain::BonusReleaseServiceTrait>,
     mapper: Arc<dyn dto::WalletMapperTrait>,
+    wallet_limit_service: Arc<dyn domain::WalletLimitServiceTrait>,
+    combined_wallet: Option<CombinedWallet>, // 合併錢包模式 None 表示使用請求指定的錢包
 }
 
 impl GameWalletService {
+    /// ### 參數
+    /// - `deduct_order`: Option<domain::DeductOrder> - 合併錢包的扣款順序 None 表示不合併錢包
     pub fn new(
         wallet_source_repo: Arc<dyn domain::WalletSourceRepositoryTrait>,
         wallet_service: Arc<dyn domain::WalletServiceTrait>,
         rollover_service: Arc<dyn domain::RolloverServiceTrait>,
+        bonus_release_service: Arc<dyn domain::BonusReleaseServiceTrait>,
         mapper: Arc<dyn dto::WalletMapperTrait>,
+        wallet_limit_service: Arc<dyn domain::WalletLimitServiceTrait>,
+        deduct_order: Option<domain::DeductOrder>,
     ) -> Self {
+        let combined_wallet = deduct_order.map(|deduct_order| {
+            CombinedWallet::new(
+                deduct_order,
+                wallet_source_repo.clone(),
+                wallet_service.clone(),
+                rollover_service.clone(),
+                bonus_release_service.clone(),
+                wallet_limit_service.clone(),
+            )
+        });
+
         Self {
             wallet_source_repo,
             wallet_service,
             rollover_service,
+            bonus_release_service,
             mapper,
+            wallet_limit_service,
+            combined_wallet,
         }
     }
 }
@@ -43,13 +69,21 @@ impl GameWalletService {
     pub async fn get_balance(&self, payload: BalanceRequest) -> Result<BalanceResponse, KgsStatus> {
         let wallet_info = self.mapper.to_wallet_info(&payload).await?;
 
+        // 合併錢包回傳本金與獎金錢包的可用金額總和
+        if let Some(combined_wallet) = &self.combined_wallet {
+            return Ok(BalanceResponse {
+                balance: combined_wallet.get_balance(&wallet_info).await?.to_string(),
+            });
+        }
+
         let wallet_entity = self
             .wallet_service
             .get_or_create_new_one(&wallet_info)
             .await?;
 
+        // 遊戲端只能使用可用金額 凍結中的金額不回傳
         Ok(BalanceResponse {
-            balance: wallet_entity.amount.to_string(),
+            balance: wallet_entity.available_amount().to_string(),
         })
     }
 
@@ -57,17 +91,216 @@ impl GameWalletService {
     #[transactional(SeaOrmPostgres)]
     pub async fn deposit(&self, payload: DepositRequest) -> Result<DepositResponse, KgsStatus> {
         let wallet_info = self.mapper.to_wallet_info(&payload).await?;
-        let amount = payload.get_amount()?;
-        let effective_bet = payload.get_effective_bet()?;
-        let rollover_rate = payload.get_rollover_rate()?;
+
+        let balance = self
+            .settle_deposit(
+                &wallet_info,
+                payload.transaction_id,
+                payload.get_amount()?,
+                payload.get_effective_bet()?,
+                payload.get_rollover_rate()?,
+                payload.get_game_info(),
+            )
+            .await?;
+
+        Ok(DepositResponse { balance })
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn withdraw(&self, payload: WithdrawRequest) -> Result<WithdrawResponse, KgsStatus> {
+        let wallet_info = self.mapper.to_wallet_info(&payload).await?;
+
+        let balance = self
+            .settle_withdraw(&wallet_info, payload.transaction_id, payload.get_amount()?)
+            .await?;
+
+        Ok(WithdrawResponse { balance })
+    }
+
+    /// 批次結算 同一局多個玩家的上分與下分
+    /// - `AllOrNothing`: 全部在同一個交易中處理 任一筆失敗時全部回滾 並回傳該筆的錯誤
+    /// - `BestEffort`: 每筆使用各自的交易 失敗的項目記錄在結果中 不影響其他項目
+    ///
+    /// 結果的順序與請求的項目相同
+    #[tracing::instrument]
+    pub async fn batch_settle(
+        &self,
+        payload: BatchSettleRequest,
+    ) -> Result<BatchSettleResponse, KgsStatus> {
+        if payload.items.is_empty() || payload.items.len() > MAX_BATCH_SETTLE_ITEMS {
+            warn!("批次結算的項目數量: {} 不正確", payload.items.len());
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 依照玩家排序處理 批次之間鎖定錢包的順序一致 避免互相等待造成死結
+        let mut order: Vec<usize> = (0..payload.items.len()).collect();
+        order.sort_by_key(|&index| payload.items[index].user_id);
+
+        let results = match BatchSettleMode::try_from(payload.mode) {
+            Ok(BatchSettleMode::AllOrNothing) => self.batch_settle_all(&payload, &order).await?,
+            Ok(BatchSettleMode::BestEffort) => self.batch_settle_each(&payload, &order).await?,
+            Err(_) => {
+                warn!("批次結算模式: {} 不正確", payload.mode);
+                return Err(KgsStatus::InvalidArgument);
+            }
+        };
+
+        Ok(BatchSettleResponse { results })
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn batch_settle_all(
+        &self,
+        payload: &BatchSettleRequest,
+        order: &[usize],
+    ) -> Result<Vec<BatchSettleItemResult>, KgsStatus> {
+        // 同一批次的幣別與錢包來源相同 只查詢一次
+        let wallet_info = self.mapper.to_wallet_info(payload).await?;
+
+        let mut results = vec![BatchSettleItemResult::default(); payload.items.len()];
+        for &index in order {
+            let item = &payload.items[index];
+            let balance = self.settle_item(&wallet_info, item).await?;
+            results[index] = to_settle_result(item, Ok(balance));
+        }
+
+        Ok(results)
+    }
+
+    #[tracing::instrument]
+    async fn batch_settle_each(
+        &self,
+        payload: &BatchSettleRequest,
+        order: &[usize],
+    ) -> Result<Vec<BatchSettleItemResult>, KgsStatus> {
+        // 同一批次的幣別與錢包來源相同 只查詢一次
+        let wallet_info = self.resolve_wallet_info(payload).await?;
+
+        let mut results = vec![BatchSettleItemResult::default(); payload.items.len()];
+        for &index in order {
+            let item = &payload.items[index];
+            let result = self.settle_item_in_transaction(&wallet_info, item).await;
+            results[index] = to_settle_result(item, result);
+        }
+
+        Ok(results)
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn resolve_wallet_info(
+        &self,
+        payload: &BatchSettleRequest,
+    ) -> Result<domain::WalletInfo, KgsStatus> {
+        self.mapper.to_wallet_info(payload).await
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn settle_item_in_transaction(
+        &self,
+        wallet_info: &domain::WalletInfo,
+        item: &BatchSettleItem,
+    ) -> Result<String, KgsStatus> {
+        self.settle_item(wallet_info, item).await
+    }
+
+    /// 結算單一項目 只接受遊戲上分與遊戲下分
+    /// ### 回傳
+    /// - `String` - 結算後的可用金額
+    async fn settle_item(
+        &self,
+        wallet_info: &domain::WalletInfo,
+        item: &BatchSettleItem,
+    ) -> Result<String, KgsStatus> {
+        let wallet_info = wallet_info.for_user(item.user_id);
+
+        match WalletAction::from_i32(item.action)? {
+            WalletAction::GameDeposit => {
+                self.settle_deposit(
+                    &wallet_info,
+                    item.transaction_id,
+                    item.get_amount()?,
+                    item.get_effective_bet()?,
+                    item.get_rollover_rate()?,
+                    item.get_game_info(),
+                )
+                .await
+            }
+            WalletAction::GameWithdraw => {
+                self.settle_withdraw(&wallet_info, item.transaction_id, item.get_amount()?)
+                    .await
+            }
+            action => {
+                warn!("批次結算不支援的錢包操作: {:?}", action);
+                Err(KgsStatus::InvalidArgument)
+            }
+        }
+    }
+
+    /// 遊戲上分 並修改流水 合併錢包模式下依照下注的比例入帳 呼叫端需要在交易中呼叫
+    /// 有效投注依照遊戲的流水貢獻比例計入達成流水
+    /// ### 回傳
+    /// - `String` - 上分後的可用金額 重送的請求回傳原本的餘額
+    async fn settle_deposit(
+        &self,
+        wallet_info: &domain::WalletInfo,
+        transaction_id: i64,
+        amount: BigDecimal,
+        effective_bet: BigDecimal,
+        rollover_rate: BigDecimal,
+        game: Option<domain::GameInfo>,
+    ) -> Result<String, KgsStatus> {
+        if let Some(combined_wallet) = &self.combined_wallet {
+            return combined_wallet
+                .deposit(
+                    wallet_info,
+                    transaction_id,
+                    amount,
+                    effective_bet,
+                    rollover_rate,
+                    game.as_ref(),
+                )
+                .await;
+        }
+
+        // 重送的請求 與原本的回應及合併錢包相同 回傳目前的可用金額
+        // 交易紀錄的 after_amount 包含凍結中的金額 不能直接回傳
+        if self
+            .wallet_service
+            .get_processed_transaction(
+                wallet_info,
+                transaction_id,
+                &amount,
+                &WalletAction::GameDeposit,
+            )
+            .await?
+            .is_some()
+        {
+            let user_wallet = self
+                .wallet_service
+                .get_or_create_new_one(wallet_info)
+                .await?;
+            return Ok(user_wallet.available_amount().to_string());
+        }
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(wallet_info, &WalletAction::GameDeposit, &amount)
+            .await?;
+
+        // 獎金錢包流水達成時會轉入本金錢包 先依照錢包來源 ID 的順序鎖定兩個錢包
+        self.bonus_release_service.lock_wallets(wallet_info).await?;
 
         // 錢包上分
         let (user_wallet, wallet_txn) = self
             .wallet_service
             .change_amount(
-                &wallet_info,
+                wallet_info,
                 0,
-                payload.transaction_id,
+                transaction_id,
                 amount,
                 &WalletAction::GameDeposit,
             )
@@ -78,31 +311,73 @@ impl GameWalletService {
             .rollover_service
             .change_rollover(
                 user_wallet.id,
-                &wallet_info,
+                wallet_info,
                 wallet_txn.id,
                 effective_bet,
                 rollover_rate,
                 WalletAction::GameDeposit,
+                game.as_ref(),
                 wallet_info.user_id,
             )
             .await?;
 
-        // TODO: 如果是獎金錢包 需要判斷流水是否打滿 然後做對應的處理
+        // 如果是獎金錢包 流水達成時轉入本金錢包
+        let user_wallet = match self
+            .bonus_release_service
+            .release_if_achieved(wallet_info, wallet_info.user_id)
+            .await?
+        {
+            Some(transfer_result) => transfer_result.from_wallet,
+            None => user_wallet,
+        };
 
-        Ok(DepositResponse {
-            balance: user_wallet.amount.to_string(),
-        })
+        Ok(user_wallet.available_amount().to_string())
     }
 
-    #[tracing::instrument]
-    pub async fn withdraw(&self, payload: WithdrawRequest) -> Result<WithdrawResponse, KgsStatus> {
-        let wallet_info = self.mapper.to_wallet_info(&payload).await?;
-        let amount = payload.get_amount()?;
+    /// 遊戲下分 並修改流水 合併錢包模式下依照扣款順序從兩個錢包扣款 呼叫端需要在交易中呼叫
+    /// ### 回傳
+    /// - `String` - 下分後的可用金額 重送的請求回傳原本的餘額
+    async fn settle_withdraw(
+        &self,
+        wallet_info: &domain::WalletInfo,
+        transaction_id: i64,
+        amount: BigDecimal,
+    ) -> Result<String, KgsStatus> {
+        if let Some(combined_wallet) = &self.combined_wallet {
+            return combined_wallet
+                .withdraw(wallet_info, transaction_id, amount)
+                .await;
+        }
+
+        // 重送的請求 與原本的回應及合併錢包相同 回傳目前的可用金額
+        // 交易紀錄的 after_amount 包含凍結中的金額 不能直接回傳
+        if self
+            .wallet_service
+            .get_processed_transaction(
+                wallet_info,
+                transaction_id,
+                &amount,
+                &WalletAction::GameWithdraw,
+            )
+            .await?
+            .is_some()
+        {
+            let user_wallet = self
+                .wallet_service
+                .get_or_create_new_one(wallet_info)
+                .await?;
+            return Ok(user_wallet.available_amount().to_string());
+        }
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(wallet_info, &WalletAction::GameWithdraw, &amount)
+            .await?;
 
         // 檢查餘額是否足夠
         if !self
             .wallet_service
-            .is_wallet_amount_enough(&wallet_info, &amount)
+            .is_wallet_amount_enough(wallet_info, &amount)
             .await?
         {
             return Err(KgsStatus::WalletAmountNotEnough);
@@ -112,9 +387,9 @@ impl GameWalletService {
         let (user_wallet, wallet_txn) = self
             .wallet_service
             .change_amount(
-                &wallet_info,
+                wallet_info,
                 0,
-                payload.transaction_id,
+                transaction_id,
                 amount.clone(),
                 &WalletAction::GameWithdraw,
             )
@@ -125,24 +400,37 @@ impl GameWalletService {
             .rollover_service
             .change_rollover(
                 user_wallet.id,
-                &wallet_info,
+                wallet_info,
                 wallet_txn.id,
                 amount,
                 BigDecimal::zero(),
                 WalletAction::GameWithdraw,
+                None,
                 wallet_info.user_id,
             )
             .await?;
 
-        Ok(WithdrawResponse {
-            balance: user_wallet.amount.to_string(),
-        })
+        Ok(user_wallet.available_amount().to_string())
     }
 
     #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
     pub async fn rollback(&self, payload: RollbackRequest) -> Result<RollbackResponse, KgsStatus> {
         let wallet_info = self.mapper.to_wallet_info(&payload).await?;
 
+        // 合併錢包回滾各錢包的交易
+        if let Some(combined_wallet) = &self.combined_wallet {
+            let balance = combined_wallet
+                .rollback(&wallet_info, &payload.transaction_ids)
+                .await?;
+            return Ok(RollbackResponse { balance });
+        }
+
+        // 獎金錢包流水達成時會轉入本金錢包 先依照錢包來源 ID 的順序鎖定兩個錢包
+        self.bonus_release_service
+            .lock_wallets(&wallet_info)
+            .await?;
+
         // 創建rollback策略
         let strategy = RollbackStrategyFactory::new(
             &wallet_info.wallet_source,
@@ -156,6 +444,11 @@ impl GameWalletService {
             .apply(&wallet_info, &payload.transaction_ids)
             .await?;
 
+        // 如果是獎金錢包 流水達成時轉入本金錢包
+        self.bonus_release_service
+            .release_if_achieved(&wallet_info, wallet_info.user_id)
+            .await?;
+
         // 獲取傳入錢包的最新餘額
         let user_wallet = self
             .wallet_service
@@ -163,18 +456,31 @@ impl GameWalletService {
             .await?;
 
         Ok(RollbackResponse {
-            balance: user_wallet.amount.to_string(),
+            balance: user_wallet.available_amount().to_string(),
         })
     }
 
     #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
     pub async fn update(&self, payload: UpdateRequest) -> Result<UpdateResponse, KgsStatus> {
         let wallet_info = self.mapper.to_wallet_info(&payload).await?;
 
+        // 合併錢包的交易分散在兩個錢包 無法只更新其中一筆
+        if self.combined_wallet.is_some() {
+            warn!("合併錢包模式不支援更新交易");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
         let new_amount = payload.get_new_amount()?;
         let old_amount = payload.get_old_amount()?;
         let effective_bet = payload.get_effective_bet()?;
         let rollover_rate = payload.get_rollover_rate()?;
+        let game = payload.get_game_info();
+
+        // 獎金錢包流水達成時會轉入本金錢包 先依照錢包來源 ID 的順序鎖定兩個錢包
+        self.bonus_release_service
+            .lock_wallets(&wallet_info)
+            .await?;
 
         // 獲取更新策略
         let strategy = UpdateStrategyFactory::new(
@@ -193,16 +499,49 @@ impl GameWalletService {
                 new_amount,
                 effective_bet,
                 rollover_rate,
+                game.as_ref(),
             )
             .await?;
 
+        // 如果是獎金錢包 流水達成時轉入本金錢包
+        self.bonus_release_service
+            .release_if_achieved(&wallet_info, wallet_info.user_id)
+            .await?;
+
         let user_wallet = self
             .wallet_service
             .get_or_create_new_one(&wallet_info)
             .await?;
 
         Ok(UpdateResponse {
-            balance: user_wallet.amount.to_string(),
+            balance: user_wallet.available_amount().to_string(),
         })
     }
 }
+
+/// 將單一項目的結算結果轉為回傳的格式 失敗時使用與 grpc 錯誤相同的代碼與訊息
+fn to_settle_result(
+    item: &BatchSettleItem,
+    result: Result<String, KgsStatus>,
+) -> BatchSettleItemResult {
+    match result {
+        Ok(balance) => BatchSettleItemResult {
+            user_id: item.user_id,
+            transaction_id: item.transaction_id,
+            success: true,
+            balance,
+            ..Default::default()
+        },
+        Err(e) => {
+            let status = kgs_err::grpc::error::error_response(e);
+            BatchSettleItemResult {
+                user_id: item.user_id,
+                transaction_id: item.transaction_id,
+                success: false,
+                error_code: status.code() as i32,
+                error_message: status.message().to_string(),
+                ..Default::default()
+            }
+        }
+    }
+}
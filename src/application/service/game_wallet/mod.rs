@@ -1,5 +1,10 @@
+mod combined_wallet;
+#[cfg(test)]
+mod concurrency_test;
 mod game_wallet;
 mod rollback_strategy;
 mod update_strategy;
+#[cfg(test)]
+mod update_strategy_test;
 
 pub use game_wallet::GameWalletService;
@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use bigdecimal::{BigDecimal, Zero};
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{tracing, warn};
 
 use crate::domain::*;
 use crate::enums;
@@ -163,12 +163,27 @@ impl RollbackWalletStrategy for BonusRollbackWalletStrategy {
         let mut need_rollback_amount = BigDecimal::zero();
         for txn in wallet_txn_list.iter() {
             match enums::WalletAction::from_i32(txn.action)? {
-                enums::WalletAction::GameDeposit | enums::WalletAction::PaymentDeposit => {
+                enums::WalletAction::GameDeposit
+                | enums::WalletAction::PaymentDeposit
+                | enums::WalletAction::TransferIn
+                | enums::WalletAction::PaymentWithdrawReject
+                | enums::WalletAction::BonusGrant => {
                     need_rollback_amount -= &txn.change_amount;
                 }
-                enums::WalletAction::GameWithdraw | enums::WalletAction::PaymentWithdraw => {
+                enums::WalletAction::GameWithdraw
+                | enums::WalletAction::PaymentWithdraw
+                | enums::WalletAction::TransferOut
+                | enums::WalletAction::BonusCancel => {
                     need_rollback_amount += &txn.change_amount;
                 }
+                enums::WalletAction::Adjustment => {
+                    warn!("調整的交易紀錄不能rollback wallet_txn_id: {}", txn.id);
+                    return Err(KgsStatus::InvalidArgument);
+                }
+                enums::WalletAction::BonusForfeit => {
+                    warn!("獎金沒收的交易紀錄不能rollback wallet_txn_id: {}", txn.id);
+                    return Err(KgsStatus::InvalidArgument);
+                }
             }
         }
 
@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use bigdecimal::{BigDecimal, Zero};
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{tracing, warn};
 
 use crate::domain::*;
 use crate::enums;
@@ -43,6 +43,7 @@ pub trait UpdateWalletStrategy: Send {
         new_amount: BigDecimal,
         effective_bet: BigDecimal,
         rollover_rate: BigDecimal,
+        game: Option<&GameInfo>,
     ) -> Result<(), KgsStatus>;
 }
 
@@ -79,6 +80,7 @@ impl UpdateWalletStrategy for NormalUpdateWalletStrategy {
         new_amount: BigDecimal,
         effective_bet: BigDecimal,
         rollover_rate: BigDecimal,
+        game: Option<&GameInfo>,
     ) -> Result<(), KgsStatus> {
         // 找出需要修改的wallet_txn
         let origin_wallet_txn = self
@@ -92,11 +94,28 @@ impl UpdateWalletStrategy for NormalUpdateWalletStrategy {
 
         // 轉換wallet_amount 成有正負的數字
         let wallet_txn_amount = match enums::WalletAction::from_i32(origin_wallet_txn.action)? {
-            enums::WalletAction::PaymentDeposit | enums::WalletAction::GameDeposit => {
-                origin_wallet_txn.change_amount.clone()
+            enums::WalletAction::PaymentDeposit
+            | enums::WalletAction::GameDeposit
+            | enums::WalletAction::TransferIn
+            | enums::WalletAction::PaymentWithdrawReject
+            | enums::WalletAction::BonusGrant => origin_wallet_txn.change_amount.clone(),
+            enums::WalletAction::PaymentWithdraw
+            | enums::WalletAction::GameWithdraw
+            | enums::WalletAction::TransferOut
+            | enums::WalletAction::BonusCancel => -origin_wallet_txn.change_amount.clone(),
+            enums::WalletAction::Adjustment => {
+                warn!(
+                    "調整的交易紀錄不能更新 wallet_txn_id: {}",
+                    origin_wallet_txn.id
+                );
+                return Err(KgsStatus::InvalidArgument);
             }
-            enums::WalletAction::PaymentWithdraw | enums::WalletAction::GameWithdraw => {
-                -origin_wallet_txn.change_amount.clone()
+            enums::WalletAction::BonusForfeit => {
+                warn!(
+                    "獎金沒收的交易紀錄不能更新 wallet_txn_id: {}",
+                    origin_wallet_txn.id
+                );
+                return Err(KgsStatus::InvalidArgument);
             }
         };
 
@@ -161,6 +180,7 @@ impl UpdateWalletStrategy for NormalUpdateWalletStrategy {
                 effective_bet.abs(),
                 rollover_rate,
                 action,
+                game,
                 user_wallet.user_id,
             )
             .await?;
@@ -205,6 +225,7 @@ impl UpdateWalletStrategy for BonusUpdateWalletStrategy {
         new_amount: BigDecimal,
         effective_bet: BigDecimal,
         rollover_rate: BigDecimal,
+        game: Option<&GameInfo>,
     ) -> Result<(), KgsStatus> {
         // 找出需要修改的wallet_txn
         let origin_wallet_txn = self
@@ -218,11 +239,28 @@ impl UpdateWalletStrategy for BonusUpdateWalletStrategy {
 
         // 轉換wallet_amount 成有正負的數字
         let wallet_txn_amount = match enums::WalletAction::from_i32(origin_wallet_txn.action)? {
-            enums::WalletAction::PaymentDeposit | enums::WalletAction::GameDeposit => {
-                origin_wallet_txn.change_amount.clone()
+            enums::WalletAction::PaymentDeposit
+            | enums::WalletAction::GameDeposit
+            | enums::WalletAction::TransferIn
+            | enums::WalletAction::PaymentWithdrawReject
+            | enums::WalletAction::BonusGrant => origin_wallet_txn.change_amount.clone(),
+            enums::WalletAction::PaymentWithdraw
+            | enums::WalletAction::GameWithdraw
+            | enums::WalletAction::TransferOut
+            | enums::WalletAction::BonusCancel => -origin_wallet_txn.change_amount.clone(),
+            enums::WalletAction::Adjustment => {
+                warn!(
+                    "調整的交易紀錄不能更新 wallet_txn_id: {}",
+                    origin_wallet_txn.id
+                );
+                return Err(KgsStatus::InvalidArgument);
             }
-            enums::WalletAction::PaymentWithdraw | enums::WalletAction::GameWithdraw => {
-                -origin_wallet_txn.change_amount.clone()
+            enums::WalletAction::BonusForfeit => {
+                warn!(
+                    "獎金沒收的交易紀錄不能更新 wallet_txn_id: {}",
+                    origin_wallet_txn.id
+                );
+                return Err(KgsStatus::InvalidArgument);
             }
         };
 
@@ -309,6 +347,7 @@ impl UpdateWalletStrategy for BonusUpdateWalletStrategy {
                 effective_bet.abs(),
                 rollover_rate,
                 action,
+                game,
                 user_wallet.user_id,
             )
             .await?;
@@ -0,0 +1,145 @@
+//! 以記憶體資料庫測試本金錢包的 update 策略
+
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::update_strategy::{UpdateStrategyFactory, UpdateWalletStrategy};
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure::memory_impl::*;
+
+const SOURCE_TXN_ID: i64 = 1;
+
+struct Fixture {
+    db: Arc<MemoryDatabase>,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+    wallet_info: WalletInfo,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let db = MemoryDatabase::new();
+        let wallet_source = WalletSource {
+            id: enums::WalletSource::Normal.to_id(),
+            name: "normal".to_string(),
+            create_at: chrono::Utc::now().naive_utc(),
+        };
+        db.insert_wallet_source(wallet_source.clone());
+
+        Self {
+            wallet_source_repo: Arc::new(MemoryWalletSourceRepository::new(db.clone())),
+            wallet_service: Arc::new(WalletService::new(
+                Arc::new(MemoryWalletTransactionRepository::new(db.clone())),
+                Arc::new(MemoryUserWalletRepository::new(db.clone())),
+                Arc::new(MemoryWalletOutboxRepository::new(db.clone())),
+                LockStrategy::Pessimistic,
+            )),
+            rollover_service: Arc::new(RolloverService::new(
+                Arc::new(MemoryRolloverMainRepository::new(db.clone())),
+                Arc::new(MemoryRolloverRecordRepository::new(db.clone())),
+                Arc::new(MemoryRolloverContributionRepository::new(db.clone())),
+            )),
+            wallet_info: WalletInfo {
+                client_id: 1,
+                user_id: 1,
+                currency: Currency {
+                    id: 1,
+                    name: "TWD".to_string(),
+                },
+                wallet_source,
+            },
+            db,
+        }
+    }
+
+    /// 與 GameWallet/Deposit 相同 上分並增加流水
+    async fn game_deposit(&self, amount: i64) {
+        self.db
+            .transaction(|| async move {
+                let (user_wallet, wallet_txn) = self
+                    .wallet_service
+                    .change_amount(
+                        &self.wallet_info,
+                        0,
+                        SOURCE_TXN_ID,
+                        BigDecimal::from(amount),
+                        &enums::WalletAction::GameDeposit,
+                    )
+                    .await?;
+                self.rollover_service
+                    .change_rollover(
+                        user_wallet.id,
+                        &self.wallet_info,
+                        wallet_txn.id,
+                        BigDecimal::from(amount),
+                        BigDecimal::from(1),
+                        enums::WalletAction::GameDeposit,
+                        None,
+                        self.wallet_info.user_id,
+                    )
+                    .await
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn apply_update(&self, old_amount: i64, new_amount: i64) -> Result<(), KgsStatus> {
+        let strategy = UpdateStrategyFactory::new(
+            &self.wallet_info.wallet_source,
+            self.wallet_source_repo.clone(),
+            self.wallet_service.clone(),
+            self.rollover_service.clone(),
+        )?;
+
+        self.db
+            .transaction(|| {
+                strategy.apply(
+                    &self.wallet_info,
+                    SOURCE_TXN_ID,
+                    BigDecimal::from(old_amount),
+                    BigDecimal::from(new_amount),
+                    BigDecimal::from(new_amount),
+                    BigDecimal::from(1),
+                    None,
+                )
+            })
+            .await
+    }
+
+    fn wallet_amount(&self) -> BigDecimal {
+        self.db.read(|tables| tables.user_wallets[0].amount.clone())
+    }
+}
+
+#[tokio::test]
+async fn normal_update_commit() {
+    let fixture = Fixture::new();
+    fixture.game_deposit(100).await;
+
+    fixture.apply_update(100, 40).await.unwrap();
+
+    assert_eq!(fixture.wallet_amount(), BigDecimal::from(40));
+    // 原本的上分 rollback 的紀錄 update 後的上分
+    fixture
+        .db
+        .read(|tables| assert_eq!(tables.wallet_txns.len(), 3));
+}
+
+#[tokio::test]
+async fn normal_update_amount_error_rollback() {
+    let fixture = Fixture::new();
+    fixture.game_deposit(100).await;
+
+    let result = fixture.apply_update(90, 40).await;
+
+    assert!(matches!(result, Err(KgsStatus::GameRollbackAmountError)));
+    assert!(!fixture.db.in_transaction());
+    assert_eq!(fixture.wallet_amount(), BigDecimal::from(100));
+    fixture
+        .db
+        .read(|tables| assert_eq!(tables.wallet_txns.len(), 1));
+}
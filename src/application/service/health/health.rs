@@ -0,0 +1,135 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use database_manager::transactional;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+use tokio::sync::{watch, Mutex};
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+use crate::infrastructure;
+
+/// 定時檢查資料庫與 bank server 更新 grpc.health.v1 的服務狀態
+/// - 錢包服務查詢幣別需要 bank server 資料庫或 bank server 無法使用時都是 NOT_SERVING
+/// - 對帳等服務只需要資料庫
+/// - 空字串的整體狀態 所有依賴都正常時才是 SERVING
+pub struct HealthService {
+    reporter: HealthReporter,
+    wallet_services: Vec<&'static str>,
+    db_only_services: Vec<&'static str>,
+    shutting_down: Mutex<bool>, // 更新狀態時持有 關閉後檢查中的結果不再寫入
+}
+
+impl Debug for HealthService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HealthService")
+            .field("wallet_services", &self.wallet_services)
+            .field("db_only_services", &self.db_only_services)
+            .finish()
+    }
+}
+
+impl HealthService {
+    /// ### 參數
+    /// - `reporter`: HealthReporter - 更新 grpc.health.v1 狀態
+    /// - `wallet_services`: Vec<&'static str> - 需要資料庫與 bank server 的服務名稱
+    /// - `db_only_services`: Vec<&'static str> - 只需要資料庫的服務名稱
+    pub fn new(
+        reporter: HealthReporter,
+        wallet_services: Vec<&'static str>,
+        db_only_services: Vec<&'static str>,
+    ) -> Self {
+        Self {
+            reporter,
+            wallet_services,
+            db_only_services,
+            shutting_down: Mutex::new(false),
+        }
+    }
+
+    /// 定時檢查依賴的服務 啟動後會立即檢查一次
+    /// 收到關閉通知後停止檢查 避免關閉期間又把狀態改回 SERVING
+    /// ### 參數
+    /// - `interval`: Duration - 檢查的間隔
+    /// - `shutdown`: watch::Receiver<bool> - 服務關閉通知
+    pub async fn run(self: Arc<Self>, interval: Duration, mut shutdown: watch::Receiver<bool>) {
+        info!("health check start, interval: {:?}", interval);
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.check().await,
+                _ = shutdown.changed() => break,
+            }
+        }
+
+        info!("health check stopped");
+    }
+
+    /// 將所有服務設為 NOT_SERVING 關閉服務前呼叫 讓負載平衡不再導入新的請求
+    /// 之後的 `check` 不再更新狀態 避免檢查中的結果又把狀態改回 SERVING
+    pub async fn set_not_serving(&self) {
+        let mut shutting_down = self.shutting_down.lock().await;
+        *shutting_down = true;
+
+        let status = ServingStatus::NotServing;
+        self.reporter.set_service_status("", status).await;
+        for service in self
+            .wallet_services
+            .iter()
+            .chain(self.db_only_services.iter())
+        {
+            self.reporter.set_service_status(service, status).await;
+        }
+    }
+
+    /// 檢查一次依賴的服務並更新狀態 已經開始關閉服務時不更新
+    #[tracing::instrument]
+    pub async fn check(&self) {
+        let db_ok = self
+            .check_db()
+            .await
+            .map_err(|e| warn!("wallet db unhealthy: {:?}", e))
+            .is_ok();
+        let bank_server_ok = infrastructure::bank_server::check_bank_server_health()
+            .await
+            .map_err(|e| warn!("bank server unhealthy: {:?}", e))
+            .is_ok();
+
+        // 檢查期間可能已經開始關閉 持有鎖直到狀態更新完 與 set_not_serving 不會交錯
+        let shutting_down = self.shutting_down.lock().await;
+        if *shutting_down {
+            return;
+        }
+
+        let wallet_status = to_serving_status(db_ok && bank_server_ok);
+        self.reporter.set_service_status("", wallet_status).await;
+        for service in self.wallet_services.iter() {
+            self.reporter
+                .set_service_status(service, wallet_status)
+                .await;
+        }
+        for service in self.db_only_services.iter() {
+            self.reporter
+                .set_service_status(service, to_serving_status(db_ok))
+                .await;
+        }
+    }
+
+    /// 從連線池取得連線並執行查詢 連線池無法取得連線時會在開啟交易時失敗
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn check_db(&self) -> Result<(), KgsStatus> {
+        infrastructure::database::ping_current().await
+    }
+}
+
+fn to_serving_status(ok: bool) -> ServingStatus {
+    if ok {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    }
+}
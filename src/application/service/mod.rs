@@ -1,5 +1,13 @@
+mod bonus_expiry;
 mod game_wallet;
+mod health;
+mod outbox;
+mod reconciliation;
 mod user_wallet;
 
+pub use bonus_expiry::BonusExpiryService;
 pub use game_wallet::GameWalletService;
+pub use health::HealthService;
+pub use outbox::OutboxRelayService;
+pub use reconciliation::ReconciliationService;
 pub use user_wallet::UserWalletService;
@@ -0,0 +1,3 @@
+mod outbox_relay;
+
+pub use outbox_relay::OutboxRelayService;
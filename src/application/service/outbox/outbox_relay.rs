@@ -0,0 +1,99 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use database_manager::transactional;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+use tokio::sync::watch;
+
+use crate::domain;
+
+/// 將 outbox 中待發送的事件發送到 RabbitMQ
+/// 事件在發送成功後才會標記為已發送 重試時可能重複發送 下游需以 event_id 去除重複
+#[derive(Debug)]
+pub struct OutboxRelayService {
+    outbox_repo: Arc<dyn domain::WalletOutboxRepositoryTrait>,
+    event_publisher: Arc<dyn domain::EventPublisherTrait>,
+    batch_size: u64,
+}
+
+impl OutboxRelayService {
+    pub fn new(
+        outbox_repo: Arc<dyn domain::WalletOutboxRepositoryTrait>,
+        event_publisher: Arc<dyn domain::EventPublisherTrait>,
+        batch_size: u64,
+    ) -> Self {
+        Self {
+            outbox_repo,
+            event_publisher,
+            batch_size,
+        }
+    }
+
+    /// 定時發送待發送的事件 每次處理一批 還有剩餘時立即處理下一批
+    /// 收到關閉通知後 處理完目前這一批就結束
+    /// ### 參數
+    /// - `interval`: Duration - 沒有待發送事件時的等待時間
+    /// - `shutdown`: watch::Receiver<bool> - 服務關閉通知
+    pub async fn run(self: Arc<Self>, interval: Duration, mut shutdown: watch::Receiver<bool>) {
+        info!("outbox relay start, interval: {:?}", interval);
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+
+            while !*shutdown.borrow() {
+                match self.relay_batch().await {
+                    Ok(count) if count as u64 == self.batch_size => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        warn!("outbox relay failed: {:?}", e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        info!("outbox relay stopped");
+    }
+
+    /// 發送一批待發送的事件
+    /// 依照 ID 順序發送 遇到發送失敗時停止 保留後續事件的順序
+    /// ### 回傳
+    /// - `usize` - 發送成功的事件數量
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn relay_batch(&self) -> Result<usize, KgsStatus> {
+        let outbox_list = self
+            .outbox_repo
+            .get_pending_for_update(self.batch_size)
+            .await?;
+
+        let mut published_ids = Vec::with_capacity(outbox_list.len());
+        for outbox in outbox_list.iter() {
+            match self
+                .event_publisher
+                .publish(&outbox.routing_key(), &outbox.payload)
+                .await
+            {
+                Ok(_) => published_ids.push(outbox.id),
+                Err(_) => {
+                    warn!("publish outbox event failed, id: {}", outbox.id);
+                    self.outbox_repo.increase_retry_count(outbox.id).await?;
+                    break;
+                }
+            }
+        }
+
+        let count = published_ids.len();
+        if !published_ids.is_empty() {
+            self.outbox_repo.mark_published(published_ids).await?;
+        }
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,3 @@
+mod reconciliation;
+
+pub use reconciliation::ReconciliationService;
@@ -0,0 +1,112 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use database_manager::transactional;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+use protos::reconciliation::*;
+use tokio::sync::watch;
+
+use crate::application::dto::*;
+use crate::domain;
+
+#[derive(Debug)]
+pub struct ReconciliationService {
+    user_wallet_repo: Arc<dyn domain::UserWalletRepositoryTrait>,
+    reconciliation_service: Arc<dyn domain::ReconciliationServiceTrait>,
+}
+
+impl ReconciliationService {
+    pub fn new(
+        user_wallet_repo: Arc<dyn domain::UserWalletRepositoryTrait>,
+        reconciliation_service: Arc<dyn domain::ReconciliationServiceTrait>,
+    ) -> Self {
+        Self {
+            user_wallet_repo,
+            reconciliation_service,
+        }
+    }
+
+    /// 對帳 回傳有差異的錢包報告
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn reconcile(
+        &self,
+        payload: ReconcileRequest,
+    ) -> Result<ReconcileResponse, KgsStatus> {
+        let report_list = self
+            .reconciliation_service
+            .reconcile(payload.client_id)
+            .await?;
+
+        Ok(report_list.to_proto())
+    }
+
+    /// 審核報告 並新增調整的交易紀錄
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn approve(
+        &self,
+        payload: ApproveReconciliationRequest,
+    ) -> Result<ReconciliationReportModel, KgsStatus> {
+        let (report, _) = self
+            .reconciliation_service
+            .approve(payload.client_id, payload.report_id, payload.approve_by)
+            .await?;
+
+        Ok(report.to_proto())
+    }
+
+    /// 定時對帳所有 client 的錢包
+    /// ### 參數
+    /// - `interval`: Duration - 對帳間隔
+    /// - `shutdown`: watch::Receiver<bool> - 服務關閉通知
+    pub async fn run(self: Arc<Self>, interval: Duration, mut shutdown: watch::Receiver<bool>) {
+        info!("reconciliation scheduler start, interval: {:?}", interval);
+
+        let mut ticker = tokio::time::interval(interval);
+        // 第一次 tick 會立即觸發 服務啟動時不需要馬上對帳
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+
+            let client_ids = match self.get_client_id_list().await {
+                Ok(client_ids) => client_ids,
+                Err(e) => {
+                    warn!("get reconciliation client list failed: {:?}", e);
+                    continue;
+                }
+            };
+
+            // 每個 client 使用各自的交易 避免單一 client 失敗影響其他 client
+            // 收到關閉通知後不再處理剩下的 client 下次啟動後重新對帳
+            for client_id in client_ids {
+                if *shutdown.borrow() {
+                    break;
+                }
+                if let Err(e) = self.reconcile_client(client_id).await {
+                    warn!("reconcile client failed, client_id: {}, {:?}", client_id, e);
+                }
+            }
+        }
+
+        info!("reconciliation scheduler stopped");
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn get_client_id_list(&self) -> Result<Vec<i64>, KgsStatus> {
+        self.user_wallet_repo.get_client_id_list().await
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    async fn reconcile_client(&self, client_id: i64) -> Result<(), KgsStatus> {
+        self.reconciliation_service.reconcile(client_id).await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 mod rollback_strategy;
+#[cfg(test)]
+mod rollback_strategy_test;
 mod user_wallet;
 
 pub use user_wallet::UserWalletService;
@@ -3,7 +3,7 @@ use std::{fmt::Debug, sync::Arc};
 use crate::domain::*;
 use crate::enums;
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{tracing, warn};
 
 pub struct RollbackWalletStrategyFactory;
 
@@ -31,6 +31,21 @@ impl RollbackWalletStrategyFactory {
             ))),
         }
     }
+
+    /// 轉帳的rollback策略 轉出與轉入的錢包由交易紀錄決定
+    pub fn new_transfer(
+        currency_service: Arc<dyn CurrencyServiceTrait>,
+        wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        rollover_service: Arc<dyn RolloverServiceTrait>,
+    ) -> Box<dyn RollbackWalletStrategy + Send> {
+        Box::new(RollbackTransferStrategy::new(
+            currency_service,
+            wallet_source_repo,
+            wallet_service,
+            rollover_service,
+        ))
+    }
 }
 
 #[tonic::async_trait]
@@ -216,3 +231,195 @@ impl RollbackWalletStrategy for RollbackBonusWalletStrategy {
         Ok((user_wallet, rollover_main))
     }
 }
+
+/// 轉帳的rollback策略
+/// 轉帳的交易鏈中 最後兩筆為同一組轉出/轉入
+/// 依序回滾最後一筆與其上一筆 並串接 parent_id 讓交易鏈維持單一路徑
+#[derive(Debug)]
+struct RollbackTransferStrategy {
+    currency_service: Arc<dyn CurrencyServiceTrait>,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+}
+
+impl RollbackTransferStrategy {
+    #[tracing::instrument]
+    pub fn new(
+        currency_service: Arc<dyn CurrencyServiceTrait>,
+        wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        rollover_service: Arc<dyn RolloverServiceTrait>,
+    ) -> Self {
+        Self {
+            currency_service,
+            wallet_source_repo,
+            wallet_service,
+            rollover_service,
+        }
+    }
+
+    /// 回滾單一錢包的轉帳交易
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 交易所屬的錢包資訊
+    /// - `origin_wallet_txn`: &WalletTransaction - 需要回滾的交易紀錄
+    /// - `parent_txn_id`: i64 - 新交易紀錄的 parent_id
+    #[tracing::instrument]
+    async fn rollback_leg(
+        &self,
+        wallet_info: &WalletInfo,
+        origin_wallet_txn: &WalletTransaction,
+        parent_txn_id: i64,
+    ) -> Result<(UserWallet, WalletTransaction, RolloverMain), KgsStatus> {
+        let rollback_action = match enums::WalletAction::from_i32(origin_wallet_txn.action)? {
+            enums::WalletAction::TransferOut => enums::WalletAction::TransferIn,
+            enums::WalletAction::TransferIn => enums::WalletAction::TransferOut,
+            _ => {
+                warn!("交易紀錄不是轉帳 wallet_txn_id: {}", origin_wallet_txn.id);
+                return Err(KgsStatus::InvalidArgument);
+            }
+        };
+
+        // 轉入的金額已被使用 無法回滾
+        if rollback_action == enums::WalletAction::TransferOut
+            && !self
+                .wallet_service
+                .is_wallet_amount_enough(wallet_info, &origin_wallet_txn.change_amount)
+                .await?
+        {
+            return Err(KgsStatus::WalletAmountNotEnough);
+        }
+
+        // rollback錢包金額
+        let (user_wallet, wallet_txn) = self
+            .wallet_service
+            .change_amount(
+                wallet_info,
+                parent_txn_id,
+                origin_wallet_txn.transaction_source_id,
+                origin_wallet_txn.change_amount.clone(),
+                &rollback_action,
+            )
+            .await?;
+
+        // 從獎金錢包轉出 與轉帳相同需要流水達成
+        // 錢包已在上面鎖定 這裡才鎖定流水主表 流水未達成時整筆交易會被回滾
+        if rollback_action == enums::WalletAction::TransferOut
+            && enums::WalletSource::from_id(wallet_info.wallet_source.id)?
+                == enums::WalletSource::Bonus
+            && !self
+                .rollover_service
+                .is_rollover_achieved(wallet_info)
+                .await?
+        {
+            return Err(KgsStatus::RolloverNotAchieved);
+        }
+
+        // rollback流水
+        let (rollover_main, _rollover_record) = self
+            .rollover_service
+            .rollback_rollover(
+                user_wallet.id,
+                wallet_info,
+                origin_wallet_txn.id,
+                wallet_txn.id,
+                wallet_info.user_id,
+            )
+            .await?;
+
+        Ok((user_wallet, wallet_txn, rollover_main))
+    }
+}
+
+#[tonic::async_trait]
+impl RollbackWalletStrategy for RollbackTransferStrategy {
+    #[tracing::instrument]
+    async fn apply(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        _wallet_source: WalletSource,
+        source_txn_id: i64,
+    ) -> Result<(UserWallet, RolloverMain), KgsStatus> {
+        // 獲取最後一筆交易紀錄 與其上一筆交易紀錄
+        let last_wallet_txn = self
+            .wallet_service
+            .get_last_transaction_by_source_id(client_id, user_id, source_txn_id)
+            .await?;
+        let prev_wallet_txn = self
+            .wallet_service
+            .get_transaction(client_id, user_id, last_wallet_txn.parent_id)
+            .await?;
+
+        // 兩筆必須是不同錢包的同一組轉帳
+        if last_wallet_txn.wallet_source_id == prev_wallet_txn.wallet_source_id
+            || last_wallet_txn.change_amount != prev_wallet_txn.change_amount
+        {
+            warn!("轉帳交易紀錄不成對 source_txn_id: {}", source_txn_id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 已回滾過的轉帳不能再回滾 否則會再執行一次原本的轉帳
+        let wallet_txn_list = self
+            .wallet_service
+            .get_transaction_list_by_source_id(client_id, user_id, source_txn_id)
+            .await?;
+        for leg in [&last_wallet_txn, &prev_wallet_txn] {
+            if is_rolled_back(&wallet_txn_list, leg) {
+                warn!("轉帳已回滾過 source_txn_id: {}", source_txn_id);
+                return Err(KgsStatus::InvalidArgument);
+            }
+        }
+
+        // 檢查幣別
+        let currency = self
+            .currency_service
+            .get_enable_currency_by_id(client_id, last_wallet_txn.currency_id)
+            .await?;
+
+        // 組裝兩個錢包的WalletInfo
+        let last_wallet_info = WalletInfo {
+            client_id,
+            user_id,
+            currency: currency.clone(),
+            wallet_source: self
+                .wallet_source_repo
+                .get(last_wallet_txn.wallet_source_id)
+                .await?,
+        };
+        let prev_wallet_info = WalletInfo {
+            client_id,
+            user_id,
+            currency,
+            wallet_source: self
+                .wallet_source_repo
+                .get(prev_wallet_txn.wallet_source_id)
+                .await?,
+        };
+
+        // 先回滾最後一筆
+        let (_user_wallet, last_rollback_txn, _rollover_main) = self
+            .rollback_leg(&last_wallet_info, &last_wallet_txn, last_wallet_txn.id)
+            .await?;
+
+        // 再回滾上一筆 parent_id 指向上一步產生的交易紀錄
+        let (user_wallet, _wallet_txn, rollover_main) = self
+            .rollback_leg(&prev_wallet_info, &prev_wallet_txn, last_rollback_txn.id)
+            .await?;
+
+        Ok((user_wallet, rollover_main))
+    }
+}
+
+/// 交易紀錄是否已被回滾 或本身就是回滾產生的交易紀錄
+/// 回滾的交易紀錄與原交易在同一個錢包 且 parent_id 指向原交易
+/// 轉入的 parent_id 雖然指向轉出 但兩筆在不同錢包 不視為回滾
+fn is_rolled_back(wallet_txn_list: &[WalletTransaction], leg: &WalletTransaction) -> bool {
+    let is_rollback_of = |txn: &WalletTransaction, origin: &WalletTransaction| {
+        txn.parent_id == origin.id && txn.wallet_source_id == origin.wallet_source_id
+    };
+
+    wallet_txn_list
+        .iter()
+        .any(|txn| is_rollback_of(txn, leg) || is_rollback_of(leg, txn))
+}
@@ -0,0 +1,243 @@
+//! 以記憶體資料庫測試轉帳的 rollback 策略
+
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::rollback_strategy::{RollbackWalletStrategy, RollbackWalletStrategyFactory};
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure::memory_impl::*;
+
+const CLIENT_ID: i64 = 1;
+const USER_ID: i64 = 1;
+const TRANSFER_TXN_ID: i64 = 10;
+
+struct Fixture {
+    db: Arc<MemoryDatabase>,
+    currency_service: Arc<dyn CurrencyServiceTrait>,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+    normal_wallet_info: WalletInfo,
+    bonus_wallet_info: WalletInfo,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let db = MemoryDatabase::new();
+        let currency = Currency {
+            id: 1,
+            name: "TWD".to_string(),
+        };
+        let wallet_info = |wallet_source: enums::WalletSource, name: &str| {
+            let wallet_source = WalletSource {
+                id: wallet_source.to_id(),
+                name: name.to_string(),
+                create_at: chrono::Utc::now().naive_utc(),
+            };
+            db.insert_wallet_source(wallet_source.clone());
+            WalletInfo {
+                client_id: CLIENT_ID,
+                user_id: USER_ID,
+                currency: currency.clone(),
+                wallet_source,
+            }
+        };
+        let normal_wallet_info = wallet_info(enums::WalletSource::Normal, "normal");
+        let bonus_wallet_info = wallet_info(enums::WalletSource::Bonus, "bonus");
+
+        Self {
+            currency_service: Arc::new(MemoryCurrencyService::new(vec![currency])),
+            wallet_source_repo: Arc::new(MemoryWalletSourceRepository::new(db.clone())),
+            wallet_service: Arc::new(WalletService::new(
+                Arc::new(MemoryWalletTransactionRepository::new(db.clone())),
+                Arc::new(MemoryUserWalletRepository::new(db.clone())),
+                Arc::new(MemoryWalletOutboxRepository::new(db.clone())),
+                LockStrategy::Pessimistic,
+            )),
+            rollover_service: Arc::new(RolloverService::new(
+                Arc::new(MemoryRolloverMainRepository::new(db.clone())),
+                Arc::new(MemoryRolloverRecordRepository::new(db.clone())),
+                Arc::new(MemoryRolloverContributionRepository::new(db.clone())),
+            )),
+            normal_wallet_info,
+            bonus_wallet_info,
+            db,
+        }
+    }
+
+    /// 與金流入金相同 上分並增加需求流水
+    async fn deposit(&self, wallet_info: &WalletInfo, source_txn_id: i64, amount: i64) {
+        self.db
+            .transaction(|| async move {
+                let (user_wallet, wallet_txn) = self
+                    .wallet_service
+                    .change_amount(
+                        wallet_info,
+                        0,
+                        source_txn_id,
+                        BigDecimal::from(amount),
+                        &enums::WalletAction::PaymentDeposit,
+                    )
+                    .await?;
+                self.rollover_service
+                    .change_rollover(
+                        user_wallet.id,
+                        wallet_info,
+                        wallet_txn.id,
+                        BigDecimal::from(amount),
+                        BigDecimal::from(1),
+                        enums::WalletAction::PaymentDeposit,
+                        None,
+                        USER_ID,
+                    )
+                    .await
+            })
+            .await
+            .unwrap();
+    }
+
+    /// 與 PlayerWallet/Transfer 相同 轉帳並修改兩個錢包的流水
+    async fn transfer(
+        &self,
+        from_wallet_info: &WalletInfo,
+        to_wallet_info: &WalletInfo,
+        amount: i64,
+    ) {
+        self.db
+            .transaction(|| async move {
+                let transfer_result = self
+                    .wallet_service
+                    .transfer(
+                        from_wallet_info,
+                        to_wallet_info,
+                        TRANSFER_TXN_ID,
+                        BigDecimal::from(amount),
+                    )
+                    .await?;
+                self.rollover_service
+                    .change_rollover(
+                        transfer_result.from_wallet.id,
+                        from_wallet_info,
+                        transfer_result.out_wallet_txn.id,
+                        BigDecimal::from(amount),
+                        BigDecimal::zero(),
+                        enums::WalletAction::TransferOut,
+                        None,
+                        USER_ID,
+                    )
+                    .await?;
+                self.rollover_service
+                    .change_rollover(
+                        transfer_result.to_wallet.id,
+                        to_wallet_info,
+                        transfer_result.in_wallet_txn.id,
+                        BigDecimal::from(amount),
+                        BigDecimal::zero(),
+                        enums::WalletAction::TransferIn,
+                        None,
+                        USER_ID,
+                    )
+                    .await
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn rollback_transfer(&self) -> Result<(UserWallet, RolloverMain), KgsStatus> {
+        let strategy = RollbackWalletStrategyFactory::new_transfer(
+            self.currency_service.clone(),
+            self.wallet_source_repo.clone(),
+            self.wallet_service.clone(),
+            self.rollover_service.clone(),
+        );
+
+        self.db
+            .transaction(|| {
+                strategy.apply(
+                    CLIENT_ID,
+                    USER_ID,
+                    self.normal_wallet_info.wallet_source.clone(),
+                    TRANSFER_TXN_ID,
+                )
+            })
+            .await
+    }
+
+    fn wallet_amount(&self, wallet_info: &WalletInfo) -> BigDecimal {
+        self.db.read(|tables| {
+            tables
+                .user_wallets
+                .iter()
+                .find(|wallet| wallet.wallet_source_id == wallet_info.wallet_source.id)
+                .map(|wallet| wallet.amount.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    fn wallet_txn_count(&self) -> usize {
+        self.db.read(|tables| tables.wallet_txns.len())
+    }
+}
+
+#[tokio::test]
+async fn transfer_rollback_twice_rejected() {
+    let fixture = Fixture::new();
+    fixture.deposit(&fixture.normal_wallet_info, 1, 100).await;
+    fixture
+        .transfer(&fixture.normal_wallet_info, &fixture.bonus_wallet_info, 40)
+        .await;
+
+    fixture.rollback_transfer().await.unwrap();
+
+    assert_eq!(
+        fixture.wallet_amount(&fixture.normal_wallet_info),
+        BigDecimal::from(100)
+    );
+    assert_eq!(
+        fixture.wallet_amount(&fixture.bonus_wallet_info),
+        BigDecimal::zero()
+    );
+    let wallet_txn_count = fixture.wallet_txn_count();
+
+    // 第二次回滾不能再執行一次原本的轉帳
+    let result = fixture.rollback_transfer().await;
+
+    assert!(matches!(result, Err(KgsStatus::InvalidArgument)));
+    assert_eq!(
+        fixture.wallet_amount(&fixture.normal_wallet_info),
+        BigDecimal::from(100)
+    );
+    assert_eq!(
+        fixture.wallet_amount(&fixture.bonus_wallet_info),
+        BigDecimal::zero()
+    );
+    assert_eq!(fixture.wallet_txn_count(), wallet_txn_count);
+}
+
+#[tokio::test]
+async fn transfer_rollback_out_of_bonus_requires_rollover() {
+    let fixture = Fixture::new();
+    fixture.deposit(&fixture.normal_wallet_info, 1, 100).await;
+    fixture.deposit(&fixture.bonus_wallet_info, 2, 100).await;
+    fixture
+        .transfer(&fixture.normal_wallet_info, &fixture.bonus_wallet_info, 40)
+        .await;
+    let wallet_txn_count = fixture.wallet_txn_count();
+
+    // 回滾轉入獎金錢包的交易 等同從獎金錢包轉出 流水未達成時不能回滾
+    let result = fixture.rollback_transfer().await;
+
+    assert!(matches!(result, Err(KgsStatus::RolloverNotAchieved)));
+    assert_eq!(
+        fixture.wallet_amount(&fixture.normal_wallet_info),
+        BigDecimal::from(60)
+    );
+    assert_eq!(
+        fixture.wallet_amount(&fixture.bonus_wallet_info),
+        BigDecimal::from(140)
+    );
+    assert_eq!(fixture.wallet_txn_count(), wallet_txn_count);
+}
@@ -1,6 +1,8 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use bigdecimal::{BigDecimal, Zero};
+use database_manager::transactional;
 use kgs_tracing::tracing;
 use protos::player_wallet::*;
 
@@ -13,32 +15,44 @@ use kgs_err::models::status::Status as KgsStatus;
 #[derive(Debug)]
 pub struct UserWalletService {
     user_wallet_repo: Arc<dyn domain::UserWalletRepositoryTrait>,
+    rollover_record_repo: Arc<dyn domain::RolloverRecordRepositoryTrait>,
     wallet_source_repo: Arc<dyn domain::WalletSourceRepositoryTrait>,
     wallet_service: Arc<dyn domain::WalletServiceTrait>,
     rollover_service: Arc<dyn domain::RolloverServiceTrait>,
+    bonus_release_service: Arc<dyn domain::BonusReleaseServiceTrait>,
+    bonus_grant_service: Arc<dyn domain::BonusGrantServiceTrait>,
     wallet_mapper: Arc<dyn dto::WalletMapperTrait>,
     query_mapper: Arc<dyn dto::QueryMapperTrait>,
     currency_service: Arc<dyn domain::CurrencyServiceTrait>,
+    wallet_limit_service: Arc<dyn domain::WalletLimitServiceTrait>,
 }
 
 impl UserWalletService {
     pub fn new(
         user_wallet_repo: Arc<dyn domain::UserWalletRepositoryTrait>,
+        rollover_record_repo: Arc<dyn domain::RolloverRecordRepositoryTrait>,
         wallet_source_repo: Arc<dyn domain::WalletSourceRepositoryTrait>,
         wallet_service: Arc<dyn domain::WalletServiceTrait>,
         rollover_service: Arc<dyn domain::RolloverServiceTrait>,
+        bonus_release_service: Arc<dyn domain::BonusReleaseServiceTrait>,
+        bonus_grant_service: Arc<dyn domain::BonusGrantServiceTrait>,
         wallet_mapper: Arc<dyn dto::WalletMapperTrait>,
         query_mapper: Arc<dyn dto::QueryMapperTrait>,
         currency_service: Arc<dyn domain::CurrencyServiceTrait>,
+        wallet_limit_service: Arc<dyn domain::WalletLimitServiceTrait>,
     ) -> Self {
         Self {
             user_wallet_repo,
+            rollover_record_repo,
             wallet_source_repo,
             wallet_service,
             rollover_service,
+            bonus_release_service,
+            bonus_grant_service,
             wallet_mapper,
             query_mapper,
             currency_service,
+            wallet_limit_service,
         }
     }
 }
@@ -79,6 +93,41 @@ impl UserWalletService {
         Ok(result.to_proto())
     }
 
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn get_transaction_list(
+        &self,
+        payload: GetTransactionListRequest,
+    ) -> Result<GetTransactionListResponse, KgsStatus> {
+        let query = self
+            .query_mapper
+            .to_select_wallet_transaction_query(payload)
+            .await?;
+
+        let result = self.wallet_service.get_transaction_list(query).await?;
+
+        Ok(result.to_proto())
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn get_rollover_record_list(
+        &self,
+        payload: GetRolloverRecordListRequest,
+    ) -> Result<GetRolloverRecordListResponse, KgsStatus> {
+        let query = self
+            .query_mapper
+            .to_select_rollover_record_query(payload)
+            .await?;
+
+        let result = self
+            .rollover_record_repo
+            .get_list_with_transaction(query)
+            .await?;
+
+        Ok(result.to_proto())
+    }
+
     #[tracing::instrument]
     #[transactional(SeaOrmPostgres)]
     pub async fn deposit(
@@ -88,33 +137,99 @@ impl UserWalletService {
         let wallet_info = self.wallet_mapper.to_wallet_info(&payload).await?;
         let amount = payload.get_amount()?;
         let rollover_rate = payload.get_rollover_rate()?;
+        let payment = payload.get_payment_reference()?;
+        let bonus_expire_at = payload.get_bonus_expire_at()?;
 
-        // 錢包上分
+        // 相同金流訂單重送 回傳目前的錢包
+        if self
+            .wallet_service
+            .get_processed_payment(
+                &wallet_info,
+                &payment.order_id,
+                &amount,
+                &enums::WalletAction::PaymentDeposit,
+            )
+            .await?
+            .is_some()
+        {
+            return self.get_wallet_proto(&wallet_info).await;
+        }
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(&wallet_info, &enums::WalletAction::PaymentDeposit, &amount)
+            .await?;
+
+        // 獎金錢包流水達成時會轉入本金錢包 先依照錢包來源 ID 的順序鎖定兩個錢包
+        self.bonus_release_service
+            .lock_wallets(&wallet_info)
+            .await?;
+
+        // 錢包上分 交易紀錄帶有金流訂單參考
         let (user_wallet, wallet_txn) = self
             .wallet_service
-            .change_amount(
+            .change_payment_amount(
                 &wallet_info,
-                0,
-                payload.wallet_source_id,
+                &payment,
                 amount.clone(),
                 &enums::WalletAction::PaymentDeposit,
             )
             .await?;
 
+        // 檢查錢包餘額上限 超過時整筆交易回滾
+        self.wallet_limit_service
+            .check_after_change(
+                &wallet_info,
+                &enums::WalletAction::PaymentDeposit,
+                &user_wallet,
+            )
+            .await?;
+
         // 修改流水
-        let (rollover_main, _rollover_detail) = self
+        let (rollover_main, rollover_detail) = self
             .rollover_service
             .change_rollover(
                 user_wallet.id,
                 &wallet_info,
                 wallet_txn.id,
                 amount,
-                rollover_rate,
+                rollover_rate.clone(),
                 enums::WalletAction::PaymentDeposit,
+                None,
                 wallet_info.user_id,
             )
             .await?;
 
+        // 如果是獎金錢包 紀錄發放的獎金與到期時間
+        self.bonus_grant_service
+            .grant(
+                &wallet_info,
+                &wallet_txn,
+                rollover_detail.as_ref(),
+                domain::BonusTerms {
+                    campaign_id: None,
+                    rollover_rate,
+                    expire_at: bonus_expire_at,
+                    create_by: wallet_info.user_id,
+                },
+            )
+            .await?;
+
+        // 如果是獎金錢包 流水達成時轉入本金錢包
+        if let Some(transfer_result) = self
+            .bonus_release_service
+            .release_if_achieved(&wallet_info, wallet_info.user_id)
+            .await?
+        {
+            let rollover_main = self
+                .rollover_service
+                .get_or_create_new_one(transfer_result.from_wallet.id, &wallet_info)
+                .await?;
+            return self
+                .wallet_mapper
+                .to_wallet_proto(transfer_result.from_wallet, rollover_main);
+        }
+
         self.wallet_mapper
             .to_wallet_proto(user_wallet, rollover_main)
     }
@@ -128,6 +243,37 @@ impl UserWalletService {
         let wallet_info = self.wallet_mapper.to_wallet_info(&payload).await?;
         let amount = payload.get_amount()?;
         let rollover_rate = payload.get_rollover_rate()?;
+        let payment = payload.get_payment_reference()?;
+
+        // 相同金流訂單重送 回傳目前的錢包
+        if self
+            .wallet_service
+            .get_processed_payment(
+                &wallet_info,
+                &payment.order_id,
+                &amount,
+                &enums::WalletAction::PaymentWithdraw,
+            )
+            .await?
+            .is_some()
+        {
+            return self.get_wallet_proto(&wallet_info).await;
+        }
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(&wallet_info, &enums::WalletAction::PaymentWithdraw, &amount)
+            .await?;
+
+        // 檢查餘額是否足夠
+        // 需先鎖定錢包再鎖定流水主表 與其他流程的鎖定順序一致 避免死結
+        if !self
+            .wallet_service
+            .is_wallet_amount_enough(&wallet_info, &amount)
+            .await?
+        {
+            return Err(KgsStatus::WalletAmountNotEnough);
+        }
 
         // 確認流水是否達成
         if !self
@@ -138,18 +284,26 @@ impl UserWalletService {
             return Err(KgsStatus::RolloverNotAchieved);
         }
 
-        // 錢包下分
+        // 錢包下分 交易紀錄帶有金流訂單參考
         let (user_wallet, wallet_txn) = self
             .wallet_service
-            .change_amount(
+            .change_payment_amount(
                 &wallet_info,
-                0,
-                payload.wallet_source_id,
+                &payment,
                 amount.clone(),
                 &enums::WalletAction::PaymentWithdraw,
             )
             .await?;
 
+        // 檢查出金總額上限 包含本次出金 超過時整筆交易回滾
+        self.wallet_limit_service
+            .check_after_change(
+                &wallet_info,
+                &enums::WalletAction::PaymentWithdraw,
+                &user_wallet,
+            )
+            .await?;
+
         // 修改流水
         let (rollover_main, _rollover_detail) = self
             .rollover_service
@@ -160,6 +314,7 @@ impl UserWalletService {
                 amount,
                 rollover_rate,
                 enums::WalletAction::PaymentWithdraw,
+                None,
                 wallet_info.user_id,
             )
             .await?;
@@ -169,30 +324,433 @@ impl UserWalletService {
     }
 
     #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn transfer(&self, payload: PlayerTransferRequest) -> Result<WalletModel, KgsStatus> {
+        let from_wallet_info = self.wallet_mapper.to_wallet_info(&payload).await?;
+        let to_wallet_source = self
+            .wallet_source_repo
+            .get(payload.to_wallet_source_id)
+            .await?;
+        let to_wallet_info = domain::WalletInfo {
+            client_id: from_wallet_info.client_id,
+            user_id: from_wallet_info.user_id,
+            currency: from_wallet_info.currency.clone(),
+            wallet_source: to_wallet_source,
+        };
+        let amount = payload.get_amount()?;
+
+        // 檢查單筆金額限額
+        self.wallet_limit_service
+            .check_amount(
+                &from_wallet_info,
+                &enums::WalletAction::TransferOut,
+                &amount,
+            )
+            .await?;
+        self.wallet_limit_service
+            .check_amount(&to_wallet_info, &enums::WalletAction::TransferIn, &amount)
+            .await?;
+
+        // 錢包轉帳
+        let transfer_result = self
+            .wallet_service
+            .transfer(
+                &from_wallet_info,
+                &to_wallet_info,
+                payload.transaction_id,
+                amount.clone(),
+            )
+            .await?;
+
+        // 重送的請求 回傳目前的錢包
+        if transfer_result.is_resend {
+            let rollover_main = self
+                .rollover_service
+                .get_or_create_new_one(transfer_result.from_wallet.id, &from_wallet_info)
+                .await?;
+            return self
+                .wallet_mapper
+                .to_wallet_proto(transfer_result.from_wallet, rollover_main);
+        }
+
+        // 檢查轉入錢包的餘額上限 超過時整筆交易回滾
+        self.wallet_limit_service
+            .check_after_change(
+                &to_wallet_info,
+                &enums::WalletAction::TransferIn,
+                &transfer_result.to_wallet,
+            )
+            .await?;
+
+        // 從獎金錢包轉出 需要流水達成
+        // 錢包已在轉帳時依序鎖定 這裡才鎖定流水主表 與其他流程的鎖定順序一致
+        // 流水未達成時回傳錯誤 整筆交易會被回滾
+        if enums::WalletSource::from_id(from_wallet_info.wallet_source.id)?
+            == enums::WalletSource::Bonus
+            && !self
+                .rollover_service
+                .is_rollover_achieved(&from_wallet_info)
+                .await?
+        {
+            return Err(KgsStatus::RolloverNotAchieved);
+        }
+
+        // 修改轉出錢包流水
+        let (rollover_main, _rollover_detail) = self
+            .rollover_service
+            .change_rollover(
+                transfer_result.from_wallet.id,
+                &from_wallet_info,
+                transfer_result.out_wallet_txn.id,
+                amount.clone(),
+                BigDecimal::zero(),
+                enums::WalletAction::TransferOut,
+                None,
+                from_wallet_info.user_id,
+            )
+            .await?;
+
+        // 修改轉入錢包流水
+        self.rollover_service
+            .change_rollover(
+                transfer_result.to_wallet.id,
+                &to_wallet_info,
+                transfer_result.in_wallet_txn.id,
+                amount,
+                BigDecimal::zero(),
+                enums::WalletAction::TransferIn,
+                None,
+                to_wallet_info.user_id,
+            )
+            .await?;
+
+        self.wallet_mapper
+            .to_wallet_proto(transfer_result.from_wallet, rollover_main)
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn withdraw_reject(
+        &self,
+        payload: WithdrawRejectRequest,
+    ) -> Result<WalletModel, KgsStatus> {
+        let withdraw_wallet_txn = self
+            .wallet_service
+            .get_transaction(payload.client_id, payload.player_id, payload.wallet_txn_id)
+            .await?;
+        let wallet_info = self
+            .wallet_info_by_transaction(&withdraw_wallet_txn)
+            .await?;
+
+        // 出金金額退回原本的錢包
+        let (user_wallet, reject_wallet_txn) = self
+            .wallet_service
+            .reject_withdraw(&wallet_info, &withdraw_wallet_txn)
+            .await?;
+
+        // 還原出金時清零的流水
+        let (rollover_main, _rollover_detail) = self
+            .rollover_service
+            .rollback_rollover(
+                user_wallet.id,
+                &wallet_info,
+                withdraw_wallet_txn.id,
+                reject_wallet_txn.id,
+                wallet_info.user_id,
+            )
+            .await?;
+
+        self.wallet_mapper
+            .to_wallet_proto(user_wallet, rollover_main)
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn hold(&self, payload: PlayerHoldRequest) -> Result<WalletModel, KgsStatus> {
+        let wallet_info = self.wallet_mapper.to_wallet_info(&payload).await?;
+        let amount = payload.get_amount()?;
+
+        // 凍結的金額確認後會出款 使用出金的限額
+        self.wallet_limit_service
+            .check_amount(&wallet_info, &enums::WalletAction::PaymentWithdraw, &amount)
+            .await?;
+
+        // 凍結金額 可用金額不足時回傳錯誤
+        let (user_wallet, _wallet_txn) = self
+            .wallet_service
+            .hold(&wallet_info, payload.hold_id, amount)
+            .await?;
+
+        // 凍結中的出金也計入出金總額 超過時整筆交易回滾
+        self.wallet_limit_service
+            .check_after_change(
+                &wallet_info,
+                &enums::WalletAction::PaymentWithdraw,
+                &user_wallet,
+            )
+            .await?;
+
+        // 凍結的金額確認後會出款 需要流水達成
+        // 錢包已在凍結時鎖定 這裡才鎖定流水主表 與其他流程的鎖定順序一致
+        // 流水未達成時回傳錯誤 整筆交易會被回滾
+        if !self
+            .rollover_service
+            .is_rollover_achieved(&wallet_info)
+            .await?
+        {
+            return Err(KgsStatus::RolloverNotAchieved);
+        }
+
+        let rollover_main = self
+            .rollover_service
+            .get_or_create_new_one(user_wallet.id, &wallet_info)
+            .await?;
+
+        self.wallet_mapper
+            .to_wallet_proto(user_wallet, rollover_main)
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn commit_hold(
+        &self,
+        payload: PlayerHoldActionRequest,
+    ) -> Result<WalletModel, KgsStatus> {
+        let hold_wallet_txn = self
+            .wallet_service
+            .get_hold_transaction(payload.client_id, payload.player_id, payload.hold_id)
+            .await?;
+        let wallet_info = self.wallet_info_by_transaction(&hold_wallet_txn).await?;
+
+        // 已確認過的凍結 直接回傳目前的錢包
+        if enums::WalletStatus::from_i32(hold_wallet_txn.status)? == enums::WalletStatus::Success {
+            return self.get_wallet_proto(&wallet_info).await;
+        }
+
+        // 確認凍結 扣除錢包金額
+        let (user_wallet, wallet_txn) = self
+            .wallet_service
+            .commit_hold(&wallet_info, payload.hold_id)
+            .await?;
+
+        // 修改流水 與一般出款相同
+        let (rollover_main, _rollover_detail) = self
+            .rollover_service
+            .change_rollover(
+                user_wallet.id,
+                &wallet_info,
+                wallet_txn.id,
+                wallet_txn.change_amount.clone(),
+                BigDecimal::zero(),
+                enums::WalletAction::PaymentWithdraw,
+                None,
+                wallet_info.user_id,
+            )
+            .await?;
+
+        self.wallet_mapper
+            .to_wallet_proto(user_wallet, rollover_main)
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn release_hold(
+        &self,
+        payload: PlayerHoldActionRequest,
+    ) -> Result<WalletModel, KgsStatus> {
+        let hold_wallet_txn = self
+            .wallet_service
+            .get_hold_transaction(payload.client_id, payload.player_id, payload.hold_id)
+            .await?;
+        let wallet_info = self.wallet_info_by_transaction(&hold_wallet_txn).await?;
+
+        // 已取消過的凍結 直接回傳目前的錢包
+        if enums::WalletStatus::from_i32(hold_wallet_txn.status)? == enums::WalletStatus::Cancel {
+            return self.get_wallet_proto(&wallet_info).await;
+        }
+
+        // 解除凍結
+        let (user_wallet, _wallet_txn) = self
+            .wallet_service
+            .release_hold(&wallet_info, payload.hold_id)
+            .await?;
+
+        let rollover_main = self
+            .rollover_service
+            .get_or_create_new_one(user_wallet.id, &wallet_info)
+            .await?;
+
+        self.wallet_mapper
+            .to_wallet_proto(user_wallet, rollover_main)
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
     pub async fn rollback(&self, payload: RollbackRequest) -> Result<WalletModel, KgsStatus> {
         let wallet_source = self
             .wallet_source_repo
             .get(enums::WalletSource::Normal.to_id())
             .await?;
 
-        let strategy = rollback_strategy::RollbackWalletStrategyFactory::new(
-            &wallet_source,
-            self.currency_service.clone(),
-            self.wallet_source_repo.clone(),
-            self.wallet_service.clone(),
-            self.rollover_service.clone(),
-        )?;
+        // 金流訂單以金流訂單 ID 找回原始交易的來源交易 ID
+        let source_transaction_id = if payload.payment_order_id.is_empty() {
+            payload.source_transaction_id
+        } else {
+            self.wallet_service
+                .get_payment_transaction(
+                    payload.client_id,
+                    payload.user_id,
+                    &payload.payment_order_id,
+                )
+                .await?
+                .transaction_source_id
+        };
+
+        // 轉帳需要同時回滾轉出與轉入兩個錢包
+        let last_wallet_txn = self
+            .wallet_service
+            .get_last_transaction_by_source_id(
+                payload.client_id,
+                payload.user_id,
+                source_transaction_id,
+            )
+            .await?;
+        let strategy = match enums::WalletAction::from_i32(last_wallet_txn.action)? {
+            enums::WalletAction::TransferOut | enums::WalletAction::TransferIn => {
+                rollback_strategy::RollbackWalletStrategyFactory::new_transfer(
+                    self.currency_service.clone(),
+                    self.wallet_source_repo.clone(),
+                    self.wallet_service.clone(),
+                    self.rollover_service.clone(),
+                )
+            }
+            _ => rollback_strategy::RollbackWalletStrategyFactory::new(
+                &wallet_source,
+                self.currency_service.clone(),
+                self.wallet_source_repo.clone(),
+                self.wallet_service.clone(),
+                self.rollover_service.clone(),
+            )?,
+        };
 
         let (user_wallet, rollover_main) = strategy
             .apply(
                 payload.client_id,
                 payload.user_id,
                 wallet_source,
-                payload.source_transaction_id,
+                source_transaction_id,
             )
             .await?;
 
         self.wallet_mapper
             .to_wallet_proto(user_wallet, rollover_main)
     }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn grant_bonus(
+        &self,
+        payload: GrantBonusRequest,
+    ) -> Result<BonusGrantResponse, KgsStatus> {
+        let wallet_info = self.wallet_mapper.to_wallet_info(&payload).await?;
+        let amount = payload.get_amount()?;
+        let terms = payload.get_bonus_terms()?;
+
+        // 檢查單筆金額限額 與入金相同
+        self.wallet_limit_service
+            .check_amount(&wallet_info, &enums::WalletAction::PaymentDeposit, &amount)
+            .await?;
+
+        // 獎金錢包上分 增加需求流水 並紀錄發放的獎金
+        let (user_wallet, rollover_main, bonus_grant) = self
+            .bonus_grant_service
+            .grant_bonus(&wallet_info, amount, terms)
+            .await?;
+
+        // 檢查錢包餘額上限 超過時整筆交易回滾
+        self.wallet_limit_service
+            .check_after_change(
+                &wallet_info,
+                &enums::WalletAction::PaymentDeposit,
+                &user_wallet,
+            )
+            .await?;
+
+        Ok(BonusGrantResponse {
+            bonus_grant: Some(bonus_grant.to_proto()),
+            wallet: Some(
+                self.wallet_mapper
+                    .to_wallet_proto(user_wallet, rollover_main)?,
+            ),
+        })
+    }
+
+    #[tracing::instrument]
+    #[transactional(SeaOrmPostgres)]
+    pub async fn cancel_bonus(
+        &self,
+        payload: CancelBonusRequest,
+    ) -> Result<BonusGrantResponse, KgsStatus> {
+        // 扣回發放金額 並還原發放時增加的需求流水
+        let (user_wallet, rollover_main, bonus_grant) = self
+            .bonus_grant_service
+            .cancel(
+                payload.client_id,
+                payload.bonus_grant_id,
+                payload.operator_id,
+            )
+            .await?;
+
+        Ok(BonusGrantResponse {
+            bonus_grant: Some(bonus_grant.to_proto()),
+            wallet: Some(
+                self.wallet_mapper
+                    .to_wallet_proto(user_wallet, rollover_main)?,
+            ),
+        })
+    }
+}
+
+impl UserWalletService {
+    /// 依照交易紀錄組裝該交易所屬錢包的WalletInfo
+    #[tracing::instrument]
+    async fn wallet_info_by_transaction(
+        &self,
+        wallet_txn: &domain::WalletTransaction,
+    ) -> Result<domain::WalletInfo, KgsStatus> {
+        let currency = self
+            .currency_service
+            .get_enable_currency_by_id(wallet_txn.client_id, wallet_txn.currency_id)
+            .await?;
+        let wallet_source = self
+            .wallet_source_repo
+            .get(wallet_txn.wallet_source_id)
+            .await?;
+
+        Ok(domain::WalletInfo {
+            client_id: wallet_txn.client_id,
+            user_id: wallet_txn.user_id,
+            currency,
+            wallet_source,
+        })
+    }
+
+    /// 取得目前的錢包與流水
+    #[tracing::instrument]
+    async fn get_wallet_proto(
+        &self,
+        wallet_info: &domain::WalletInfo,
+    ) -> Result<WalletModel, KgsStatus> {
+        let user_wallet = self
+            .wallet_service
+            .get_or_create_new_one(wallet_info)
+            .await?;
+        let rollover_main = self
+            .rollover_service
+            .get_or_create_new_one(user_wallet.id, wallet_info)
+            .await?;
+
+        self.wallet_mapper
+            .to_wallet_proto(user_wallet, rollover_main)
+    }
 }
@@ -12,6 +12,15 @@ pub struct Config {
     pub wallet_db: WalletDb,
     pub bank_server: BankServer,
     pub rabbitmq: RabbitMQ,
+    pub bonus_release: BonusRelease,
+    pub bonus_expiry: BonusExpiry,
+    pub outbox: Outbox,
+    pub reconciliation: Reconciliation,
+    pub currency_cache: CurrencyCache,
+    pub migration: Migration,
+    pub health: Health,
+    pub wallet_lock: WalletLock,
+    pub game_wallet: GameWallet,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -24,6 +33,8 @@ pub struct Host {
     pub service_name: String,
     pub service_host: String,
     pub service_port: String,
+    pub shutdown_drain_timeout_secs: u64, // 關閉服務時等待處理中請求完成的時間
+    pub shutdown_grace_period_secs: u64,  // 設為 NOT_SERVING 後等待負載平衡停止轉送的時間
 }
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct RabbitMQ {
@@ -44,6 +55,93 @@ pub struct WalletDb {
     pub wallet_db_name: String,
     pub wallet_db_max_connection: u32,
     pub wallet_db_min_connection: u32,
+    pub wallet_db_connect_timeout_secs: u64, // 取得連線的逾時時間
+    pub wallet_db_idle_timeout_secs: u64,    // 閒置連線關閉的時間
+    pub wallet_db_ssl_mode: Option<String>, // disable / prefer / require / verify-ca / verify-full 不設定為 prefer
+    pub wallet_db_read_host: Option<String>, // 讀取用副本的位置 不設定表示查詢也使用主庫
+    pub wallet_db_read_port: Option<String>, // 讀取用副本的 port 不設定時與主庫相同
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BonusRelease {
+    pub bonus_release_max_amount: Option<String>, // 獎金錢包流水達成時 單次轉入本金錢包的上限 不設定表示不限制
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BonusExpiry {
+    pub bonus_expiry_default_secs: Option<u64>, // 未指定到期時間的獎金有效期限 不設定表示不會到期
+    pub bonus_expiry_interval_secs: u64,        // 檢查到期獎金的間隔
+    pub bonus_expiry_batch_size: u64,           // 每次沒收的獎金數量
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Outbox {
+    pub outbox_exchange: String,       // 錢包事件發送的 exchange
+    pub outbox_relay_interval_ms: u64, // 沒有待發送事件時 檢查 outbox 的間隔
+    pub outbox_relay_batch_size: u64,  // 每次發送的事件數量
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Reconciliation {
+    pub reconciliation_interval_secs: Option<u64>, // 背景對帳的間隔 不設定表示不執行背景對帳
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CurrencyCache {
+    pub currency_cache_ttl_secs: u64, // 幣別快取的有效時間 0 表示不使用快取
+    pub currency_event_exchange: Option<String>, // 幣別變更事件的 exchange 不設定表示不接收事件
+    pub currency_event_queue: String, // 接收幣別變更事件的 queue 每個服務需要不同
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Migration {
+    pub migration_mode: MigrationMode, // 啟動時處理 migration 的方式
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationMode {
+    Apply, // 執行尚未執行的 migration
+    Check, // 有尚未執行的 migration 時不啟動服務
+    Off,   // 不檢查
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Health {
+    pub health_check_interval_secs: u64, // 檢查資料庫與 bank server 狀態的間隔
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WalletLock {
+    pub wallet_lock_mode: WalletLockMode, // 修改錢包時的鎖定方式
+    pub wallet_lock_max_retries: u32,     // 樂觀鎖版本衝突時 修改錢包的最大重試次數
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletLockMode {
+    Pessimistic, // 讀取錢包時以 SELECT ... FOR UPDATE 鎖定到交易結束
+    Optimistic,  // 讀取時不鎖定 更新時比對版本號 衝突時重試
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GameWallet {
+    pub game_wallet_mode: GameWalletMode, // 遊戲端使用的錢包
+    pub game_wallet_deduct_order: GameWalletDeductOrder, // 合併錢包下注時的扣款順序
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GameWalletMode {
+    Single,   // 使用請求指定的錢包
+    Combined, // 本金與獎金錢包合併為一個錢包
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameWalletDeductOrder {
+    BonusFirst,  // 先扣獎金錢包
+    NormalFirst, // 先扣本金錢包
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -61,6 +159,18 @@ impl Config {
         let wallet_db = envy::from_env::<WalletDb>().expect("載入WalletDb 環境變數失敗");
         let bank_server = envy::from_env::<BankServer>().expect("載入BankServer 環境變數失敗");
         let rabbitmq = envy::from_env::<RabbitMQ>().expect("載入RabbitMQ 環境變數失敗");
+        let bonus_release =
+            envy::from_env::<BonusRelease>().expect("載入BonusRelease 環境變數失敗");
+        let bonus_expiry = envy::from_env::<BonusExpiry>().expect("載入BonusExpiry 環境變數失敗");
+        let outbox = envy::from_env::<Outbox>().expect("載入Outbox 環境變數失敗");
+        let reconciliation =
+            envy::from_env::<Reconciliation>().expect("載入Reconciliation 環境變數失敗");
+        let currency_cache =
+            envy::from_env::<CurrencyCache>().expect("載入CurrencyCache 環境變數失敗");
+        let migration = envy::from_env::<Migration>().expect("載入Migration 環境變數失敗");
+        let health = envy::from_env::<Health>().expect("載入Health 環境變數失敗");
+        let wallet_lock = envy::from_env::<WalletLock>().expect("載入WalletLock 環境變數失敗");
+        let game_wallet = envy::from_env::<GameWallet>().expect("載入GameWallet 環境變數失敗");
 
         let config = Config {
             telemetry,
@@ -68,6 +178,15 @@ impl Config {
             wallet_db,
             bank_server,
             rabbitmq,
+            bonus_release,
+            bonus_expiry,
+            outbox,
+            reconciliation,
+            currency_cache,
+            migration,
+            health,
+            wallet_lock,
+            game_wallet,
         };
 
         Arc::new(config)
@@ -93,3 +212,39 @@ pub fn get_bank_server() -> &'static BankServer {
 pub fn get_rabbit() -> &'static RabbitMQ {
     &CONFIG.rabbitmq
 }
+
+pub fn get_bonus_release() -> &'static BonusRelease {
+    &CONFIG.bonus_release
+}
+
+pub fn get_bonus_expiry() -> &'static BonusExpiry {
+    &CONFIG.bonus_expiry
+}
+
+pub fn get_outbox() -> &'static Outbox {
+    &CONFIG.outbox
+}
+
+pub fn get_reconciliation() -> &'static Reconciliation {
+    &CONFIG.reconciliation
+}
+
+pub fn get_currency_cache() -> &'static CurrencyCache {
+    &CONFIG.currency_cache
+}
+
+pub fn get_migration() -> &'static Migration {
+    &CONFIG.migration
+}
+
+pub fn get_health() -> &'static Health {
+    &CONFIG.health
+}
+
+pub fn get_wallet_lock() -> &'static WalletLock {
+    &CONFIG.wallet_lock
+}
+
+pub fn get_game_wallet() -> &'static GameWallet {
+    &CONFIG.game_wallet
+}
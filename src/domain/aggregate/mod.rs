@@ -1,3 +1,9 @@
+mod rollover_record;
 mod user_wallet;
+mod wallet_drift;
+mod wallet_transaction;
 
+pub use rollover_record::*;
 pub use user_wallet::*;
+pub use wallet_drift::*;
+pub use wallet_transaction::*;
@@ -0,0 +1,18 @@
+use bigdecimal::BigDecimal;
+
+use crate::domain::RolloverRecord;
+
+/// 這是由 RolloverRecord 與 WalletTransaction 資料組成的結構
+/// 並帶有依照流水紀錄順序累計的需求流水與達成流水
+pub struct RolloverRecordWithTransaction {
+    // RolloverRecord
+    pub rollover_record: RolloverRecord,
+    // WalletTransaction
+    pub wallet_txn_action: Option<i32>,               // 錢包操作
+    pub wallet_txn_status: Option<i32>,               // 交易狀態
+    pub transaction_source_id: Option<i64>,           // 來源交易 ID
+    pub wallet_txn_change_amount: Option<BigDecimal>, // 交易金額
+    // 累計流水
+    pub total_requirement_rollover: BigDecimal, // 累計到此筆的需求流水
+    pub total_achievement_rollover: BigDecimal, // 累計到此筆的達成流水
+}
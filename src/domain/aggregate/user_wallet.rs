@@ -3,13 +3,14 @@ use bigdecimal::BigDecimal;
 /// 這是由 UserWallet 與 RolloverMain 資料組成的結構
 pub struct UserWalletWithRollover {
     // UserWallet
-    pub id: i64,               // UserWallet ID
-    pub client_id: i64,        // 客戶ID
-    pub user_id: i64,          // 用戶ID
-    pub currency_id: i64,      // 幣別ID
-    pub currency_name: String, // 貨幣名稱
-    pub wallet_source_id: i64, // 錢包來源
-    pub amount: BigDecimal,    // 錢包金額
+    pub id: i64,                 // UserWallet ID
+    pub client_id: i64,          // 客戶ID
+    pub user_id: i64,            // 用戶ID
+    pub currency_id: i64,        // 幣別ID
+    pub currency_name: String,   // 貨幣名稱
+    pub wallet_source_id: i64,   // 錢包來源
+    pub amount: BigDecimal,      // 錢包金額
+    pub hold_amount: BigDecimal, // 凍結金額
     // WalletSource
     pub wallet_source_name: String, // 錢包來源名稱
     // RolloverMain
@@ -0,0 +1,22 @@
+use bigdecimal::BigDecimal;
+
+/// 這是由 UserWallet 與 WalletTransaction 資料組成的對帳結果
+/// 交易紀錄金額為成功交易的金額加總 不包含對帳調整
+pub struct WalletDrift {
+    pub user_wallet_id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub wallet_source_id: i64,
+    pub wallet_amount: BigDecimal,        // 錢包金額
+    pub ledger_amount: BigDecimal,        // 交易紀錄金額
+    pub broken_chain_count: i64,          // 最後一筆對帳調整之後 前後金額不連續的交易紀錄數量
+    pub first_broken_txn_id: Option<i64>, // 第一筆前後金額不連續的交易紀錄 ID
+}
+
+impl WalletDrift {
+    /// 差額 = 錢包金額 - 交易紀錄金額
+    pub fn drift_amount(&self) -> BigDecimal {
+        &self.wallet_amount - &self.ledger_amount
+    }
+}
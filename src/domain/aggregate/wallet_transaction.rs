@@ -0,0 +1,13 @@
+use crate::domain::WalletTransaction;
+
+/// 交易紀錄與其上層交易的 ID
+pub struct WalletTransactionWithParents {
+    pub wallet_txn: WalletTransaction, // 交易紀錄
+    pub parent_ids: Vec<i64>,          // 上層交易 ID 由最近的上層排到原始交易
+}
+
+/// 交易紀錄列表 以交易 ID 作為游標分頁
+pub struct WalletTransactionPage {
+    pub wallet_txn_list: Vec<WalletTransactionWithParents>, // 交易紀錄
+    pub next_cursor: Option<i64>,                           // 下一頁的游標 None 表示沒有下一頁
+}
@@ -0,0 +1,105 @@
+use bigdecimal::BigDecimal;
+
+use crate::domain;
+use crate::enums;
+use crate::infrastructure;
+
+/// 沒收失敗後第一次重試的等待時間 之後每次失敗加倍
+const FORFEIT_RETRY_BASE_SECS: i64 = 60;
+/// 沒收失敗後重試的最長等待時間
+const FORFEIT_RETRY_MAX_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone)]
+pub struct BonusGrant {
+    pub id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub campaign_id: Option<String>, // 活動 ID 由入金發放的獎金沒有活動 ID
+    pub wallet_txn_id: i64,          // 發放獎金的交易紀錄 ID
+    pub rollover_record_id: Option<i64>, // 發放獎金的流水紀錄 ID
+    pub amount: BigDecimal,          // 發放金額
+    pub rollover_rate: BigDecimal,   // 流水倍率
+    pub expire_at: Option<chrono::NaiveDateTime>, // 到期時間 None 表示不會到期
+    pub status: i32,
+    pub forfeit_amount: Option<BigDecimal>, // 到期沒收的金額
+    pub forfeit_txn_id: Option<i64>,        // 到期沒收的交易紀錄 ID 餘額為零時沒有交易紀錄
+    pub forfeit_retry_count: i32,           // 沒收失敗次數
+    pub next_forfeit_at: Option<chrono::NaiveDateTime>, // 沒收失敗後下次重試的時間
+    pub cancel_txn_id: Option<i64>,         // 取消獎金的交易紀錄 ID
+    pub create_by: i64,                     // 發放者 ID
+    pub cancel_by: Option<i64>,             // 取消者 ID
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+impl BonusGrant {
+    /// ### 依照獎金錢包的入金交易與流水紀錄創建有效的獎金
+    pub async fn new(
+        wallet_txn: &domain::WalletTransaction,
+        rollover_record: Option<&domain::RolloverRecord>,
+        terms: domain::BonusTerms,
+    ) -> BonusGrant {
+        let now = chrono::Utc::now().naive_utc();
+
+        BonusGrant {
+            id: infrastructure::snowflake::generate_id().await,
+            client_id: wallet_txn.client_id,
+            user_id: wallet_txn.user_id,
+            currency_id: wallet_txn.currency_id,
+            campaign_id: terms.campaign_id,
+            wallet_txn_id: wallet_txn.id,
+            rollover_record_id: rollover_record.map(|record| record.id),
+            amount: wallet_txn.change_amount.clone(),
+            rollover_rate: terms.rollover_rate,
+            expire_at: terms.expire_at,
+            status: enums::BonusGrantStatus::Active.to_id(),
+            forfeit_amount: None,
+            forfeit_txn_id: None,
+            forfeit_retry_count: 0,
+            next_forfeit_at: None,
+            cancel_txn_id: None,
+            create_by: terms.create_by,
+            cancel_by: None,
+            create_at: now,
+            update_at: now,
+        }
+    }
+
+    /// ### 是否為有效且已到期的獎金
+    pub fn is_expired(&self, now: chrono::NaiveDateTime) -> bool {
+        self.status == enums::BonusGrantStatus::Active.to_id()
+            && self.expire_at.is_some_and(|expire_at| expire_at <= now)
+    }
+
+    /// ### 沒收失敗後下次重試的時間 每次失敗等待時間加倍 最長等待一天
+    pub fn next_forfeit_retry_at(&self, now: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+        let exponent = self.forfeit_retry_count.clamp(0, 20) as u32;
+        let delay_secs = FORFEIT_RETRY_BASE_SECS
+            .saturating_mul(2_i64.pow(exponent))
+            .min(FORFEIT_RETRY_MAX_SECS);
+        now + chrono::Duration::seconds(delay_secs)
+    }
+
+    /// ### 流水達成 獎金已轉入本金錢包
+    pub fn released(&mut self) {
+        self.status = enums::BonusGrantStatus::Released.to_id();
+        self.update_at = chrono::Utc::now().naive_utc();
+    }
+
+    /// ### 到期沒收 紀錄沒收的金額與交易紀錄
+    pub fn forfeited(&mut self, forfeit_amount: BigDecimal, forfeit_txn_id: Option<i64>) {
+        self.status = enums::BonusGrantStatus::Forfeited.to_id();
+        self.forfeit_amount = Some(forfeit_amount);
+        self.forfeit_txn_id = forfeit_txn_id;
+        self.update_at = chrono::Utc::now().naive_utc();
+    }
+
+    /// ### 取消獎金 紀錄取消的交易紀錄與取消者
+    pub fn cancelled(&mut self, cancel_txn_id: i64, cancel_by: i64) {
+        self.status = enums::BonusGrantStatus::Cancelled.to_id();
+        self.cancel_txn_id = Some(cancel_txn_id);
+        self.cancel_by = Some(cancel_by);
+        self.update_at = chrono::Utc::now().naive_utc();
+    }
+}
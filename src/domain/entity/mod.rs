@@ -1,11 +1,21 @@
+mod bonus_grant;
+mod reconciliation_report;
+mod rollover_contribution;
 mod rollover_main;
 mod rollover_record;
 mod user_wallet;
+mod wallet_limit;
+mod wallet_outbox;
 mod wallet_source;
 mod wallet_transaction;
 
+pub use bonus_grant::BonusGrant;
+pub use reconciliation_report::ReconciliationReport;
+pub use rollover_contribution::RolloverContribution;
 pub use rollover_main::RolloverMain;
 pub use rollover_record::*;
 pub use user_wallet::UserWallet;
+pub use wallet_limit::{WalletActionLimit, WalletLimit};
+pub use wallet_outbox::WalletOutbox;
 pub use wallet_source::WalletSource;
 pub use wallet_transaction::WalletTransaction;
@@ -0,0 +1,57 @@
+use bigdecimal::BigDecimal;
+
+use crate::domain;
+use crate::enums;
+use crate::infrastructure;
+
+#[derive(Debug, Clone)]
+pub struct ReconciliationReport {
+    pub id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub wallet_source_id: i64,
+    pub wallet_amount: BigDecimal, // 對帳時的錢包金額
+    pub ledger_amount: BigDecimal, // 對帳時依交易紀錄計算的金額
+    pub drift_amount: BigDecimal,  // 差額 錢包金額 - 交易紀錄金額
+    pub broken_chain_count: i32,   // 前後金額不連續的交易紀錄數量
+    pub first_broken_txn_id: Option<i64>,
+    pub status: i32,
+    pub adjustment_txn_id: Option<i64>, // 調整的交易紀錄 ID
+    pub approve_by: Option<i64>,        // 審核者 ID
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+impl ReconciliationReport {
+    /// ### 依照對帳結果創建待審核的報告
+    pub async fn new(wallet_drift: &domain::WalletDrift) -> ReconciliationReport {
+        let now = chrono::Utc::now().naive_utc();
+
+        ReconciliationReport {
+            id: infrastructure::snowflake::generate_id().await,
+            client_id: wallet_drift.client_id,
+            user_id: wallet_drift.user_id,
+            currency_id: wallet_drift.currency_id,
+            wallet_source_id: wallet_drift.wallet_source_id,
+            wallet_amount: wallet_drift.wallet_amount.clone(),
+            ledger_amount: wallet_drift.ledger_amount.clone(),
+            drift_amount: wallet_drift.drift_amount(),
+            broken_chain_count: wallet_drift.broken_chain_count as i32,
+            first_broken_txn_id: wallet_drift.first_broken_txn_id,
+            status: enums::ReconciliationStatus::Pending.to_id(),
+            adjustment_txn_id: None,
+            approve_by: None,
+            create_at: now,
+            update_at: now,
+        }
+    }
+
+    /// ### 審核通過 紀錄調整的交易紀錄
+    pub fn adjusted(&mut self, adjustment_txn_id: i64, approve_by: i64) {
+        self.status = enums::ReconciliationStatus::Adjusted.to_id();
+        self.adjustment_txn_id = Some(adjustment_txn_id);
+        self.approve_by = Some(approve_by);
+        self.update_at = chrono::Utc::now().naive_utc();
+    }
+}
@@ -0,0 +1,14 @@
+use bigdecimal::BigDecimal;
+
+/// 遊戲流水貢獻比例 每個 client 每個錢包來源 每個遊戲類別或遊戲一筆 沒有設定時表示 100%
+#[derive(Debug, Clone)]
+pub struct RolloverContribution {
+    pub id: i64,
+    pub client_id: i64,
+    pub wallet_source_id: i64,
+    pub game_category: String, // 遊戲類別
+    pub game_id: String,       // 遊戲 ID 空字串表示整個遊戲類別
+    pub weight: BigDecimal,    // 貢獻比例 1 表示 100% 0 表示不計入流水
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
@@ -2,7 +2,7 @@ use bigdecimal::{BigDecimal, Zero};
 
 use crate::{domain, infrastructure};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RolloverMain {
     pub id: i64,
     pub user_wallet_id: i64,
@@ -13,6 +13,7 @@ pub struct RolloverMain {
     pub wallet_source_id: i64,            // 錢包來源id
     pub requirement_rollover: BigDecimal, // 需求流水(有正負號)
     pub achievement_rollover: BigDecimal, // 達成流水(有正負號)
+    pub version: i64,                     // 版本號 每次更新加一 樂觀鎖比對用
     pub create_at: chrono::NaiveDateTime,
     pub update_at: chrono::NaiveDateTime,
 }
@@ -29,6 +30,7 @@ impl RolloverMain {
             wallet_source_id: wallet_info.wallet_source.id,
             requirement_rollover: BigDecimal::zero(),
             achievement_rollover: BigDecimal::zero(),
+            version: 0,
             create_at: chrono::Utc::now().naive_utc(),
             update_at: chrono::Utc::now().naive_utc(),
         }
@@ -5,7 +5,7 @@ use crate::domain::vo;
 use crate::enums::RolloverType;
 use crate::infrastructure;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RolloverRecord {
     pub id: i64,
     pub main_id: i64, // 對應到流水主表的 ID
@@ -15,6 +15,7 @@ pub struct RolloverRecord {
     pub requirement_rollover_rate: BigDecimal, // 因為流水倍率是可被動的 所以需要紀錄計算當下的流水倍率
     pub achievement_rollover: BigDecimal,      // 達成流水(有正負號)
     pub achievement_rollover_rate: BigDecimal, // 因為流水倍率是可被動的 所以需要紀錄計算當下的流水倍率
+    pub contribution_weight: BigDecimal,       // 計算當下的遊戲流水貢獻比例 非遊戲上分的紀錄為 1
     pub create_by: i64,                        // 創建此筆紀錄的user_id
     pub wallet_txn_id: i64,                    // 對應的wallet txn id
     pub create_at: chrono::NaiveDateTime,      // 創建時間
@@ -58,6 +59,7 @@ impl RolloverRecord {
             requirement_rollover_rate,
             achievement_rollover,
             achievement_rollover_rate,
+            contribution_weight: BigDecimal::one(),
             create_by,
             wallet_txn_id,
             create_at: chrono::Utc::now().naive_utc(),
@@ -82,6 +84,7 @@ impl RolloverRecord {
             requirement_rollover_rate: BigDecimal::one(),
             achievement_rollover,
             achievement_rollover_rate: BigDecimal::one(),
+            contribution_weight: BigDecimal::one(),
             create_by,
             wallet_txn_id,
             create_at: chrono::Utc::now().naive_utc(),
@@ -105,6 +108,7 @@ impl RolloverRecord {
             requirement_rollover_rate: self.requirement_rollover_rate,
             achievement_rollover,
             achievement_rollover_rate: self.achievement_rollover_rate,
+            contribution_weight: self.contribution_weight,
             create_by,
             wallet_txn_id,
             create_at: chrono::Utc::now().naive_utc(),
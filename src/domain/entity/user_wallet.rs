@@ -4,7 +4,7 @@ use bigdecimal::Zero;
 use crate::domain;
 use crate::infrastructure;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UserWallet {
     pub id: i64,
     pub client_id: i64,
@@ -14,6 +14,8 @@ pub struct UserWallet {
     pub wallet_source_id: i64,
     pub wallet_source_name: String,
     pub amount: BigDecimal,
+    pub hold_amount: BigDecimal, // 凍結金額 包含在amount內
+    pub version: i64,            // 版本號 每次更新加一 樂觀鎖比對用
 }
 
 impl UserWallet {
@@ -27,6 +29,8 @@ impl UserWallet {
             wallet_source_id: wallet_info.wallet_source.id,
             wallet_source_name: wallet_info.wallet_source.name.clone(),
             amount: BigDecimal::zero(),
+            hold_amount: BigDecimal::zero(),
+            version: 0,
         }
     }
 
@@ -37,4 +41,25 @@ impl UserWallet {
     pub fn withdraw(&mut self, amount: &BigDecimal) {
         self.amount -= amount;
     }
+
+    /// 可用金額 = 錢包金額 - 凍結金額
+    pub fn available_amount(&self) -> BigDecimal {
+        &self.amount - &self.hold_amount
+    }
+
+    /// 凍結金額 錢包金額不變
+    pub fn hold(&mut self, amount: &BigDecimal) {
+        self.hold_amount += amount;
+    }
+
+    /// 確認凍結 扣除錢包金額並解除凍結
+    pub fn commit_hold(&mut self, amount: &BigDecimal) {
+        self.hold_amount -= amount;
+        self.amount -= amount;
+    }
+
+    /// 取消凍結 錢包金額不變
+    pub fn release_hold(&mut self, amount: &BigDecimal) {
+        self.hold_amount -= amount;
+    }
 }
@@ -0,0 +1,27 @@
+use bigdecimal::BigDecimal;
+
+/// 錢包限額 每個 client 每個幣別一筆 沒有設定時表示不限制
+#[derive(Debug, Clone)]
+pub struct WalletLimit {
+    pub id: i64,
+    pub client_id: i64,
+    pub currency_id: i64,
+    pub max_balance: Option<BigDecimal>,           // 錢包餘額上限
+    pub daily_withdraw_limit: Option<BigDecimal>,  // 最近 24 小時出金總額上限
+    pub weekly_withdraw_limit: Option<BigDecimal>, // 最近 7 天出金總額上限
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+/// 單筆交易限額 每個 client 每個幣別 每種錢包操作一筆 沒有設定時表示不限制
+#[derive(Debug, Clone)]
+pub struct WalletActionLimit {
+    pub id: i64,
+    pub client_id: i64,
+    pub currency_id: i64,
+    pub action: i32,
+    pub min_amount: Option<BigDecimal>, // 單筆金額下限
+    pub max_amount: Option<BigDecimal>, // 單筆金額上限
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,78 @@
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::warn;
+
+use crate::domain;
+use crate::enums;
+use crate::infrastructure;
+
+#[derive(Debug, Clone)]
+pub struct WalletOutbox {
+    pub id: i64, // 同時作為事件 ID
+    pub client_id: i64,
+    pub user_id: i64,
+    pub aggregate_id: i64, // 事件對應的資料 ID
+    pub event_type: String,
+    pub event_version: i32,
+    pub payload: String, // 事件內容(JSON)
+    pub status: i32,
+    pub retry_count: i32, // 發送失敗次數
+    pub create_at: chrono::NaiveDateTime,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+}
+
+impl WalletOutbox {
+    /// ### 創建錢包餘額變動事件
+    pub async fn wallet_changed(
+        wallet_txn: &domain::WalletTransaction,
+    ) -> Result<WalletOutbox, KgsStatus> {
+        let id = infrastructure::snowflake::generate_id().await;
+        let event = domain::WalletChangedEvent::new(id, wallet_txn);
+        let payload = serde_json::to_string(&event).map_err(|e| {
+            warn!("序列化錢包事件失敗: {:?}", e);
+            KgsStatus::InternalServerError
+        })?;
+
+        Ok(WalletOutbox {
+            id,
+            client_id: wallet_txn.client_id,
+            user_id: wallet_txn.user_id,
+            aggregate_id: wallet_txn.id,
+            event_type: domain::WalletChangedEvent::EVENT_TYPE.to_string(),
+            event_version: domain::WalletChangedEvent::EVENT_VERSION,
+            payload,
+            status: enums::OutboxStatus::Pending.to_id(),
+            retry_count: 0,
+            create_at: chrono::Utc::now().naive_utc(),
+            publish_at: None,
+        })
+    }
+
+    /// ### 創建獎金到期沒收事件
+    pub async fn bonus_forfeited(grant: &domain::BonusGrant) -> Result<WalletOutbox, KgsStatus> {
+        let id = infrastructure::snowflake::generate_id().await;
+        let event = domain::BonusForfeitedEvent::new(id, grant);
+        let payload = serde_json::to_string(&event).map_err(|e| {
+            warn!("序列化獎金事件失敗: {:?}", e);
+            KgsStatus::InternalServerError
+        })?;
+
+        Ok(WalletOutbox {
+            id,
+            client_id: grant.client_id,
+            user_id: grant.user_id,
+            aggregate_id: grant.id,
+            event_type: domain::BonusForfeitedEvent::EVENT_TYPE.to_string(),
+            event_version: domain::BonusForfeitedEvent::EVENT_VERSION,
+            payload,
+            status: enums::OutboxStatus::Pending.to_id(),
+            retry_count: 0,
+            create_at: chrono::Utc::now().naive_utc(),
+            publish_at: None,
+        })
+    }
+
+    /// ### 發送時使用的 routing key 例如 wallet.changed.v1
+    pub fn routing_key(&self) -> String {
+        format!("{}.v{}", self.event_type, self.event_version)
+    }
+}
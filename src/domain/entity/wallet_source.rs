@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WalletSource {
     pub id: i64,
     pub name: String,
@@ -3,7 +3,7 @@ use crate::enums;
 use crate::infrastructure;
 use bigdecimal::BigDecimal;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WalletTransaction {
     pub id: i64,
     pub parent_id: i64, // 關聯單的id
@@ -17,6 +17,9 @@ pub struct WalletTransaction {
     pub change_amount: BigDecimal,
     pub after_amount: BigDecimal,
     pub status: i32,
+    pub payment: Option<domain::PaymentReference>, // 金流訂單參考 只有出入金的原始交易有
+    pub hold_id: Option<i64>,                      // 凍結單號 只有凍結產生的出金交易有
+    pub combined_leg: bool,                        // 合併錢包從各錢包扣款或入帳的原始交易
     pub create_at: chrono::NaiveDateTime,
     pub update_at: chrono::NaiveDateTime,
 }
@@ -32,12 +35,18 @@ impl WalletTransaction {
     ) -> WalletTransaction {
         let before_amount = before_changed_wallet.amount.clone();
         let after_amount = match action {
-            enums::WalletAction::PaymentDeposit | enums::WalletAction::GameDeposit => {
-                &before_amount + change_amount
-            }
-            enums::WalletAction::PaymentWithdraw | enums::WalletAction::GameWithdraw => {
-                &before_amount - change_amount
-            }
+            enums::WalletAction::PaymentDeposit
+            | enums::WalletAction::GameDeposit
+            | enums::WalletAction::TransferIn
+            | enums::WalletAction::PaymentWithdrawReject
+            | enums::WalletAction::BonusGrant => &before_amount + change_amount,
+            enums::WalletAction::PaymentWithdraw
+            | enums::WalletAction::GameWithdraw
+            | enums::WalletAction::TransferOut
+            | enums::WalletAction::BonusForfeit
+            | enums::WalletAction::BonusCancel => &before_amount - change_amount,
+            // 調整的金額有正負
+            enums::WalletAction::Adjustment => &before_amount + change_amount,
         };
         let now = chrono::Utc::now().naive_utc();
 
@@ -54,6 +63,9 @@ impl WalletTransaction {
             change_amount: change_amount.clone(),
             after_amount,
             status: enums::WalletStatus::Success.to_id(),
+            payment: None,
+            hold_id: None,
+            combined_leg: false,
             create_at: now,
             update_at: now,
         }
@@ -69,12 +81,17 @@ impl WalletTransaction {
     ) -> WalletTransaction {
         let after_amount = after_changed_wallet.amount.clone();
         let before_amount = match action {
-            enums::WalletAction::PaymentDeposit | enums::WalletAction::GameDeposit => {
-                &after_amount - change_amount
-            }
-            enums::WalletAction::PaymentWithdraw | enums::WalletAction::GameWithdraw => {
-                &after_amount + change_amount
-            }
+            enums::WalletAction::PaymentDeposit
+            | enums::WalletAction::GameDeposit
+            | enums::WalletAction::TransferIn
+            | enums::WalletAction::PaymentWithdrawReject
+            | enums::WalletAction::BonusGrant => &after_amount - change_amount,
+            enums::WalletAction::PaymentWithdraw
+            | enums::WalletAction::GameWithdraw
+            | enums::WalletAction::TransferOut
+            | enums::WalletAction::BonusForfeit
+            | enums::WalletAction::BonusCancel => &after_amount + change_amount,
+            enums::WalletAction::Adjustment => &after_amount - change_amount,
         };
         let now = chrono::Utc::now().naive_utc();
 
@@ -91,8 +108,43 @@ impl WalletTransaction {
             change_amount: change_amount.clone(),
             after_amount,
             status: enums::WalletStatus::Success.to_id(),
+            payment: None,
+            hold_id: None,
+            combined_leg: false,
             create_at: now,
             update_at: now,
         }
     }
+
+    /// ### 創建凍結中的交易紀錄 錢包金額尚未修改
+    /// before/after 為凍結當下預計的金額 確認凍結時會以實際金額更新
+    /// 凍結單號記錄在 hold_id 來源交易 ID 與金流訂單相同由系統產生 不與其他出金共用
+    pub async fn create_pending(
+        before_hold_wallet: &domain::UserWallet,
+        hold_id: i64,
+        action: &enums::WalletAction,
+        change_amount: &BigDecimal,
+    ) -> WalletTransaction {
+        let source_txn_id = infrastructure::snowflake::generate_id().await;
+        let mut wallet_txn =
+            Self::create_before_change(before_hold_wallet, 0, source_txn_id, action, change_amount)
+                .await;
+        wallet_txn.status = enums::WalletStatus::Pending.to_id();
+        wallet_txn.hold_id = Some(hold_id);
+        wallet_txn
+    }
+
+    /// ### 確認凍結 以扣款前的錢包更新交易紀錄的金額與狀態
+    pub fn commit(&mut self, before_changed_wallet: &domain::UserWallet) {
+        self.before_amount = before_changed_wallet.amount.clone();
+        self.after_amount = &before_changed_wallet.amount - &self.change_amount;
+        self.status = enums::WalletStatus::Success.to_id();
+        self.update_at = chrono::Utc::now().naive_utc();
+    }
+
+    /// ### 取消凍結
+    pub fn cancel(&mut self) {
+        self.status = enums::WalletStatus::Cancel.to_id();
+        self.update_at = chrono::Utc::now().naive_utc();
+    }
 }
@@ -0,0 +1,40 @@
+use std::fmt::Debug;
+
+use crate::domain;
+use kgs_err::models::status::Status as KgsStatus;
+
+#[tonic::async_trait]
+pub trait BonusGrantRepositoryTrait: Send + Sync + Debug {
+    async fn insert(&self, grant: domain::BonusGrant) -> Result<domain::BonusGrant, KgsStatus>;
+
+    async fn get(&self, client_id: i64, id: i64) -> Result<Option<domain::BonusGrant>, KgsStatus>;
+
+    /// 取得獎金並以 `SELECT ... FOR UPDATE` 鎖定 避免同一筆獎金被重複沒收
+    async fn get_for_update(&self, id: i64) -> Result<Option<domain::BonusGrant>, KgsStatus>;
+
+    /// 取得玩家該幣別所有有效的獎金
+    async fn get_active_list(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        currency_id: i64,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus>;
+
+    /// 依照到期時間順序取得已到期的有效獎金 只用來找出需要沒收的獎金 不鎖定
+    /// 沒收失敗且尚未到下次重試時間的獎金不會取得 避免一直佔用每批的名額
+    async fn get_expired_list(
+        &self,
+        now: chrono::NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus>;
+
+    /// 沒收失敗 增加失敗次數並設定下次重試的時間
+    /// 只更新這兩個欄位 不需要鎖定獎金
+    async fn defer_forfeit(
+        &self,
+        id: i64,
+        next_forfeit_at: chrono::NaiveDateTime,
+    ) -> Result<(), KgsStatus>;
+
+    async fn update(&self, grant: domain::BonusGrant) -> Result<domain::BonusGrant, KgsStatus>;
+}
@@ -1,11 +1,21 @@
+mod bonus_grant;
+mod reconciliation_report;
+mod rollover_contribution;
 mod rollover_main;
 mod rollover_record;
 mod user_wallet;
+mod wallet_limit;
+mod wallet_outbox;
 mod wallet_source;
 mod wallet_transaction;
 
+pub use bonus_grant::BonusGrantRepositoryTrait;
+pub use reconciliation_report::ReconciliationReportRepositoryTrait;
+pub use rollover_contribution::RolloverContributionRepositoryTrait;
 pub use rollover_main::RolloverMainRepositoryTrait;
 pub use rollover_record::RolloverRecordRepositoryTrait;
 pub use user_wallet::UserWalletRepositoryTrait;
+pub use wallet_limit::WalletLimitRepositoryTrait;
+pub use wallet_outbox::WalletOutboxRepositoryTrait;
 pub use wallet_source::WalletSourceRepositoryTrait;
 pub use wallet_transaction::WalletTransactionRepositoryTrait;
@@ -0,0 +1,30 @@
+use std::fmt::Debug;
+
+use crate::domain;
+use kgs_err::models::status::Status as KgsStatus;
+
+#[tonic::async_trait]
+pub trait ReconciliationReportRepositoryTrait: Send + Sync + Debug {
+    async fn insert(
+        &self,
+        report: domain::ReconciliationReport,
+    ) -> Result<domain::ReconciliationReport, KgsStatus>;
+
+    /// 取得報告並以 `SELECT ... FOR UPDATE` 鎖定 避免同一份報告被重複審核
+    async fn get_for_update(
+        &self,
+        client_id: i64,
+        id: i64,
+    ) -> Result<Option<domain::ReconciliationReport>, KgsStatus>;
+
+    /// 取得待審核的報告 對帳時略過已經有待審核報告的錢包
+    async fn get_pending_list(
+        &self,
+        client_id: i64,
+    ) -> Result<Vec<domain::ReconciliationReport>, KgsStatus>;
+
+    async fn update(
+        &self,
+        report: domain::ReconciliationReport,
+    ) -> Result<domain::ReconciliationReport, KgsStatus>;
+}
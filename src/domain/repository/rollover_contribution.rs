@@ -0,0 +1,15 @@
+use std::fmt::Debug;
+
+use crate::domain;
+use kgs_err::models::status::Status as KgsStatus;
+
+#[tonic::async_trait]
+pub trait RolloverContributionRepositoryTrait: Sync + Send + Debug {
+    /// 取得遊戲流水貢獻比例 優先使用遊戲的設定 沒有時使用遊戲類別的設定 都沒有設定時回傳 None
+    async fn get(
+        &self,
+        client_id: i64,
+        wallet_source_id: i64,
+        game: &domain::GameInfo,
+    ) -> Result<Option<domain::RolloverContribution>, KgsStatus>;
+}
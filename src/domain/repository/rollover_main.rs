@@ -10,6 +10,18 @@ pub trait RolloverMainRepositoryTrait: Debug + Sync + Send {
         wallet_info: &vo::WalletInfo,
     ) -> Result<Option<domain::RolloverMain>, KgsStatus>;
 
+    /// 取得流水主表並以 `SELECT ... FOR UPDATE` 鎖定該筆資料 直到交易結束
+    /// 所有會修改流水的流程都必須透過此方法讀取流水主表
+    async fn get_for_update(
+        &self,
+        wallet_info: &vo::WalletInfo,
+    ) -> Result<Option<domain::RolloverMain>, KgsStatus>;
+
+    /// 更新流水主表 只在版本號與讀取時相同時更新 並將版本號加一
+    /// ### 回傳
+    /// - `RolloverMain` - 更新後的流水主表
+    /// - `Err(KgsStatus::ConcurrentUpdateConflict)` - 流水主表已被其他交易修改
+    ///   流水主表一律透過 `get_for_update` 鎖定後才更新 正常流程不會發生 發生時不重試 直接回滾整筆交易
     async fn update(
         &self,
         wallet_info: domain::RolloverMain,
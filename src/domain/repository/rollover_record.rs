@@ -13,4 +13,11 @@ pub trait RolloverRecordRepositoryTrait: Send + Sync + Debug {
     ) -> Result<Option<RolloverRecord>, KgsStatus>;
 
     async fn insert(&self, rollover_record: RolloverRecord) -> Result<RolloverRecord, KgsStatus>;
+
+    /// 依照查詢條件取得流水紀錄與對應的交易紀錄 依照流水紀錄 ID 由新到舊排序
+    /// 累計流水依照流水紀錄 ID 由舊到新計算
+    async fn get_list_with_transaction(
+        &self,
+        select_query: SelectRolloverRecordsQuery,
+    ) -> Result<Vec<RolloverRecordWithTransaction>, KgsStatus>;
 }
@@ -15,13 +15,39 @@ pub trait UserWalletRepositoryTrait: Send + Sync + Debug {
         wallet_info: &vo::WalletInfo,
     ) -> Result<Option<domain::UserWallet>, KgsStatus>;
 
+    /// 取得錢包並以 `SELECT ... FOR UPDATE` 鎖定該筆資料 直到交易結束
+    /// 悲觀鎖模式下 所有會修改錢包金額的流程都必須透過此方法讀取錢包
+    async fn get_for_update(
+        &self,
+        wallet_info: &vo::WalletInfo,
+    ) -> Result<Option<domain::UserWallet>, KgsStatus>;
+
     async fn insert(
         &self,
         user_wallet: domain::UserWallet,
     ) -> Result<domain::UserWallet, KgsStatus>;
 
+    /// 更新錢包 只在版本號與讀取時相同時更新 並將版本號加一
+    /// ### 回傳
+    /// - `UserWallet` - 更新後的錢包
+    /// - `Err(KgsStatus::ConcurrentUpdateConflict)` - 錢包已被其他交易修改
     async fn update(
         &self,
         user_wallet: domain::UserWallet,
     ) -> Result<domain::UserWallet, KgsStatus>;
+
+    /// 取得有錢包的 client ID 列表
+    async fn get_client_id_list(&self) -> Result<Vec<i64>, KgsStatus>;
+
+    /// 比對錢包金額與成功交易紀錄的金額加總 並檢查交易紀錄的前後金額是否連續
+    /// 對帳調整的交易紀錄視為重新起算 只檢查最後一筆調整之後的交易紀錄是否連續
+    /// 只回傳金額不一致或交易紀錄不連續的錢包
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `user_id`: Option<i64> - 只檢查指定的用戶
+    async fn get_drift_list(
+        &self,
+        client_id: i64,
+        user_id: Option<i64>,
+    ) -> Result<Vec<aggregate::WalletDrift>, KgsStatus>;
 }
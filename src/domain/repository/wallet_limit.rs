@@ -0,0 +1,22 @@
+use std::fmt::Debug;
+
+use crate::domain;
+use kgs_err::models::status::Status as KgsStatus;
+
+#[tonic::async_trait]
+pub trait WalletLimitRepositoryTrait: Sync + Send + Debug {
+    /// 取得錢包限額 沒有設定時回傳 None
+    async fn get(
+        &self,
+        client_id: i64,
+        currency_id: i64,
+    ) -> Result<Option<domain::WalletLimit>, KgsStatus>;
+
+    /// 取得單筆交易限額 沒有設定時回傳 None
+    async fn get_action_limit(
+        &self,
+        client_id: i64,
+        currency_id: i64,
+        action: i32,
+    ) -> Result<Option<domain::WalletActionLimit>, KgsStatus>;
+}
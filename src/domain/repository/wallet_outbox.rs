@@ -0,0 +1,25 @@
+use std::fmt::Debug;
+
+use crate::domain;
+use kgs_err::models::status::Status as KgsStatus;
+
+#[tonic::async_trait]
+pub trait WalletOutboxRepositoryTrait: Send + Sync + Debug {
+    async fn insert(
+        &self,
+        wallet_outbox: domain::WalletOutbox,
+    ) -> Result<domain::WalletOutbox, KgsStatus>;
+
+    /// 依照 ID 順序取得待發送的事件 並以 `SELECT ... FOR UPDATE SKIP LOCKED` 鎖定
+    /// 多個服務同時發送時 不會取得同一筆事件
+    async fn get_pending_for_update(
+        &self,
+        limit: u64,
+    ) -> Result<Vec<domain::WalletOutbox>, KgsStatus>;
+
+    /// 將事件標記為已發送
+    async fn mark_published(&self, ids: Vec<i64>) -> Result<(), KgsStatus>;
+
+    /// 增加發送失敗次數
+    async fn increase_retry_count(&self, id: i64) -> Result<(), KgsStatus>;
+}
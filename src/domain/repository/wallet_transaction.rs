@@ -1,19 +1,79 @@
 use std::fmt::Debug;
 
-use crate::domain;
+use bigdecimal::BigDecimal;
+
+use crate::domain::{self, vo};
 use kgs_err::models::status::Status as KgsStatus;
 
 #[tonic::async_trait]
 pub trait WalletTransactionRepositoryTrait: Sync + Send + Debug {
+    async fn get(&self, id: i64) -> Result<Option<domain::WalletTransaction>, KgsStatus>;
+
     async fn insert(
         &self,
         wallet_txn: domain::WalletTransaction,
     ) -> Result<domain::WalletTransaction, KgsStatus>;
 
+    async fn update(
+        &self,
+        wallet_txn: domain::WalletTransaction,
+    ) -> Result<domain::WalletTransaction, KgsStatus>;
+
+    /// 取得 parent_id 指向指定交易的交易紀錄
+    async fn get_child(
+        &self,
+        parent_id: i64,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus>;
+
     async fn get_list_by_transaction_source_id(
         &self,
         client_id: i64,
         user_id: i64,
         source_txn_id: i64,
     ) -> Result<Vec<domain::WalletTransaction>, KgsStatus>;
+
+    /// 依照查詢條件取得交易紀錄 依照交易 ID 由新到舊排序
+    async fn get_list(
+        &self,
+        select_query: &vo::SelectWalletTransactionsQuery,
+        limit: u64,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus>;
+
+    async fn get_list_by_ids(
+        &self,
+        ids: Vec<i64>,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus>;
+
+    /// 依照來源交易 ID 與動作取得原始交易(parent_id = 0)
+    async fn get_root_by_transaction_source_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        source_txn_id: i64,
+        action: i32,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus>;
+
+    /// 依照凍結單號取得凍結產生的出金交易
+    async fn get_by_hold_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        hold_id: i64,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus>;
+
+    /// 依照金流訂單 ID 取得出入金的原始交易
+    async fn get_by_payment_order_id(
+        &self,
+        client_id: i64,
+        payment_order_id: &str,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus>;
+
+    /// 取得指定時間之後的出金總額 包含凍結中的出金 不包含已退回 已回滾與已取消的出金
+    async fn get_withdraw_total(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        currency_id: i64,
+        since: chrono::NaiveDateTime,
+    ) -> Result<BigDecimal, KgsStatus>;
 }
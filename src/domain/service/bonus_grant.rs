@@ -0,0 +1,457 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure;
+
+#[tonic::async_trait]
+pub trait BonusGrantServiceTrait: Send + Sync + Debug {
+    /// 獎金錢包入金時 紀錄發放的獎金與到期時間
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊 非獎金錢包時不做任何處理
+    /// - `wallet_txn`: &WalletTransaction - 入金的交易紀錄
+    /// - `rollover_record`: Option<&RolloverRecord> - 入金的流水紀錄
+    /// - `terms`: BonusTerms - 發放獎金的條件
+    /// ### 回傳
+    /// - `Some(BonusGrant)` - 新增的獎金
+    /// - `None` - 非獎金錢包
+    async fn grant(
+        &self,
+        wallet_info: &WalletInfo,
+        wallet_txn: &WalletTransaction,
+        rollover_record: Option<&RolloverRecord>,
+        terms: BonusTerms,
+    ) -> Result<Option<BonusGrant>, KgsStatus>;
+
+    /// 發放獎金 獎金錢包上分 增加需求流水 並紀錄發放的獎金
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 獎金錢包資訊
+    /// - `amount`: BigDecimal - 發放金額
+    /// - `terms`: BonusTerms - 發放獎金的條件
+    /// ### 回傳
+    /// - `UserWallet` - 更新後的獎金錢包
+    /// - `RolloverMain` - 更新後的流水主表
+    /// - `BonusGrant` - 新增的獎金
+    async fn grant_bonus(
+        &self,
+        wallet_info: &WalletInfo,
+        amount: BigDecimal,
+        terms: BonusTerms,
+    ) -> Result<(UserWallet, RolloverMain, BonusGrant), KgsStatus>;
+
+    /// 取消尚未使用的獎金 扣回發放金額 並還原發放時增加的需求流水
+    /// 發放後獎金錢包有出帳的紀錄 或可用餘額不足發放金額時 視為已使用
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `bonus_grant_id`: i64 - 獎金 ID
+    /// - `cancel_by`: i64 - 取消者 ID
+    /// ### 回傳
+    /// - `UserWallet` - 更新後的獎金錢包
+    /// - `RolloverMain` - 更新後的流水主表
+    /// - `BonusGrant` - 已取消的獎金
+    async fn cancel(
+        &self,
+        client_id: i64,
+        bonus_grant_id: i64,
+        cancel_by: i64,
+    ) -> Result<(UserWallet, RolloverMain, BonusGrant), KgsStatus>;
+
+    /// 取得已到期的有效獎金 沒收失敗且尚未到重試時間的獎金不會取得
+    /// ### 參數
+    /// - `now`: NaiveDateTime - 目前時間
+    /// - `limit`: u64 - 最多取得的筆數
+    async fn get_expired_list(
+        &self,
+        now: chrono::NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<BonusGrant>, KgsStatus>;
+
+    /// 沒收到期的獎金 新增沒收的交易紀錄 並寫入獎金沒收事件
+    /// 沒收金額不超過發放金額與獎金錢包的可用餘額
+    /// 有沒收金額且沒有其他有效的獎金時 以清零流水紀錄清空獎金錢包的流水
+    /// 還有其他有效的獎金時 依照發放的流水紀錄 扣除這筆獎金增加的需求流水
+    /// ### 參數
+    /// - `expired_grant`: &BonusGrant - 已到期的獎金
+    /// - `now`: NaiveDateTime - 目前時間
+    /// ### 回傳
+    /// - `Some(BonusGrant)` - 已沒收的獎金
+    /// - `None` - 獎金已被其他請求處理
+    async fn forfeit(
+        &self,
+        expired_grant: &BonusGrant,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Option<BonusGrant>, KgsStatus>;
+
+    /// 沒收失敗時延後重試 失敗次數越多等待越久
+    /// ### 參數
+    /// - `expired_grant`: &BonusGrant - 沒收失敗的獎金
+    /// - `now`: NaiveDateTime - 目前時間
+    /// ### 回傳
+    /// - `NaiveDateTime` - 下次重試的時間
+    async fn defer_forfeit(
+        &self,
+        expired_grant: &BonusGrant,
+        now: chrono::NaiveDateTime,
+    ) -> Result<chrono::NaiveDateTime, KgsStatus>;
+}
+
+#[derive(Debug)]
+pub struct BonusGrantService {
+    bonus_grant_repo: Arc<dyn BonusGrantRepositoryTrait>,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    outbox_repo: Arc<dyn WalletOutboxRepositoryTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+    currency_service: Arc<dyn CurrencyServiceTrait>,
+    default_ttl: Option<chrono::Duration>, // 未指定到期時間時的有效期限 None 表示不會到期
+}
+
+impl BonusGrantService {
+    pub fn new(
+        bonus_grant_repo: Arc<dyn BonusGrantRepositoryTrait>,
+        wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+        outbox_repo: Arc<dyn WalletOutboxRepositoryTrait>,
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        rollover_service: Arc<dyn RolloverServiceTrait>,
+        currency_service: Arc<dyn CurrencyServiceTrait>,
+        default_ttl: Option<chrono::Duration>,
+    ) -> Self {
+        Self {
+            bonus_grant_repo,
+            wallet_source_repo,
+            outbox_repo,
+            wallet_service,
+            rollover_service,
+            currency_service,
+            default_ttl,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl BonusGrantServiceTrait for BonusGrantService {
+    #[tracing::instrument]
+    async fn grant(
+        &self,
+        wallet_info: &WalletInfo,
+        wallet_txn: &WalletTransaction,
+        rollover_record: Option<&RolloverRecord>,
+        mut terms: BonusTerms,
+    ) -> Result<Option<BonusGrant>, KgsStatus> {
+        if enums::WalletSource::from_id(wallet_info.wallet_source.id)? != enums::WalletSource::Bonus
+        {
+            if terms.expire_at.is_some() {
+                warn!("只有獎金錢包可以設定到期時間");
+                return Err(KgsStatus::InvalidArgument);
+            }
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        terms.expire_at = terms
+            .expire_at
+            .or_else(|| self.default_ttl.map(|ttl| now + ttl));
+        if terms.expire_at.is_some_and(|expire_at| expire_at <= now) {
+            warn!("獎金到期時間 早於目前時間: {:?}", terms.expire_at);
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        let grant = BonusGrant::new(wallet_txn, rollover_record, terms).await;
+        self.bonus_grant_repo.insert(grant).await.map(Some)
+    }
+
+    #[tracing::instrument]
+    async fn grant_bonus(
+        &self,
+        wallet_info: &WalletInfo,
+        amount: BigDecimal,
+        terms: BonusTerms,
+    ) -> Result<(UserWallet, RolloverMain, BonusGrant), KgsStatus> {
+        if enums::WalletSource::from_id(wallet_info.wallet_source.id)? != enums::WalletSource::Bonus
+        {
+            warn!("只能發放至獎金錢包");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 獎金錢包上分 發放獎金沒有上游的交易 ID 由系統產生來源交易 ID
+        let source_txn_id = infrastructure::snowflake::generate_id().await;
+        let (user_wallet, wallet_txn) = self
+            .wallet_service
+            .change_amount(
+                wallet_info,
+                0,
+                source_txn_id,
+                amount.clone(),
+                &enums::WalletAction::BonusGrant,
+            )
+            .await?;
+
+        // 增加需求流水
+        let (rollover_main, rollover_record) = self
+            .rollover_service
+            .change_rollover(
+                user_wallet.id,
+                wallet_info,
+                wallet_txn.id,
+                amount,
+                terms.rollover_rate.clone(),
+                enums::WalletAction::BonusGrant,
+                None,
+                terms.create_by,
+            )
+            .await?;
+
+        let grant = self
+            .grant(wallet_info, &wallet_txn, rollover_record.as_ref(), terms)
+            .await?
+            .ok_or(KgsStatus::InternalServerError)?;
+
+        Ok((user_wallet, rollover_main, grant))
+    }
+
+    #[tracing::instrument]
+    async fn cancel(
+        &self,
+        client_id: i64,
+        bonus_grant_id: i64,
+        cancel_by: i64,
+    ) -> Result<(UserWallet, RolloverMain, BonusGrant), KgsStatus> {
+        let grant = self
+            .bonus_grant_repo
+            .get(client_id, bonus_grant_id)
+            .await?
+            .ok_or(KgsStatus::DataNotFound)?;
+        let wallet_info = self.bonus_wallet_info(&grant).await?;
+
+        // 與出入金相同 先鎖定錢包再鎖定獎金
+        let user_wallet = self
+            .wallet_service
+            .get_or_create_for_update(&wallet_info)
+            .await?;
+        let mut grant = self
+            .bonus_grant_repo
+            .get_for_update(grant.id)
+            .await?
+            .ok_or(KgsStatus::DataNotFound)?;
+
+        if enums::BonusGrantStatus::from_i32(grant.status)? != enums::BonusGrantStatus::Active {
+            warn!("獎金不是有效的狀態 bonus_grant_id: {}", grant.id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+        if user_wallet.available_amount() < grant.amount || self.is_used(&grant).await? {
+            warn!("獎金已被使用 不能取消 bonus_grant_id: {}", grant.id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 扣回發放金額 與發放的交易使用相同的來源交易 ID
+        let grant_txn = self
+            .wallet_service
+            .get_transaction(grant.client_id, grant.user_id, grant.wallet_txn_id)
+            .await?;
+        let (user_wallet, cancel_txn) = self
+            .wallet_service
+            .change_amount(
+                &wallet_info,
+                grant_txn.id,
+                grant_txn.transaction_source_id,
+                grant.amount.clone(),
+                &enums::WalletAction::BonusCancel,
+            )
+            .await?;
+
+        // 還原發放時增加的需求流水
+        let (rollover_main, _) = self
+            .rollover_service
+            .rollback_rollover(
+                user_wallet.id,
+                &wallet_info,
+                grant_txn.id,
+                cancel_txn.id,
+                cancel_by,
+            )
+            .await?;
+
+        grant.cancelled(cancel_txn.id, cancel_by);
+        let grant = self.bonus_grant_repo.update(grant).await?;
+
+        info!(
+            "取消獎金 bonus_grant_id: {}, user_id: {}, amount: {}",
+            grant.id, grant.user_id, grant.amount
+        );
+
+        Ok((user_wallet, rollover_main, grant))
+    }
+
+    #[tracing::instrument]
+    async fn get_expired_list(
+        &self,
+        now: chrono::NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<BonusGrant>, KgsStatus> {
+        self.bonus_grant_repo.get_expired_list(now, limit).await
+    }
+
+    #[tracing::instrument]
+    async fn forfeit(
+        &self,
+        expired_grant: &BonusGrant,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Option<BonusGrant>, KgsStatus> {
+        let wallet_info = self.bonus_wallet_info(expired_grant).await?;
+
+        // 與出入金相同 先鎖定錢包再鎖定獎金 避免與流水達成轉出互相等待
+        let user_wallet = self
+            .wallet_service
+            .get_or_create_for_update(&wallet_info)
+            .await?;
+        let mut grant = match self
+            .bonus_grant_repo
+            .get_for_update(expired_grant.id)
+            .await?
+        {
+            Some(grant) => grant,
+            None => return Err(KgsStatus::DataNotFound),
+        };
+
+        // 其他服務已沒收 或流水達成已轉入本金錢包
+        if !grant.is_expired(now) {
+            return Ok(None);
+        }
+
+        // 其餘的餘額屬於其他獎金或遊戲贏分 凍結中的金額不沒收
+        let available_amount = user_wallet.available_amount().max(BigDecimal::zero());
+        let forfeit_amount = grant.amount.clone().min(available_amount);
+
+        let forfeit_txn_id = if forfeit_amount > BigDecimal::zero() {
+            // 來源交易 ID 使用獎金 ID
+            let (user_wallet, forfeit_txn) = self
+                .wallet_service
+                .change_amount(
+                    &wallet_info,
+                    0,
+                    grant.id,
+                    forfeit_amount.clone(),
+                    &enums::WalletAction::BonusForfeit,
+                )
+                .await?;
+
+            // 還有其他有效的獎金時 其他獎金的流水仍需要保留 只扣除這筆獎金發放時增加的需求流水
+            let has_other_grant = self
+                .bonus_grant_repo
+                .get_active_list(grant.client_id, grant.user_id, grant.currency_id)
+                .await?
+                .iter()
+                .any(|other| other.id != grant.id);
+            if has_other_grant {
+                // 系統沒收 操作者 ID 為 0
+                self.rollover_service
+                    .rollback_rollover(
+                        user_wallet.id,
+                        &wallet_info,
+                        grant.wallet_txn_id,
+                        forfeit_txn.id,
+                        0,
+                    )
+                    .await?;
+            } else {
+                // 系統沒收 操作者 ID 為 0
+                self.rollover_service
+                    .change_rollover(
+                        user_wallet.id,
+                        &wallet_info,
+                        forfeit_txn.id,
+                        forfeit_amount.clone(),
+                        BigDecimal::zero(),
+                        enums::WalletAction::BonusForfeit,
+                        None,
+                        0,
+                    )
+                    .await?;
+            }
+
+            Some(forfeit_txn.id)
+        } else {
+            None
+        };
+
+        grant.forfeited(forfeit_amount.clone(), forfeit_txn_id);
+        let grant = self.bonus_grant_repo.update(grant).await?;
+
+        // 在同一個交易內寫入事件 交易回滾時事件也不會發送
+        let outbox = WalletOutbox::bonus_forfeited(&grant).await?;
+        self.outbox_repo.insert(outbox).await?;
+
+        info!(
+            "獎金到期沒收 bonus_grant_id: {}, user_id: {}, amount: {}",
+            grant.id, grant.user_id, forfeit_amount
+        );
+
+        Ok(Some(grant))
+    }
+
+    #[tracing::instrument]
+    async fn defer_forfeit(
+        &self,
+        expired_grant: &BonusGrant,
+        now: chrono::NaiveDateTime,
+    ) -> Result<chrono::NaiveDateTime, KgsStatus> {
+        let next_forfeit_at = expired_grant.next_forfeit_retry_at(now);
+        self.bonus_grant_repo
+            .defer_forfeit(expired_grant.id, next_forfeit_at)
+            .await?;
+
+        Ok(next_forfeit_at)
+    }
+}
+
+impl BonusGrantService {
+    /// 組裝獎金所屬獎金錢包的WalletInfo
+    #[tracing::instrument]
+    async fn bonus_wallet_info(&self, grant: &BonusGrant) -> Result<WalletInfo, KgsStatus> {
+        Ok(WalletInfo {
+            client_id: grant.client_id,
+            user_id: grant.user_id,
+            currency: self
+                .currency_service
+                .get_enable_currency_by_id(grant.client_id, grant.currency_id)
+                .await?,
+            wallet_source: self
+                .wallet_source_repo
+                .get(enums::WalletSource::Bonus.to_id())
+                .await?,
+        })
+    }
+
+    /// 發放後獎金錢包是否有出帳的紀錄 包含凍結中的出金
+    #[tracing::instrument]
+    async fn is_used(&self, grant: &BonusGrant) -> Result<bool, KgsStatus> {
+        let page = self
+            .wallet_service
+            .get_transaction_list(SelectWalletTransactionsQuery {
+                client_id: grant.client_id,
+                player_ids: vec![grant.user_id],
+                currency_ids: vec![grant.currency_id],
+                wallet_source_ids: vec![enums::WalletSource::Bonus.to_id()],
+                actions: vec![
+                    enums::WalletAction::GameWithdraw.to_id(),
+                    enums::WalletAction::PaymentWithdraw.to_id(),
+                    enums::WalletAction::TransferOut.to_id(),
+                    enums::WalletAction::BonusForfeit.to_id(),
+                    enums::WalletAction::BonusCancel.to_id(),
+                ],
+                statuses: vec![],
+                start_time: Some(grant.create_at),
+                end_time: None,
+                cursor: None,
+                page_size: 1,
+            })
+            .await?;
+
+        Ok(!page.wallet_txn_list.is_empty())
+    }
+}
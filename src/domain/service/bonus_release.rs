@@ -0,0 +1,210 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing};
+
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure;
+
+#[tonic::async_trait]
+pub trait BonusReleaseServiceTrait: Send + Sync + Debug {
+    /// 獎金錢包流水達成時會轉入本金錢包 需要同時鎖定本金與獎金錢包
+    /// 修改獎金錢包前先呼叫 依照錢包來源 ID 的順序鎖定 與轉帳的鎖定順序一致 避免死結
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊 非獎金錢包時不做任何處理
+    async fn lock_wallets(&self, wallet_info: &WalletInfo) -> Result<(), KgsStatus>;
+
+    /// 獎金錢包流水達成時 將獎金錢包餘額轉入本金錢包
+    /// 轉出時會清空獎金錢包的流水 並新增一筆清零流水紀錄 有效的獎金標記為已轉入本金錢包
+    /// 超過單次轉出上限的餘額 留在獎金錢包
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊 非獎金錢包時不做任何處理
+    /// - `change_by`: i64 - 操作者 ID
+    /// ### 回傳
+    /// - `Some(TransferResult)` - 已轉入本金錢包
+    /// - `None` - 非獎金錢包 或流水尚未達成
+    async fn release_if_achieved(
+        &self,
+        wallet_info: &WalletInfo,
+        change_by: i64,
+    ) -> Result<Option<TransferResult>, KgsStatus>;
+}
+
+#[derive(Debug)]
+pub struct BonusReleaseService {
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+    main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait>,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    bonus_grant_repo: Arc<dyn BonusGrantRepositoryTrait>,
+    max_release_amount: Option<BigDecimal>, // 單次轉入本金錢包的上限 None 表示不限制
+}
+
+impl BonusReleaseService {
+    pub fn new(
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        rollover_service: Arc<dyn RolloverServiceTrait>,
+        main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait>,
+        wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+        bonus_grant_repo: Arc<dyn BonusGrantRepositoryTrait>,
+        max_release_amount: Option<BigDecimal>,
+    ) -> Self {
+        Self {
+            wallet_service,
+            rollover_service,
+            main_rollover_repo,
+            wallet_source_repo,
+            bonus_grant_repo,
+            max_release_amount,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl BonusReleaseServiceTrait for BonusReleaseService {
+    #[tracing::instrument]
+    async fn lock_wallets(&self, wallet_info: &WalletInfo) -> Result<(), KgsStatus> {
+        if enums::WalletSource::from_id(wallet_info.wallet_source.id)? != enums::WalletSource::Bonus
+        {
+            return Ok(());
+        }
+
+        // 本金錢包的來源 ID 較小 先鎖定本金錢包
+        let normal_wallet_info = self.normal_wallet_info(wallet_info).await?;
+        self.wallet_service
+            .get_or_create_for_update(&normal_wallet_info)
+            .await?;
+        self.wallet_service
+            .get_or_create_for_update(wallet_info)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    async fn release_if_achieved(
+        &self,
+        wallet_info: &WalletInfo,
+        change_by: i64,
+    ) -> Result<Option<TransferResult>, KgsStatus> {
+        if enums::WalletSource::from_id(wallet_info.wallet_source.id)? != enums::WalletSource::Bonus
+        {
+            return Ok(None);
+        }
+
+        // 先依照錢包來源 ID 的順序鎖定兩個錢包 再鎖定流水主表
+        // 呼叫端已呼叫過 lock_wallets 時 同一個交易重複鎖定不會等待
+        self.lock_wallets(wallet_info).await?;
+        let bonus_wallet = self
+            .wallet_service
+            .get_or_create_new_one(wallet_info)
+            .await?;
+
+        // 獲取流水主表 並鎖定至交易結束
+        let rollover_main = match self.main_rollover_repo.get_for_update(wallet_info).await? {
+            Some(rollover_main) => rollover_main,
+            None => return Ok(None),
+        };
+
+        // 沒有需求流水代表沒有需要解鎖的獎金
+        if rollover_main.requirement_rollover <= BigDecimal::zero()
+            || rollover_main.achievement_rollover < rollover_main.requirement_rollover
+        {
+            return Ok(None);
+        }
+
+        // 計算轉入本金錢包的金額 凍結中的金額不轉出
+        let available_amount = bonus_wallet.available_amount();
+        let release_amount = match &self.max_release_amount {
+            Some(max_release_amount) if &available_amount > max_release_amount => {
+                max_release_amount.clone()
+            }
+            _ => available_amount.clone(),
+        };
+        if release_amount <= BigDecimal::zero() {
+            return Ok(None);
+        }
+
+        let normal_wallet_info = self.normal_wallet_info(wallet_info).await?;
+
+        // 獎金錢包轉入本金錢包
+        let source_txn_id = infrastructure::snowflake::generate_id().await;
+        let transfer_result = self
+            .wallet_service
+            .transfer(
+                wallet_info,
+                &normal_wallet_info,
+                source_txn_id,
+                release_amount.clone(),
+            )
+            .await?;
+
+        // 轉出時清空獎金錢包流水
+        self.rollover_service
+            .change_rollover(
+                transfer_result.from_wallet.id,
+                wallet_info,
+                transfer_result.out_wallet_txn.id,
+                release_amount.clone(),
+                BigDecimal::zero(),
+                enums::WalletAction::TransferOut,
+                None,
+                change_by,
+            )
+            .await?;
+
+        // 轉入本金錢包
+        self.rollover_service
+            .change_rollover(
+                transfer_result.to_wallet.id,
+                &normal_wallet_info,
+                transfer_result.in_wallet_txn.id,
+                release_amount.clone(),
+                BigDecimal::zero(),
+                enums::WalletAction::TransferIn,
+                None,
+                change_by,
+            )
+            .await?;
+
+        // 流水已清空 獎金不再到期沒收
+        for mut grant in self
+            .bonus_grant_repo
+            .get_active_list(
+                wallet_info.client_id,
+                wallet_info.user_id,
+                wallet_info.currency.id,
+            )
+            .await?
+        {
+            grant.released();
+            self.bonus_grant_repo.update(grant).await?;
+        }
+
+        info!(
+            "獎金錢包流水達成 轉入本金錢包 user_id: {}, amount: {}",
+            wallet_info.user_id, release_amount
+        );
+
+        Ok(Some(transfer_result))
+    }
+}
+
+impl BonusReleaseService {
+    /// 組裝同一玩家 同幣別本金錢包的WalletInfo
+    #[tracing::instrument]
+    async fn normal_wallet_info(&self, wallet_info: &WalletInfo) -> Result<WalletInfo, KgsStatus> {
+        Ok(WalletInfo {
+            client_id: wallet_info.client_id,
+            user_id: wallet_info.user_id,
+            currency: wallet_info.currency.clone(),
+            wallet_source: self
+                .wallet_source_repo
+                .get(enums::WalletSource::Normal.to_id())
+                .await?,
+        })
+    }
+}
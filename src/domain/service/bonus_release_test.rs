@@ -0,0 +1,203 @@
+//! 以記憶體資料庫測試獎金錢包流水達成時轉入本金錢包
+
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure::memory_impl::*;
+
+const CLIENT_ID: i64 = 1;
+const USER_ID: i64 = 1;
+
+struct Fixture {
+    db: Arc<MemoryDatabase>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    rollover_service: Arc<dyn RolloverServiceTrait>,
+    bonus_release_service: BonusReleaseService,
+    bonus_wallet_info: WalletInfo,
+}
+
+impl Fixture {
+    fn new(max_release_amount: Option<i64>) -> Self {
+        let db = MemoryDatabase::new();
+        let currency = Currency {
+            id: 1,
+            name: "TWD".to_string(),
+        };
+        let wallet_source = |wallet_source: enums::WalletSource, name: &str| {
+            let wallet_source = WalletSource {
+                id: wallet_source.to_id(),
+                name: name.to_string(),
+                create_at: chrono::Utc::now().naive_utc(),
+            };
+            db.insert_wallet_source(wallet_source.clone());
+            wallet_source
+        };
+        wallet_source(enums::WalletSource::Normal, "normal");
+        let bonus_wallet_info = WalletInfo {
+            client_id: CLIENT_ID,
+            user_id: USER_ID,
+            currency,
+            wallet_source: wallet_source(enums::WalletSource::Bonus, "bonus"),
+        };
+
+        let main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait> =
+            Arc::new(MemoryRolloverMainRepository::new(db.clone()));
+        let wallet_service: Arc<dyn WalletServiceTrait> = Arc::new(WalletService::new(
+            Arc::new(MemoryWalletTransactionRepository::new(db.clone())),
+            Arc::new(MemoryUserWalletRepository::new(db.clone())),
+            Arc::new(MemoryWalletOutboxRepository::new(db.clone())),
+            LockStrategy::Pessimistic,
+        ));
+        let rollover_service: Arc<dyn RolloverServiceTrait> = Arc::new(RolloverService::new(
+            main_rollover_repo.clone(),
+            Arc::new(MemoryRolloverRecordRepository::new(db.clone())),
+            Arc::new(MemoryRolloverContributionRepository::new(db.clone())),
+        ));
+
+        Self {
+            bonus_release_service: BonusReleaseService::new(
+                wallet_service.clone(),
+                rollover_service.clone(),
+                main_rollover_repo,
+                Arc::new(MemoryWalletSourceRepository::new(db.clone())),
+                Arc::new(MemoryBonusGrantRepository::new(db.clone())),
+                max_release_amount.map(BigDecimal::from),
+            ),
+            wallet_service,
+            rollover_service,
+            bonus_wallet_info,
+            db,
+        }
+    }
+
+    /// 獎金錢包上分並修改流水
+    async fn change_bonus_wallet(
+        &self,
+        source_txn_id: i64,
+        amount: i64,
+        action: enums::WalletAction,
+    ) {
+        self.db
+            .transaction(|| async move {
+                let (user_wallet, wallet_txn) = self
+                    .wallet_service
+                    .change_amount(
+                        &self.bonus_wallet_info,
+                        0,
+                        source_txn_id,
+                        BigDecimal::from(amount),
+                        &action,
+                    )
+                    .await?;
+                self.rollover_service
+                    .change_rollover(
+                        user_wallet.id,
+                        &self.bonus_wallet_info,
+                        wallet_txn.id,
+                        BigDecimal::from(amount),
+                        BigDecimal::from(1),
+                        action,
+                        None,
+                        USER_ID,
+                    )
+                    .await
+            })
+            .await
+            .unwrap();
+    }
+
+    fn wallet_amount(&self, wallet_source: enums::WalletSource) -> BigDecimal {
+        self.db.read(|tables| {
+            tables
+                .user_wallets
+                .iter()
+                .find(|wallet| wallet.wallet_source_id == wallet_source.to_id())
+                .map(|wallet| wallet.amount.clone())
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[tokio::test]
+async fn release_not_achieved() {
+    let fixture = Fixture::new(None);
+    fixture
+        .change_bonus_wallet(1, 100, enums::WalletAction::PaymentDeposit)
+        .await;
+
+    let result = fixture
+        .db
+        .transaction(|| {
+            fixture
+                .bonus_release_service
+                .release_if_achieved(&fixture.bonus_wallet_info, USER_ID)
+        })
+        .await
+        .unwrap();
+
+    assert!(result.is_none());
+    assert_eq!(
+        fixture.wallet_amount(enums::WalletSource::Bonus),
+        BigDecimal::from(100)
+    );
+}
+
+#[tokio::test]
+async fn release_over_max_amount_keeps_remainder() {
+    let fixture = Fixture::new(Some(150));
+    // 需求流水 100 達成流水 100 獎金錢包餘額 200
+    fixture
+        .change_bonus_wallet(1, 100, enums::WalletAction::PaymentDeposit)
+        .await;
+    fixture
+        .change_bonus_wallet(2, 100, enums::WalletAction::GameDeposit)
+        .await;
+
+    let transfer_result = fixture
+        .db
+        .transaction(|| {
+            fixture
+                .bonus_release_service
+                .release_if_achieved(&fixture.bonus_wallet_info, USER_ID)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // 超過上限的餘額留在獎金錢包 不沒收
+    assert_eq!(
+        fixture.wallet_amount(enums::WalletSource::Bonus),
+        BigDecimal::from(50)
+    );
+    assert_eq!(
+        fixture.wallet_amount(enums::WalletSource::Normal),
+        BigDecimal::from(150)
+    );
+    fixture.db.read(|tables| {
+        assert!(!tables
+            .wallet_txns
+            .iter()
+            .any(|txn| txn.action == enums::WalletAction::BonusForfeit.to_id()));
+        let rollover_main = tables
+            .rollover_mains
+            .iter()
+            .find(|main| main.wallet_source_id == enums::WalletSource::Bonus.to_id())
+            .unwrap();
+        assert!(rollover_main.requirement_rollover.is_zero());
+    });
+
+    // 轉帳的交易鏈沒有分岔 可以找到最後一筆交易
+    let last_wallet_txn = fixture
+        .wallet_service
+        .get_last_transaction_by_source_id(
+            CLIENT_ID,
+            USER_ID,
+            transfer_result.out_wallet_txn.transaction_source_id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(last_wallet_txn.id, transfer_result.in_wallet_txn.id);
+}
@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use std::vec;
 
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{info, tracing};
 
 use crate::domain;
 use crate::enums;
@@ -27,16 +30,91 @@ pub trait CurrencyServiceTrait: Send + Sync + Debug {
         client_id: i64,
         currency_id: i64,
     ) -> Result<domain::Currency, KgsStatus>;
+
+    /// 清除幣別快取 幣別啟用狀態變更時呼叫
+    /// ### 參數
+    /// - `client_id`: Option<i64> - 只清除指定 client 的快取 None 表示全部清除
+    fn invalidate(&self, client_id: Option<i64>);
+}
+
+/// 啟用中幣別的快取 只快取查詢成功的結果
+#[derive(Debug, Default)]
+struct CurrencyCache {
+    by_name: HashMap<(i64, String), (domain::Currency, Instant)>,
+    by_id: HashMap<(i64, i64), (domain::Currency, Instant)>,
+}
+
+impl CurrencyCache {
+    /// 移除已過期的快取 沒有再被查詢的幣別不會一直留在快取中
+    fn prune(&mut self, now: Instant) {
+        self.by_name.retain(|_, (_, expire_at)| *expire_at > now);
+        self.by_id.retain(|_, (_, expire_at)| *expire_at > now);
+    }
+
+    fn insert(&mut self, client_id: i64, currency: &domain::Currency, expire_at: Instant) {
+        self.by_name.insert(
+            (client_id, currency.name.clone()),
+            (currency.clone(), expire_at),
+        );
+        self.by_id
+            .insert((client_id, currency.id), (currency.clone(), expire_at));
+    }
 }
 
 #[derive(Debug)]
-pub struct CurrencyService;
+pub struct CurrencyService {
+    cache_ttl: Duration, // 為 0 時不使用快取
+    cache: Mutex<CurrencyCache>,
+}
 
-/// 實作CurrencyServiceTrait
-#[tonic::async_trait]
-impl CurrencyServiceTrait for CurrencyService {
-    #[tracing::instrument]
-    async fn get_enable_currencies(
+impl CurrencyService {
+    pub fn new(cache_ttl: Duration) -> Self {
+        Self {
+            cache_ttl,
+            cache: Mutex::new(CurrencyCache::default()),
+        }
+    }
+
+    fn get_cache_by_name(&self, client_id: i64, currency_name: &str) -> Option<domain::Currency> {
+        let cache = self.cache.lock().unwrap();
+        let result = cache
+            .by_name
+            .get(&(client_id, currency_name.to_string()))
+            .filter(|(_, expire_at)| *expire_at > Instant::now())
+            .map(|(currency, _)| currency.clone());
+
+        infrastructure::metrics::record_currency_cache(result.is_some());
+        result
+    }
+
+    fn get_cache_by_id(&self, client_id: i64, currency_id: i64) -> Option<domain::Currency> {
+        let cache = self.cache.lock().unwrap();
+        let result = cache
+            .by_id
+            .get(&(client_id, currency_id))
+            .filter(|(_, expire_at)| *expire_at > Instant::now())
+            .map(|(currency, _)| currency.clone());
+
+        infrastructure::metrics::record_currency_cache(result.is_some());
+        result
+    }
+
+    fn set_cache(&self, client_id: i64, currencies: &[domain::Currency]) {
+        if self.cache_ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let expire_at = now + self.cache_ttl;
+        let mut cache = self.cache.lock().unwrap();
+        cache.prune(now);
+        for currency in currencies {
+            cache.insert(client_id, currency, expire_at);
+        }
+    }
+
+    /// 向 bank server 查詢啟用中的幣別 並寫入快取
+    async fn fetch_enable_currencies(
         &self,
         client_id: i64,
         currency_names: Vec<String>,
@@ -49,7 +127,7 @@ impl CurrencyServiceTrait for CurrencyService {
         )
         .await?;
 
-        let currencies = res
+        let currencies: Vec<domain::Currency> = res
             .currency
             .into_iter()
             .map(|x| domain::Currency {
@@ -57,9 +135,35 @@ impl CurrencyServiceTrait for CurrencyService {
                 name: x.name,
             })
             .collect();
+        self.set_cache(client_id, &currencies);
 
         Ok(currencies)
     }
+}
+
+/// 實作CurrencyServiceTrait
+#[tonic::async_trait]
+impl CurrencyServiceTrait for CurrencyService {
+    #[tracing::instrument]
+    async fn get_enable_currencies(
+        &self,
+        client_id: i64,
+        currency_names: Vec<String>,
+    ) -> Result<Vec<domain::Currency>, KgsStatus> {
+        // 指定的幣別都在快取中時 不需要呼叫 bank server
+        if !currency_names.is_empty() {
+            let cached: Vec<domain::Currency> = currency_names
+                .iter()
+                .filter_map(|name| self.get_cache_by_name(client_id, name))
+                .collect();
+            if cached.len() == currency_names.len() {
+                return Ok(cached);
+            }
+        }
+
+        self.fetch_enable_currencies(client_id, currency_names)
+            .await
+    }
 
     #[tracing::instrument]
     async fn get_enable_currency(
@@ -67,7 +171,12 @@ impl CurrencyServiceTrait for CurrencyService {
         client_id: i64,
         currency_name: &str,
     ) -> Result<domain::Currency, KgsStatus> {
-        self.get_enable_currencies(client_id, vec![currency_name.to_string()])
+        if let Some(currency) = self.get_cache_by_name(client_id, currency_name) {
+            return Ok(currency);
+        }
+
+        // 快取已經查過一次 直接向 bank server 查詢 避免重複記錄未命中
+        self.fetch_enable_currencies(client_id, vec![currency_name.to_string()])
             .await?
             .into_iter()
             .next()
@@ -80,6 +189,10 @@ impl CurrencyServiceTrait for CurrencyService {
         client_id: i64,
         currency_id: i64,
     ) -> Result<domain::Currency, KgsStatus> {
+        if let Some(currency) = self.get_cache_by_id(client_id, currency_id) {
+            return Ok(currency);
+        }
+
         let res =
             infrastructure::bank_server::get_client_currency_by_id(client_id, currency_id).await?;
 
@@ -87,9 +200,28 @@ impl CurrencyServiceTrait for CurrencyService {
             return Err(KgsStatus::NotFound);
         }
 
-        Ok(domain::Currency {
+        let currency = domain::Currency {
             id: res.id,
             name: res.name,
-        })
+        };
+        self.set_cache(client_id, &[currency.clone()]);
+
+        Ok(currency)
+    }
+
+    #[tracing::instrument]
+    fn invalidate(&self, client_id: Option<i64>) {
+        let mut cache = self.cache.lock().unwrap();
+        match client_id {
+            Some(client_id) => {
+                cache.by_name.retain(|(id, _), _| *id != client_id);
+                cache.by_id.retain(|(id, _), _| *id != client_id);
+            }
+            None => {
+                cache.by_name.clear();
+                cache.by_id.clear();
+            }
+        }
+        info!("currency cache invalidated, client_id: {:?}", client_id);
     }
 }
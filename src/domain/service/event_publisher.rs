@@ -0,0 +1,12 @@
+use std::fmt::Debug;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+#[tonic::async_trait]
+pub trait EventPublisherTrait: Send + Sync + Debug {
+    /// 發送事件
+    /// ### 參數
+    /// - `routing_key`: &str - 事件的 routing key
+    /// - `payload`: &str - 事件內容(JSON)
+    async fn publish(&self, routing_key: &str, payload: &str) -> Result<(), KgsStatus>;
+}
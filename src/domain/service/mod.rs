@@ -1,7 +1,25 @@
+mod bonus_grant;
+mod bonus_release;
+#[cfg(test)]
+mod bonus_release_test;
 mod currency;
+mod event_publisher;
+mod reconciliation;
 mod rollover;
+#[cfg(test)]
+mod rollover_test;
+mod wallet_limit;
+#[cfg(test)]
+mod wallet_limit_test;
 mod wallet_service;
+#[cfg(test)]
+mod wallet_service_test;
 
+pub use bonus_grant::*;
+pub use bonus_release::*;
 pub use currency::*;
+pub use event_publisher::*;
+pub use reconciliation::*;
 pub use rollover::*;
+pub use wallet_limit::*;
 pub use wallet_service::*;
@@ -0,0 +1,179 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use bigdecimal::Zero;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+
+use crate::domain::*;
+use crate::enums;
+
+#[tonic::async_trait]
+pub trait ReconciliationServiceTrait: Send + Sync + Debug {
+    /// 對帳 比對錢包金額與交易紀錄 並為有差異的錢包建立待審核的報告
+    /// 已經有待審核報告的錢包不重複建立
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// ### 回傳
+    /// - `Vec<ReconciliationReport>` - 新建立的報告
+    async fn reconcile(&self, client_id: i64) -> Result<Vec<ReconciliationReport>, KgsStatus>;
+
+    /// 審核報告 新增一筆調整的交易紀錄 將錢包金額修正為交易紀錄的金額
+    /// 調整的交易紀錄也是前後金額連續性檢查的起點
+    /// 只有前後金額不連續的報告沒有金額需要修正 由人工確認後審核 新增一筆金額為 0 的調整重新起算
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `report_id`: i64 - 報告 ID
+    /// - `approve_by`: i64 - 審核者 ID
+    /// ### 回傳
+    /// - `ReconciliationReport` - 更新後的報告
+    /// - `WalletTransaction` - 調整的交易紀錄
+    async fn approve(
+        &self,
+        client_id: i64,
+        report_id: i64,
+        approve_by: i64,
+    ) -> Result<(ReconciliationReport, WalletTransaction), KgsStatus>;
+}
+
+#[derive(Debug)]
+pub struct ReconciliationService {
+    user_wallet_repo: Arc<dyn UserWalletRepositoryTrait>,
+    report_repo: Arc<dyn ReconciliationReportRepositoryTrait>,
+    wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+    currency_service: Arc<dyn CurrencyServiceTrait>,
+}
+
+impl ReconciliationService {
+    pub fn new(
+        user_wallet_repo: Arc<dyn UserWalletRepositoryTrait>,
+        report_repo: Arc<dyn ReconciliationReportRepositoryTrait>,
+        wallet_source_repo: Arc<dyn WalletSourceRepositoryTrait>,
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        currency_service: Arc<dyn CurrencyServiceTrait>,
+    ) -> Self {
+        Self {
+            user_wallet_repo,
+            report_repo,
+            wallet_source_repo,
+            wallet_service,
+            currency_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ReconciliationServiceTrait for ReconciliationService {
+    #[tracing::instrument]
+    async fn reconcile(&self, client_id: i64) -> Result<Vec<ReconciliationReport>, KgsStatus> {
+        let drift_list = self
+            .user_wallet_repo
+            .get_drift_list(client_id, None)
+            .await?;
+
+        // 已經有待審核報告的錢包 等審核後再重新對帳
+        let pending_list = self.report_repo.get_pending_list(client_id).await?;
+
+        let mut report_list = Vec::with_capacity(drift_list.len());
+        for wallet_drift in drift_list.iter() {
+            if pending_list.iter().any(|report| {
+                report.user_id == wallet_drift.user_id
+                    && report.currency_id == wallet_drift.currency_id
+                    && report.wallet_source_id == wallet_drift.wallet_source_id
+            }) {
+                continue;
+            }
+
+            warn!(
+                "錢包對帳不一致 user_wallet_id: {}, drift_amount: {}, broken_chain_count: {}",
+                wallet_drift.user_wallet_id,
+                wallet_drift.drift_amount(),
+                wallet_drift.broken_chain_count
+            );
+            let report = ReconciliationReport::new(wallet_drift).await;
+            report_list.push(self.report_repo.insert(report).await?);
+        }
+
+        info!(
+            "錢包對帳完成 client_id: {}, 不一致的錢包數量: {}",
+            client_id,
+            report_list.len()
+        );
+        Ok(report_list)
+    }
+
+    #[tracing::instrument]
+    async fn approve(
+        &self,
+        client_id: i64,
+        report_id: i64,
+        approve_by: i64,
+    ) -> Result<(ReconciliationReport, WalletTransaction), KgsStatus> {
+        let mut report = self
+            .report_repo
+            .get_for_update(client_id, report_id)
+            .await?
+            .ok_or(KgsStatus::DataNotFound)?;
+
+        if enums::ReconciliationStatus::from_i32(report.status)?
+            != enums::ReconciliationStatus::Pending
+        {
+            warn!("對帳報告不是待審核的狀態 report_id: {}", report_id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+        if report.drift_amount.is_zero() && report.broken_chain_count == 0 {
+            warn!("對帳報告沒有差異 report_id: {}", report_id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+        if report.drift_amount.is_zero() {
+            info!(
+                "對帳報告只有前後金額不連續 新增金額為 0 的調整重新起算 report_id: {}",
+                report_id
+            );
+        }
+
+        let wallet_info = WalletInfo {
+            client_id: report.client_id,
+            user_id: report.user_id,
+            currency: self
+                .currency_service
+                .get_enable_currency_by_id(report.client_id, report.currency_id)
+                .await?,
+            wallet_source: self.wallet_source_repo.get(report.wallet_source_id).await?,
+        };
+
+        // 調整的金額為差額的相反數 只有前後金額不連續時為 0 來源交易 ID 使用報告 ID
+        let (_, adjustment_txn) = self
+            .wallet_service
+            .change_amount(
+                &wallet_info,
+                0,
+                report.id,
+                -report.drift_amount.clone(),
+                &enums::WalletAction::Adjustment,
+            )
+            .await?;
+
+        // 對帳後差額又發生變化時 調整後仍不一致 交易回滾 需要重新對帳
+        // 調整後重新起算連續性 仍有不連續的紀錄表示調整之後又有新的問題
+        let still_drifted = self
+            .user_wallet_repo
+            .get_drift_list(report.client_id, Some(report.user_id))
+            .await?
+            .iter()
+            .any(|drift| {
+                drift.currency_id == report.currency_id
+                    && drift.wallet_source_id == report.wallet_source_id
+            });
+        if still_drifted {
+            warn!("對帳報告的差額已變更 需要重新對帳 report_id: {}", report_id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        report.adjusted(adjustment_txn.id, approve_by);
+        let report = self.report_repo.update(report).await?;
+
+        Ok((report, adjustment_txn))
+    }
+}
@@ -1,9 +1,9 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, One};
 use kgs_err::models::status::Status as KgsStatus;
-use kgs_tracing::tracing;
+use kgs_tracing::{tracing, warn};
 
 use crate::domain::*;
 use crate::enums;
@@ -18,6 +18,7 @@ pub trait RolloverServiceTrait: Send + Sync + Debug {
     /// - `amount`: BigDecimal - 流水金額
     /// - `rollover_rate`: BigDecimal - 流水倍率
     /// - `action`: enums::wallet_action::Action - 錢包操作
+    /// - `game`: Option<&GameInfo> - 遊戲資訊 遊戲上分時依照遊戲的流水貢獻比例計算達成流水 None 表示 100%
     /// ### 回傳
     /// - `RolloverMain` - 流水主表
     /// - `Option<RolloverRecord>` - 流水紀錄
@@ -29,6 +30,7 @@ pub trait RolloverServiceTrait: Send + Sync + Debug {
         amount: BigDecimal,
         rollover_rate: BigDecimal,
         action: enums::WalletAction,
+        game: Option<&GameInfo>,
         change_by: i64,
     ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus>;
 
@@ -57,16 +59,19 @@ pub trait RolloverServiceTrait: Send + Sync + Debug {
 pub struct RolloverService {
     main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait>,
     rollover_record_repo: Arc<dyn RolloverRecordRepositoryTrait>,
+    rollover_contribution_repo: Arc<dyn RolloverContributionRepositoryTrait>,
 }
 
 impl RolloverService {
     pub fn new(
         main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait>,
         rollover_record_repo: Arc<dyn RolloverRecordRepositoryTrait>,
+        rollover_contribution_repo: Arc<dyn RolloverContributionRepositoryTrait>,
     ) -> Self {
         Self {
             main_rollover_repo,
             rollover_record_repo,
+            rollover_contribution_repo,
         }
     }
 }
@@ -82,16 +87,19 @@ impl RolloverServiceTrait for RolloverService {
         amount: BigDecimal,
         rollover_rate: BigDecimal,
         action: enums::WalletAction,
+        game: Option<&GameInfo>,
         change_by: i64,
     ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
         match action {
             enums::WalletAction::GameDeposit => {
+                let contribution_weight = self.get_contribution_weight(wallet_info, game).await?;
                 self.game_deposit_rollover(
                     user_wallet_id,
                     wallet_info,
                     wallet_txn_id,
                     &amount,
                     &rollover_rate,
+                    contribution_weight,
                     change_by,
                 )
                 .await
@@ -100,7 +108,7 @@ impl RolloverServiceTrait for RolloverService {
                 self.game_withdraw_rollover(user_wallet_id, wallet_info)
                     .await
             }
-            enums::WalletAction::PaymentDeposit => {
+            enums::WalletAction::PaymentDeposit | enums::WalletAction::BonusGrant => {
                 self.payment_deposit_rollover(
                     user_wallet_id,
                     wallet_info,
@@ -120,6 +128,29 @@ impl RolloverServiceTrait for RolloverService {
                 )
                 .await
             }
+            enums::WalletAction::TransferOut => {
+                self.transfer_out_rollover(user_wallet_id, wallet_info, wallet_txn_id, change_by)
+                    .await
+            }
+            enums::WalletAction::TransferIn => {
+                self.transfer_in_rollover(user_wallet_id, wallet_info).await
+            }
+            enums::WalletAction::PaymentWithdrawReject => {
+                self.payment_withdraw_reject_rollover(user_wallet_id, wallet_info)
+                    .await
+            }
+            enums::WalletAction::Adjustment => {
+                warn!("對帳調整不計算流水");
+                Err(KgsStatus::InvalidArgument)
+            }
+            enums::WalletAction::BonusForfeit => {
+                self.bonus_forfeit_rollover(user_wallet_id, wallet_info, wallet_txn_id, change_by)
+                    .await
+            }
+            enums::WalletAction::BonusCancel => {
+                self.bonus_cancel_rollover(user_wallet_id, wallet_info)
+                    .await
+            }
         }
     }
 
@@ -180,9 +211,10 @@ impl RolloverServiceTrait for RolloverService {
     }
 
     async fn is_rollover_achieved(&self, wallet_info: &WalletInfo) -> Result<bool, KgsStatus> {
+        // 鎖定流水主表 確保檢查後到出金前流水不會被其他請求修改
         let rollover_main = self
             .main_rollover_repo
-            .get(wallet_info)
+            .get_for_update(wallet_info)
             .await?
             .ok_or_else(|| {
                 tracing::error!("cnanot find rollover main");
@@ -194,7 +226,28 @@ impl RolloverServiceTrait for RolloverService {
 }
 
 impl RolloverService {
-    /// 遊戲上分時增加有達成流水
+    /// 取得遊戲的流水貢獻比例 沒有遊戲資訊或沒有設定時為 100%
+    #[tracing::instrument]
+    async fn get_contribution_weight(
+        &self,
+        wallet_info: &WalletInfo,
+        game: Option<&GameInfo>,
+    ) -> Result<BigDecimal, KgsStatus> {
+        let Some(game) = game else {
+            return Ok(BigDecimal::one());
+        };
+
+        let contribution = self
+            .rollover_contribution_repo
+            .get(wallet_info.client_id, wallet_info.wallet_source.id, game)
+            .await?;
+
+        Ok(contribution
+            .map(|contribution| contribution.weight)
+            .unwrap_or_else(BigDecimal::one))
+    }
+
+    /// 遊戲上分時增加有達成流水 有效投注依照遊戲的流水貢獻比例計算
     #[tracing::instrument]
     async fn game_deposit_rollover(
         &self,
@@ -203,27 +256,32 @@ impl RolloverService {
         wallet_txn_id: i64,
         rollover_amount: &BigDecimal,
         rollover_rate: &BigDecimal,
+        contribution_weight: BigDecimal,
         change_by: i64,
     ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
-        // 獲取流水主表
+        // 獲取流水主表 並鎖定至交易結束
         let mut rollover_main = self
-            .get_or_create_new_one(user_wallet_id, wallet_info)
+            .get_or_create_for_update(user_wallet_id, wallet_info)
             .await?;
 
-        // 創建流水紀錄
-        let rollover_record = RolloverRecord::new(
+        // 計入流水的有效投注
+        let rollover_amount = rollover_amount * &contribution_weight;
+
+        // 創建流水紀錄 並保存計算當下的貢獻比例
+        let mut rollover_record = RolloverRecord::new(
             rollover_main.id,
             wallet_txn_id,
             &wallet_info,
             RolloverType::Achievement,
-            rollover_amount,
+            &rollover_amount,
             rollover_rate,
             change_by,
         )
         .await;
+        rollover_record.contribution_weight = contribution_weight;
 
         // 主表增加流水
-        rollover_main.add_achievement_rollover_by_amount(rollover_amount, rollover_rate);
+        rollover_main.add_achievement_rollover_by_amount(&rollover_amount, rollover_rate);
 
         // 保存至db
         let rollover_record = self.rollover_record_repo.insert(rollover_record).await?;
@@ -247,7 +305,7 @@ impl RolloverService {
         Ok((rollover_main, None))
     }
 
-    /// 入金時增加需求流水
+    /// 入金與發放獎金時增加需求流水
     #[tracing::instrument]
     async fn payment_deposit_rollover(
         &self,
@@ -258,9 +316,9 @@ impl RolloverService {
         rollover_rate: &BigDecimal,
         change_by: i64,
     ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
-        // 獲取流水主表
+        // 獲取流水主表 並鎖定至交易結束
         let mut rollover_main = self
-            .get_or_create_new_one(user_wallet_id, wallet_info)
+            .get_or_create_for_update(user_wallet_id, wallet_info)
             .await?;
 
         // 創建流水紀錄
@@ -294,9 +352,47 @@ impl RolloverService {
         wallet_txn_id: i64,
         change_by: i64,
     ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
-        // 獲取流水主表
+        // 獲取流水主表 並鎖定至交易結束
+        let mut rollover_main = self
+            .get_or_create_for_update(user_wallet_id, wallet_info)
+            .await?;
+
+        // 新增清零流水紀錄
+        let rollover_record =
+            RolloverRecord::create_clear_rollover_record(&rollover_main, wallet_txn_id, change_by)
+                .await;
+
+        // 主表流水清空
+        rollover_main.clear_rollover();
+
+        // 保存至db
+        let rollover_record = self.rollover_record_repo.insert(rollover_record).await?;
+        let rollover_main = self.main_rollover_repo.update(rollover_main).await?;
+
+        Ok((rollover_main, Some(rollover_record)))
+    }
+
+    /// 轉出時 如果是從獎金錢包轉出 代表流水已經達成 需求流水與達成流水清零
+    /// 從本金錢包轉出則不需要修改流水
+    #[tracing::instrument]
+    async fn transfer_out_rollover(
+        &self,
+        user_wallet_id: i64,
+        wallet_info: &WalletInfo,
+        wallet_txn_id: i64,
+        change_by: i64,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        if enums::WalletSource::from_id(wallet_info.wallet_source.id)? != enums::WalletSource::Bonus
+        {
+            let rollover_main = self
+                .get_or_create_new_one(user_wallet_id, wallet_info)
+                .await?;
+            return Ok((rollover_main, None));
+        }
+
+        // 獲取流水主表 並鎖定至交易結束
         let mut rollover_main = self
-            .get_or_create_new_one(wallet_txn_id, wallet_info)
+            .get_or_create_for_update(user_wallet_id, wallet_info)
             .await?;
 
         // 新增清零流水紀錄
@@ -314,6 +410,82 @@ impl RolloverService {
         Ok((rollover_main, Some(rollover_record)))
     }
 
+    /// 獎金到期沒收時 需求流水與達成流水清零
+    #[tracing::instrument]
+    async fn bonus_forfeit_rollover(
+        &self,
+        user_wallet_id: i64,
+        wallet_info: &WalletInfo,
+        wallet_txn_id: i64,
+        change_by: i64,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        // 獲取流水主表 並鎖定至交易結束
+        let mut rollover_main = self
+            .get_or_create_for_update(user_wallet_id, wallet_info)
+            .await?;
+
+        // 新增清零流水紀錄
+        let rollover_record =
+            RolloverRecord::create_clear_rollover_record(&rollover_main, wallet_txn_id, change_by)
+                .await;
+
+        // 主表流水清空
+        rollover_main.clear_rollover();
+
+        // 保存至db
+        let rollover_record = self.rollover_record_repo.insert(rollover_record).await?;
+        let rollover_main = self.main_rollover_repo.update(rollover_main).await?;
+
+        Ok((rollover_main, Some(rollover_record)))
+    }
+
+    /// 取消獎金時不在這裡修改流水
+    /// 發放時增加的需求流水 需要透過 rollback_rollover 依照發放的交易還原
+    #[tracing::instrument]
+    async fn bonus_cancel_rollover(
+        &self,
+        user_wallet_id: i64,
+        wallet_info: &WalletInfo,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        // 獲取流水主表
+        let rollover_main = self
+            .get_or_create_new_one(user_wallet_id, wallet_info)
+            .await?;
+
+        Ok((rollover_main, None))
+    }
+
+    /// 轉入時不需要修改流水
+    #[tracing::instrument]
+    async fn transfer_in_rollover(
+        &self,
+        user_wallet_id: i64,
+        wallet_info: &WalletInfo,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        // 獲取流水主表
+        let rollover_main = self
+            .get_or_create_new_one(user_wallet_id, wallet_info)
+            .await?;
+
+        Ok((rollover_main, None))
+    }
+
+    /// 出金退回時不在這裡修改流水
+    /// 出金時清零的流水 需要透過 rollback_rollover 依照原本的出金交易還原
+    #[tracing::instrument]
+    async fn payment_withdraw_reject_rollover(
+        &self,
+        user_wallet_id: i64,
+        wallet_info: &WalletInfo,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        // 獲取流水主表
+        let rollover_main = self
+            .get_or_create_new_one(user_wallet_id, wallet_info)
+            .await?;
+
+        Ok((rollover_main, None))
+    }
+
     #[tracing::instrument]
     async fn update_rollover_main_by_wallet_info(
         &self,
@@ -323,7 +495,7 @@ impl RolloverService {
         achievement_rollover: &BigDecimal,
     ) -> Result<RolloverMain, KgsStatus> {
         let mut rollover_main = self
-            .get_or_create_new_one(user_wallet_id, wallet_info)
+            .get_or_create_for_update(user_wallet_id, wallet_info)
             .await?;
 
         rollover_main.add_achievement_rollover(achievement_rollover);
@@ -331,4 +503,21 @@ impl RolloverService {
 
         self.main_rollover_repo.update(rollover_main).await
     }
+
+    /// 取得或創建新的流水主表 並鎖定該流水主表直到交易結束
+    /// 不論錢包使用哪種 `LockStrategy` 流水主表一律使用悲觀鎖 所有修改流水主表的流程都經過這裡
+    #[tracing::instrument]
+    async fn get_or_create_for_update(
+        &self,
+        user_wallet_id: i64,
+        wallet_info: &WalletInfo,
+    ) -> Result<RolloverMain, KgsStatus> {
+        match self.main_rollover_repo.get_for_update(wallet_info).await? {
+            Some(rollover) => Ok(rollover),
+            None => {
+                let rollover = RolloverMain::new(wallet_info, user_wallet_id).await;
+                self.main_rollover_repo.insert(rollover).await
+            }
+        }
+    }
 }
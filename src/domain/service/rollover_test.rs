@@ -0,0 +1,392 @@
+//! 以記憶體資料庫測試 RolloverService 的流水修改與回滾
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure::memory_impl::*;
+
+const USER_WALLET_ID: i64 = 1;
+const USER_ID: i64 = 1;
+
+struct Fixture {
+    db: Arc<MemoryDatabase>,
+    rollover_service: RolloverService,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let db = MemoryDatabase::new();
+
+        Self {
+            rollover_service: RolloverService::new(
+                Arc::new(MemoryRolloverMainRepository::new(db.clone())),
+                Arc::new(MemoryRolloverRecordRepository::new(db.clone())),
+                Arc::new(MemoryRolloverContributionRepository::new(db.clone())),
+            ),
+            db,
+        }
+    }
+
+    /// 與修改錢包後相同 在交易中修改流水
+    async fn change_rollover(
+        &self,
+        wallet_info: &WalletInfo,
+        wallet_txn_id: i64,
+        amount: i64,
+        rollover_rate: i64,
+        action: enums::WalletAction,
+        game: Option<&GameInfo>,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        self.db
+            .transaction(|| {
+                self.rollover_service.change_rollover(
+                    USER_WALLET_ID,
+                    wallet_info,
+                    wallet_txn_id,
+                    BigDecimal::from(amount),
+                    BigDecimal::from(rollover_rate),
+                    action,
+                    game,
+                    USER_ID,
+                )
+            })
+            .await
+    }
+
+    async fn rollback_rollover(
+        &self,
+        wallet_info: &WalletInfo,
+        origin_wallet_txn_id: i64,
+        rollback_wallet_txn_id: i64,
+    ) -> Result<(RolloverMain, Option<RolloverRecord>), KgsStatus> {
+        self.db
+            .transaction(|| {
+                self.rollover_service.rollback_rollover(
+                    USER_WALLET_ID,
+                    wallet_info,
+                    origin_wallet_txn_id,
+                    rollback_wallet_txn_id,
+                    USER_ID,
+                )
+            })
+            .await
+    }
+
+    fn rollover_record_count(&self) -> usize {
+        self.db.read(|tables| tables.rollover_records.len())
+    }
+}
+
+fn wallet_info(wallet_source: enums::WalletSource) -> WalletInfo {
+    WalletInfo {
+        client_id: 1,
+        user_id: USER_ID,
+        currency: Currency {
+            id: 1,
+            name: "TWD".to_string(),
+        },
+        wallet_source: WalletSource {
+            id: wallet_source.to_id(),
+            name: "wallet".to_string(),
+            create_at: chrono::Utc::now().naive_utc(),
+        },
+    }
+}
+
+#[tokio::test]
+async fn payment_deposit_adds_requirement() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Normal);
+
+    let (rollover_main, rollover_record) = fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            2,
+            enums::WalletAction::PaymentDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let rollover_record = rollover_record.unwrap();
+    assert_eq!(rollover_main.requirement_rollover, BigDecimal::from(200));
+    assert!(rollover_main.achievement_rollover.is_zero());
+    assert_eq!(rollover_record.requirement_rollover, BigDecimal::from(200));
+    assert_eq!(rollover_record.wallet_txn_id, 1);
+}
+
+#[tokio::test]
+async fn game_deposit_uses_contribution_weight() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Bonus);
+    let now = chrono::Utc::now().naive_utc();
+    fixture
+        .db
+        .insert_rollover_contribution(RolloverContribution {
+            id: 1,
+            client_id: wallet_info.client_id,
+            wallet_source_id: wallet_info.wallet_source.id,
+            game_category: "slot".to_string(),
+            game_id: String::new(),
+            weight: BigDecimal::from_str("0.5").unwrap(),
+            create_at: now,
+            update_at: now,
+        });
+    let game = GameInfo {
+        game_category: "slot".to_string(),
+        game_id: "game-1".to_string(),
+    };
+
+    // 遊戲沒有設定時使用遊戲類別的貢獻比例
+    let (rollover_main, rollover_record) = fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            1,
+            enums::WalletAction::GameDeposit,
+            Some(&game),
+        )
+        .await
+        .unwrap();
+
+    let rollover_record = rollover_record.unwrap();
+    assert_eq!(rollover_main.achievement_rollover, BigDecimal::from(50));
+    assert_eq!(rollover_record.achievement_rollover, BigDecimal::from(50));
+    assert_eq!(
+        rollover_record.contribution_weight,
+        BigDecimal::from_str("0.5").unwrap()
+    );
+
+    // 沒有遊戲資訊時為 100%
+    let (rollover_main, _) = fixture
+        .change_rollover(
+            &wallet_info,
+            2,
+            100,
+            1,
+            enums::WalletAction::GameDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rollover_main.achievement_rollover, BigDecimal::from(150));
+}
+
+#[tokio::test]
+async fn game_withdraw_keeps_rollover() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Normal);
+    fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            1,
+            enums::WalletAction::PaymentDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let (rollover_main, rollover_record) = fixture
+        .change_rollover(
+            &wallet_info,
+            2,
+            30,
+            1,
+            enums::WalletAction::GameWithdraw,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(rollover_record.is_none());
+    assert_eq!(rollover_main.requirement_rollover, BigDecimal::from(100));
+    assert_eq!(fixture.rollover_record_count(), 1);
+}
+
+#[tokio::test]
+async fn payment_withdraw_clears_rollover() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Normal);
+    fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            1,
+            enums::WalletAction::PaymentDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+    fixture
+        .change_rollover(
+            &wallet_info,
+            2,
+            40,
+            1,
+            enums::WalletAction::GameDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let (rollover_main, rollover_record) = fixture
+        .change_rollover(
+            &wallet_info,
+            3,
+            50,
+            0,
+            enums::WalletAction::PaymentWithdraw,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let rollover_record = rollover_record.unwrap();
+    assert!(rollover_main.requirement_rollover.is_zero());
+    assert!(rollover_main.achievement_rollover.is_zero());
+    // 清零紀錄保存清零前的流水
+    assert_eq!(rollover_record.requirement_rollover, BigDecimal::from(-100));
+    assert_eq!(rollover_record.achievement_rollover, BigDecimal::from(-40));
+}
+
+#[tokio::test]
+async fn transfer_out_clears_only_bonus() {
+    let fixture = Fixture::new();
+    let normal_wallet_info = wallet_info(enums::WalletSource::Normal);
+    let bonus_wallet_info = wallet_info(enums::WalletSource::Bonus);
+    for (wallet_txn_id, wallet_info) in [(1, &normal_wallet_info), (2, &bonus_wallet_info)] {
+        fixture
+            .change_rollover(
+                wallet_info,
+                wallet_txn_id,
+                100,
+                1,
+                enums::WalletAction::PaymentDeposit,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let (normal_main, normal_record) = fixture
+        .change_rollover(
+            &normal_wallet_info,
+            3,
+            50,
+            0,
+            enums::WalletAction::TransferOut,
+            None,
+        )
+        .await
+        .unwrap();
+    let (bonus_main, bonus_record) = fixture
+        .change_rollover(
+            &bonus_wallet_info,
+            4,
+            50,
+            0,
+            enums::WalletAction::TransferOut,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(normal_record.is_none());
+    assert_eq!(normal_main.requirement_rollover, BigDecimal::from(100));
+    assert!(bonus_record.is_some());
+    assert!(bonus_main.requirement_rollover.is_zero());
+}
+
+#[tokio::test]
+async fn adjustment_rejected() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Normal);
+
+    let result = fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            1,
+            enums::WalletAction::Adjustment,
+            None,
+        )
+        .await;
+
+    assert!(matches!(result, Err(KgsStatus::InvalidArgument)));
+    assert_eq!(fixture.rollover_record_count(), 0);
+}
+
+#[tokio::test]
+async fn rollback_rollover() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Normal);
+    fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            2,
+            enums::WalletAction::PaymentDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+    fixture
+        .change_rollover(
+            &wallet_info,
+            2,
+            30,
+            1,
+            enums::WalletAction::GameDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let (rollover_main, rollback_record) =
+        fixture.rollback_rollover(&wallet_info, 2, 3).await.unwrap();
+
+    let rollback_record = rollback_record.unwrap();
+    assert_eq!(rollover_main.requirement_rollover, BigDecimal::from(200));
+    assert!(rollover_main.achievement_rollover.is_zero());
+    assert_eq!(rollback_record.achievement_rollover, BigDecimal::from(-30));
+    assert_eq!(rollback_record.wallet_txn_id, 3);
+    assert_eq!(fixture.rollover_record_count(), 3);
+}
+
+#[tokio::test]
+async fn rollback_rollover_without_record() {
+    let fixture = Fixture::new();
+    let wallet_info = wallet_info(enums::WalletSource::Normal);
+    fixture
+        .change_rollover(
+            &wallet_info,
+            1,
+            100,
+            1,
+            enums::WalletAction::PaymentDeposit,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // 遊戲下注沒有流水紀錄 回滾時流水不變
+    let (rollover_main, rollback_record) =
+        fixture.rollback_rollover(&wallet_info, 2, 3).await.unwrap();
+
+    assert!(rollback_record.is_none());
+    assert_eq!(rollover_main.requirement_rollover, BigDecimal::from(100));
+    assert_eq!(fixture.rollover_record_count(), 1);
+}
@@ -0,0 +1,184 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+
+use crate::domain::*;
+use crate::enums;
+
+#[tonic::async_trait]
+pub trait WalletLimitServiceTrait: Send + Sync + Debug {
+    /// 檢查單筆交易金額 在修改錢包之前呼叫
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `action`: &enums::WalletAction - 錢包操作
+    /// - `amount`: &BigDecimal - 金額
+    /// ### 回傳
+    /// - `Err(KgsStatus::TransactionAmountTooSmall)` - 小於單筆金額下限
+    /// - `Err(KgsStatus::TransactionAmountTooLarge)` - 大於單筆金額上限
+    async fn check_amount(
+        &self,
+        wallet_info: &WalletInfo,
+        action: &enums::WalletAction,
+        amount: &BigDecimal,
+    ) -> Result<(), KgsStatus>;
+
+    /// 檢查修改後的錢包 在同一個交易中修改錢包之後呼叫 超過限額時回傳錯誤讓整筆交易回滾
+    /// - 入金與轉入 檢查錢包餘額上限 遊戲派彩不檢查 避免玩家贏錢時無法派彩
+    /// - 出金 檢查最近 24 小時與最近 7 天的出金總額 包含本次出金
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `action`: &enums::WalletAction - 錢包操作
+    /// - `user_wallet`: &UserWallet - 修改後的錢包
+    /// ### 回傳
+    /// - `Err(KgsStatus::WalletBalanceLimitExceeded)` - 超過錢包餘額上限
+    /// - `Err(KgsStatus::DailyWithdrawLimitExceeded)` - 超過每日出金上限
+    /// - `Err(KgsStatus::WeeklyWithdrawLimitExceeded)` - 超過每週出金上限
+    async fn check_after_change(
+        &self,
+        wallet_info: &WalletInfo,
+        action: &enums::WalletAction,
+        user_wallet: &UserWallet,
+    ) -> Result<(), KgsStatus>;
+}
+
+#[derive(Debug)]
+pub struct WalletLimitService {
+    limit_repo: Arc<dyn WalletLimitRepositoryTrait>,
+    wallet_txn_repo: Arc<dyn WalletTransactionRepositoryTrait>,
+}
+
+impl WalletLimitService {
+    pub fn new(
+        limit_repo: Arc<dyn WalletLimitRepositoryTrait>,
+        wallet_txn_repo: Arc<dyn WalletTransactionRepositoryTrait>,
+    ) -> Self {
+        Self {
+            limit_repo,
+            wallet_txn_repo,
+        }
+    }
+
+    /// 指定時間之後的出金總額是否在上限之內
+    async fn is_withdraw_total_within(
+        &self,
+        wallet_info: &WalletInfo,
+        limit: &BigDecimal,
+        period: chrono::Duration,
+    ) -> Result<bool, KgsStatus> {
+        let since = chrono::Utc::now().naive_utc() - period;
+        let total = self
+            .wallet_txn_repo
+            .get_withdraw_total(
+                wallet_info.client_id,
+                wallet_info.user_id,
+                wallet_info.currency.id,
+                since,
+            )
+            .await?;
+
+        if &total > limit {
+            warn!("出金總額: {} 超過上限: {}", total, limit);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+#[tonic::async_trait]
+impl WalletLimitServiceTrait for WalletLimitService {
+    #[tracing::instrument]
+    async fn check_amount(
+        &self,
+        wallet_info: &WalletInfo,
+        action: &enums::WalletAction,
+        amount: &BigDecimal,
+    ) -> Result<(), KgsStatus> {
+        let action_limit = match self
+            .limit_repo
+            .get_action_limit(
+                wallet_info.client_id,
+                wallet_info.currency.id,
+                action.to_id(),
+            )
+            .await?
+        {
+            Some(action_limit) => action_limit,
+            None => return Ok(()),
+        };
+
+        if let Some(min_amount) = &action_limit.min_amount {
+            if amount < min_amount {
+                warn!("金額: {} 小於單筆下限: {}", amount, min_amount);
+                return Err(KgsStatus::TransactionAmountTooSmall);
+            }
+        }
+        if let Some(max_amount) = &action_limit.max_amount {
+            if amount > max_amount {
+                warn!("金額: {} 大於單筆上限: {}", amount, max_amount);
+                return Err(KgsStatus::TransactionAmountTooLarge);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    async fn check_after_change(
+        &self,
+        wallet_info: &WalletInfo,
+        action: &enums::WalletAction,
+        user_wallet: &UserWallet,
+    ) -> Result<(), KgsStatus> {
+        let wallet_limit = match self
+            .limit_repo
+            .get(wallet_info.client_id, wallet_info.currency.id)
+            .await?
+        {
+            Some(wallet_limit) => wallet_limit,
+            None => return Ok(()),
+        };
+
+        match action {
+            enums::WalletAction::PaymentDeposit | enums::WalletAction::TransferIn => {
+                if let Some(max_balance) = &wallet_limit.max_balance {
+                    if &user_wallet.amount > max_balance {
+                        warn!("錢包餘額: {} 超過上限: {}", user_wallet.amount, max_balance);
+                        return Err(KgsStatus::WalletBalanceLimitExceeded);
+                    }
+                }
+            }
+            enums::WalletAction::PaymentWithdraw => {
+                if let Some(daily_limit) = &wallet_limit.daily_withdraw_limit {
+                    if !self
+                        .is_withdraw_total_within(
+                            wallet_info,
+                            daily_limit,
+                            chrono::Duration::days(1),
+                        )
+                        .await?
+                    {
+                        return Err(KgsStatus::DailyWithdrawLimitExceeded);
+                    }
+                }
+                if let Some(weekly_limit) = &wallet_limit.weekly_withdraw_limit {
+                    if !self
+                        .is_withdraw_total_within(
+                            wallet_info,
+                            weekly_limit,
+                            chrono::Duration::days(7),
+                        )
+                        .await?
+                    {
+                        return Err(KgsStatus::WeeklyWithdrawLimitExceeded);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
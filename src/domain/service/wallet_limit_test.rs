@@ -0,0 +1,280 @@
+//! 以記憶體資料庫測試 WalletLimitService 的單筆金額 錢包餘額與出金總額限額
+
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use kgs_err::models::status::Status as KgsStatus;
+
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure::memory_impl::*;
+
+struct Fixture {
+    db: Arc<MemoryDatabase>,
+    wallet_service: WalletService,
+    wallet_limit_service: WalletLimitService,
+    wallet_info: WalletInfo,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let db = MemoryDatabase::new();
+        let wallet_txn_repo = Arc::new(MemoryWalletTransactionRepository::new(db.clone()));
+
+        Self {
+            wallet_service: WalletService::new(
+                wallet_txn_repo.clone(),
+                Arc::new(MemoryUserWalletRepository::new(db.clone())),
+                Arc::new(MemoryWalletOutboxRepository::new(db.clone())),
+                LockStrategy::Pessimistic,
+            ),
+            wallet_limit_service: WalletLimitService::new(
+                Arc::new(MemoryWalletLimitRepository::new(db.clone())),
+                wallet_txn_repo,
+            ),
+            wallet_info: WalletInfo {
+                client_id: 1,
+                user_id: 1,
+                currency: Currency {
+                    id: 1,
+                    name: "TWD".to_string(),
+                },
+                wallet_source: WalletSource {
+                    id: enums::WalletSource::Normal.to_id(),
+                    name: "normal".to_string(),
+                    create_at: chrono::Utc::now().naive_utc(),
+                },
+            },
+            db,
+        }
+    }
+
+    fn insert_wallet_limit(
+        &self,
+        max_balance: Option<i64>,
+        daily_withdraw_limit: Option<i64>,
+        weekly_withdraw_limit: Option<i64>,
+    ) {
+        let now = chrono::Utc::now().naive_utc();
+        self.db.insert_wallet_limit(WalletLimit {
+            id: 1,
+            client_id: self.wallet_info.client_id,
+            currency_id: self.wallet_info.currency.id,
+            max_balance: max_balance.map(BigDecimal::from),
+            daily_withdraw_limit: daily_withdraw_limit.map(BigDecimal::from),
+            weekly_withdraw_limit: weekly_withdraw_limit.map(BigDecimal::from),
+            create_at: now,
+            update_at: now,
+        });
+    }
+
+    /// 與金流出入金相同 修改錢包後在同一個交易中檢查限額 超過時整筆交易回滾
+    async fn change_payment_amount(
+        &self,
+        order_id: &str,
+        amount: i64,
+        action: enums::WalletAction,
+    ) -> Result<WalletTransaction, KgsStatus> {
+        let payment = PaymentReference {
+            order_id: order_id.to_string(),
+            channel: "bank".to_string(),
+            operator: "admin".to_string(),
+        };
+
+        self.db
+            .transaction(|| async move {
+                let (user_wallet, wallet_txn) = self
+                    .wallet_service
+                    .change_payment_amount(
+                        &self.wallet_info,
+                        &payment,
+                        BigDecimal::from(amount),
+                        &action,
+                    )
+                    .await?;
+                self.wallet_limit_service
+                    .check_after_change(&self.wallet_info, &action, &user_wallet)
+                    .await?;
+                Ok(wallet_txn)
+            })
+            .await
+    }
+
+    async fn check_amount(
+        &self,
+        action: enums::WalletAction,
+        amount: i64,
+    ) -> Result<(), KgsStatus> {
+        self.wallet_limit_service
+            .check_amount(&self.wallet_info, &action, &BigDecimal::from(amount))
+            .await
+    }
+
+    fn wallet_amount(&self) -> BigDecimal {
+        self.db.read(|tables| tables.user_wallets[0].amount.clone())
+    }
+}
+
+#[tokio::test]
+async fn check_amount_min_max() {
+    let fixture = Fixture::new();
+    let now = chrono::Utc::now().naive_utc();
+    fixture.db.insert_wallet_action_limit(WalletActionLimit {
+        id: 1,
+        client_id: fixture.wallet_info.client_id,
+        currency_id: fixture.wallet_info.currency.id,
+        action: enums::WalletAction::PaymentDeposit.to_id(),
+        min_amount: Some(BigDecimal::from(10)),
+        max_amount: Some(BigDecimal::from(100)),
+        create_at: now,
+        update_at: now,
+    });
+
+    assert!(matches!(
+        fixture
+            .check_amount(enums::WalletAction::PaymentDeposit, 5)
+            .await,
+        Err(KgsStatus::TransactionAmountTooSmall)
+    ));
+    assert!(matches!(
+        fixture
+            .check_amount(enums::WalletAction::PaymentDeposit, 101)
+            .await,
+        Err(KgsStatus::TransactionAmountTooLarge)
+    ));
+    assert!(fixture
+        .check_amount(enums::WalletAction::PaymentDeposit, 10)
+        .await
+        .is_ok());
+    assert!(fixture
+        .check_amount(enums::WalletAction::PaymentDeposit, 100)
+        .await
+        .is_ok());
+    // 沒有設定限額的錢包操作不限制
+    assert!(fixture
+        .check_amount(enums::WalletAction::PaymentWithdraw, 1000)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn deposit_over_balance_limit_rollback() {
+    let fixture = Fixture::new();
+    fixture.insert_wallet_limit(Some(100), None, None);
+
+    fixture
+        .change_payment_amount("deposit-1", 80, enums::WalletAction::PaymentDeposit)
+        .await
+        .unwrap();
+    let result = fixture
+        .change_payment_amount("deposit-2", 30, enums::WalletAction::PaymentDeposit)
+        .await;
+
+    assert!(matches!(result, Err(KgsStatus::WalletBalanceLimitExceeded)));
+    assert_eq!(fixture.wallet_amount(), BigDecimal::from(80));
+    fixture
+        .db
+        .read(|tables| assert_eq!(tables.wallet_txns.len(), 1));
+}
+
+#[tokio::test]
+async fn withdraw_over_daily_limit_rollback() {
+    let fixture = Fixture::new();
+    fixture.insert_wallet_limit(None, Some(100), None);
+    fixture
+        .change_payment_amount("deposit-1", 500, enums::WalletAction::PaymentDeposit)
+        .await
+        .unwrap();
+
+    fixture
+        .change_payment_amount("withdraw-1", 60, enums::WalletAction::PaymentWithdraw)
+        .await
+        .unwrap();
+    let result = fixture
+        .change_payment_amount("withdraw-2", 50, enums::WalletAction::PaymentWithdraw)
+        .await;
+
+    assert!(matches!(result, Err(KgsStatus::DailyWithdrawLimitExceeded)));
+    assert_eq!(fixture.wallet_amount(), BigDecimal::from(440));
+}
+
+#[tokio::test]
+async fn withdraw_over_weekly_limit_rollback() {
+    let fixture = Fixture::new();
+    fixture.insert_wallet_limit(None, Some(100), Some(150));
+    fixture
+        .change_payment_amount("deposit-1", 500, enums::WalletAction::PaymentDeposit)
+        .await
+        .unwrap();
+    let withdraw_txn = fixture
+        .change_payment_amount("withdraw-1", 100, enums::WalletAction::PaymentWithdraw)
+        .await
+        .unwrap();
+
+    // 3 天前的出金只計入每週出金總額
+    fixture.db.write(|tables| {
+        let row = tables
+            .wallet_txns
+            .iter_mut()
+            .find(|row| row.id == withdraw_txn.id)
+            .unwrap();
+        row.create_at -= chrono::Duration::days(3);
+    });
+    let result = fixture
+        .change_payment_amount("withdraw-2", 60, enums::WalletAction::PaymentWithdraw)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(KgsStatus::WeeklyWithdrawLimitExceeded)
+    ));
+    assert_eq!(fixture.wallet_amount(), BigDecimal::from(400));
+    fixture
+        .change_payment_amount("withdraw-3", 50, enums::WalletAction::PaymentWithdraw)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn withdraw_total_excludes_rejected_and_rolled_back() {
+    let fixture = Fixture::new();
+    fixture.insert_wallet_limit(None, Some(100), None);
+    fixture
+        .change_payment_amount("deposit-1", 500, enums::WalletAction::PaymentDeposit)
+        .await
+        .unwrap();
+
+    // 退回與回滾的出金 都不計入出金總額
+    let rejected_txn = fixture
+        .change_payment_amount("withdraw-1", 100, enums::WalletAction::PaymentWithdraw)
+        .await
+        .unwrap();
+    fixture
+        .db
+        .transaction(|| {
+            fixture
+                .wallet_service
+                .reject_withdraw(&fixture.wallet_info, &rejected_txn)
+        })
+        .await
+        .unwrap();
+    let rolled_back_txn = fixture
+        .change_payment_amount("withdraw-2", 100, enums::WalletAction::PaymentWithdraw)
+        .await
+        .unwrap();
+    fixture
+        .db
+        .transaction(|| {
+            fixture
+                .wallet_service
+                .rollback_transaction(&fixture.wallet_info, &rolled_back_txn)
+        })
+        .await
+        .unwrap();
+
+    fixture
+        .change_payment_amount("withdraw-3", 100, enums::WalletAction::PaymentWithdraw)
+        .await
+        .unwrap();
+    assert_eq!(fixture.wallet_amount(), BigDecimal::from(400));
+}
@@ -1,11 +1,13 @@
 use bigdecimal::BigDecimal;
 use kgs_tracing::tracing;
 use kgs_tracing::warn;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
 use crate::domain::*;
 use crate::enums;
+use crate::infrastructure;
 use kgs_err::models::status::Status as KgsStatus;
 
 #[tonic::async_trait]
@@ -19,6 +21,7 @@ pub trait WalletServiceTrait: Send + Sync + Debug {
     /// ### 回傳
     /// - `UserWallet` - 更新後的錢包
     /// - `WalletTransaction` - 更新後的交易紀錄
+    /// - `Err(KgsStatus::ConcurrentUpdateConflict)` - 樂觀鎖模式下 重試次數用完仍版本衝突
     async fn change_amount(
         &self,
         wallet_info: &WalletInfo,
@@ -28,7 +31,134 @@ pub trait WalletServiceTrait: Send + Sync + Debug {
         action: &enums::WalletAction,
     ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
 
-    /// 檢查錢包金額是否足夠
+    /// 金流出入金 修改錢包金額 並新增帶有金流訂單參考的交易紀錄
+    /// 來源交易 ID 由系統產生 回滾時以金流訂單 ID 找回此交易
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `payment`: &PaymentReference - 金流訂單參考
+    /// - `amount`: BigDecimal - 金額
+    /// - `action`: &enums::WalletAction - 錢包操作 入金或出金
+    /// ### 回傳
+    /// - `UserWallet` - 更新後的錢包
+    /// - `WalletTransaction` - 更新後的交易紀錄
+    /// - `Err(KgsStatus::DuplicateTransaction)` - 金流訂單 ID 已被使用
+    async fn change_payment_amount(
+        &self,
+        wallet_info: &WalletInfo,
+        payment: &PaymentReference,
+        amount: BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
+
+    /// 合併錢包從單一錢包扣款或入帳 修改錢包金額 並新增標記為合併錢包的原始交易
+    /// 同一個來源交易 ID 在本金與獎金錢包各有一筆原始交易 只在同一個錢包內不能重複
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `source_txn_id`: i64 - 來源交易 ID
+    /// - `amount`: BigDecimal - 金額
+    /// - `action`: &enums::WalletAction - 錢包操作 遊戲上分或下分
+    /// ### 回傳
+    /// - `UserWallet` - 更新後的錢包
+    /// - `WalletTransaction` - 更新後的交易紀錄
+    /// - `Err(KgsStatus::DuplicateTransaction)` - 同一個錢包的來源交易 ID 與動作已存在
+    async fn change_combined_leg_amount(
+        &self,
+        wallet_info: &WalletInfo,
+        source_txn_id: i64,
+        amount: BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
+
+    /// 同一玩家 同幣別 在兩個不同來源的錢包之間轉帳
+    /// 轉出與轉入的交易紀錄使用相同的來源交易 ID 轉入交易的 parent_id 指向轉出交易
+    /// 來源交易 ID 已轉帳過時 內容相同視為重送 回傳目前的錢包與原本的交易紀錄 不再轉帳
+    /// ### 參數
+    /// - `from_wallet_info`: &WalletInfo - 轉出錢包資訊
+    /// - `to_wallet_info`: &WalletInfo - 轉入錢包資訊
+    /// - `source_txn_id`: i64 - 來源交易 ID
+    /// - `amount`: BigDecimal - 金額
+    /// ### 回傳
+    /// - `TransferResult` - 轉出與轉入後的錢包與交易紀錄
+    /// - `Err(KgsStatus::DuplicateTransactionAmountError)` - 來源交易 ID 已轉帳過 但內容不同
+    async fn transfer(
+        &self,
+        from_wallet_info: &WalletInfo,
+        to_wallet_info: &WalletInfo,
+        source_txn_id: i64,
+        amount: BigDecimal,
+    ) -> Result<TransferResult, KgsStatus>;
+
+    /// 凍結錢包金額 新增一筆凍結中的出款交易紀錄 錢包金額不變
+    /// 相同的凍結單號重送時 仍在凍結中才回傳原本的凍結結果
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `hold_id`: i64 - 凍結單號 記錄在交易紀錄的 hold_id 不與其他出金的來源交易 ID 共用
+    /// - `amount`: BigDecimal - 凍結金額
+    /// ### 回傳
+    /// - `UserWallet` - 凍結後的錢包
+    /// - `WalletTransaction` - 凍結中的交易紀錄
+    /// - `Err(KgsStatus::InvalidArgument)` - 凍結單號已確認或已釋放
+    async fn hold(
+        &self,
+        wallet_info: &WalletInfo,
+        hold_id: i64,
+        amount: BigDecimal,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
+
+    /// 確認凍結 扣除錢包金額 並將交易紀錄改為成功
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `hold_id`: i64 - 凍結單號
+    /// ### 回傳
+    /// - `UserWallet` - 扣款後的錢包
+    /// - `WalletTransaction` - 更新後的交易紀錄
+    async fn commit_hold(
+        &self,
+        wallet_info: &WalletInfo,
+        hold_id: i64,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
+
+    /// 取消凍結 錢包金額不變 並將交易紀錄改為取消
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `hold_id`: i64 - 凍結單號
+    /// ### 回傳
+    /// - `UserWallet` - 解除凍結後的錢包
+    /// - `WalletTransaction` - 更新後的交易紀錄
+    async fn release_hold(
+        &self,
+        wallet_info: &WalletInfo,
+        hold_id: i64,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
+
+    /// 出金退回 將出金的金額退回原本的錢包 退回的交易紀錄 parent_id 指向出金交易
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 出金交易所屬的錢包資訊
+    /// - `withdraw_wallet_txn`: &WalletTransaction - 需要退回的出金交易
+    /// ### 回傳
+    /// - `UserWallet` - 退回後的錢包
+    /// - `WalletTransaction` - 退回的交易紀錄
+    async fn reject_withdraw(
+        &self,
+        wallet_info: &WalletInfo,
+        withdraw_wallet_txn: &WalletTransaction,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus>;
+
+    /// 依照凍結單號取得凍結的交易紀錄
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `user_id`: i64 - 用戶 ID
+    /// - `hold_id`: i64 - 凍結單號
+    /// ### 回傳
+    /// - `WalletTransaction` - 交易紀錄
+    async fn get_hold_transaction(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        hold_id: i64,
+    ) -> Result<WalletTransaction, KgsStatus>;
+
+    /// 檢查錢包金額是否足夠 凍結中的金額不可使用
     /// ### 參數
     /// - `wallet_info`: &WalletInfo - 錢包資訊
     /// - `withdraw_amount`: &BigDecimal - 提款金額
@@ -40,6 +170,30 @@ pub trait WalletServiceTrait: Send + Sync + Debug {
         withdraw_amount: &BigDecimal,
     ) -> Result<bool, KgsStatus>;
 
+    /// 依照 ID 取得交易紀錄
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `user_id`: i64 - 用戶 ID
+    /// - `wallet_txn_id`: i64 - 交易紀錄 ID
+    /// ### 回傳
+    /// - `WalletTransaction` - 交易紀錄
+    async fn get_transaction(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        wallet_txn_id: i64,
+    ) -> Result<WalletTransaction, KgsStatus>;
+
+    /// 依照查詢條件取得交易紀錄 以交易 ID 作為游標由新到舊分頁
+    /// ### 參數
+    /// - `select_query`: SelectWalletTransactionsQuery - 查詢條件
+    /// ### 回傳
+    /// - `WalletTransactionPage` - 交易紀錄與其上層交易 以及下一頁的游標
+    async fn get_transaction_list(
+        &self,
+        select_query: SelectWalletTransactionsQuery,
+    ) -> Result<WalletTransactionPage, KgsStatus>;
+
     /// 依照來源交易 ID 取得最後一筆交易紀錄
     /// ### 參數
     /// - `client_id`: i64 - 用戶 ID
@@ -53,6 +207,56 @@ pub trait WalletServiceTrait: Send + Sync + Debug {
         source_txn_id: i64,
     ) -> Result<WalletTransaction, KgsStatus>;
 
+    /// 取得已處理過的原始交易 用於上游重送請求時回傳原本的結果
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `source_txn_id`: i64 - 來源交易 ID
+    /// - `amount`: &BigDecimal - 本次請求的金額
+    /// - `action`: &enums::WalletAction - 錢包操作
+    /// ### 回傳
+    /// - `Some(WalletTransaction)` - 已處理過 且錢包與金額皆相同
+    /// - `None` - 尚未處理過
+    /// - `Err(KgsStatus::DuplicateTransactionAmountError)` - 已處理過 但錢包或金額不同
+    async fn get_processed_transaction(
+        &self,
+        wallet_info: &WalletInfo,
+        source_txn_id: i64,
+        amount: &BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<Option<WalletTransaction>, KgsStatus>;
+
+    /// 取得已處理過的金流訂單 用於金流重送請求時回傳原本的結果
+    /// ### 參數
+    /// - `wallet_info`: &WalletInfo - 錢包資訊
+    /// - `payment_order_id`: &str - 金流訂單 ID
+    /// - `amount`: &BigDecimal - 本次請求的金額
+    /// - `action`: &enums::WalletAction - 錢包操作
+    /// ### 回傳
+    /// - `Some(WalletTransaction)` - 已處理過 且玩家 錢包 操作與金額皆相同
+    /// - `None` - 尚未處理過
+    /// - `Err(KgsStatus::DuplicateTransactionAmountError)` - 已處理過 但內容不同
+    async fn get_processed_payment(
+        &self,
+        wallet_info: &WalletInfo,
+        payment_order_id: &str,
+        amount: &BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<Option<WalletTransaction>, KgsStatus>;
+
+    /// 依照金流訂單 ID 取得出入金的原始交易
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `user_id`: i64 - 用戶 ID
+    /// - `payment_order_id`: &str - 金流訂單 ID
+    /// ### 回傳
+    /// - `WalletTransaction` - 交易紀錄
+    async fn get_payment_transaction(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        payment_order_id: &str,
+    ) -> Result<WalletTransaction, KgsStatus>;
+
     /// 回滾交易 到指定的wallet上
     /// ### 參數
     /// - `wallet_info`: &WalletInfo - 錢包資訊
@@ -71,22 +275,49 @@ pub trait WalletServiceTrait: Send + Sync + Debug {
         &self,
         wallet_info: &WalletInfo,
     ) -> Result<UserWallet, KgsStatus>;
+
+    /// 取得或創建新的錢包 悲觀鎖模式下鎖定該錢包直到交易結束
+    /// 需要同時修改多個錢包時 呼叫端先依照錢包來源 ID 的順序鎖定 避免死結
+    async fn get_or_create_for_update(
+        &self,
+        wallet_info: &WalletInfo,
+    ) -> Result<UserWallet, KgsStatus>;
+
+    /// 依照來源交易 ID 取得所有交易紀錄 包含各錢包的原始交易與其回滾 更新的交易
+    /// ### 參數
+    /// - `client_id`: i64 - 用戶的client ID
+    /// - `user_id`: i64 - 用戶 ID
+    /// - `source_txn_id`: i64 - 來源交易 ID
+    /// ### 回傳
+    /// - `Vec<WalletTransaction>` - 交易紀錄
+    async fn get_transaction_list_by_source_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        source_txn_id: i64,
+    ) -> Result<Vec<WalletTransaction>, KgsStatus>;
 }
 
 #[derive(Debug)]
 pub struct WalletService {
     wallet_txn_repo: Arc<dyn WalletTransactionRepositoryTrait>,
     wallet_repo: Arc<dyn UserWalletRepositoryTrait>,
+    outbox_repo: Arc<dyn WalletOutboxRepositoryTrait>,
+    lock_strategy: LockStrategy,
 }
 
 impl WalletService {
     pub fn new(
         wallet_txn_repo: Arc<dyn WalletTransactionRepositoryTrait>,
         wallet_repo: Arc<dyn UserWalletRepositoryTrait>,
+        outbox_repo: Arc<dyn WalletOutboxRepositoryTrait>,
+        lock_strategy: LockStrategy,
     ) -> Self {
         Self {
             wallet_txn_repo,
             wallet_repo,
+            outbox_repo,
+            lock_strategy,
         }
     }
 }
@@ -102,46 +333,455 @@ impl WalletServiceTrait for WalletService {
         amount: BigDecimal,
         action: &enums::WalletAction,
     ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
-        // 獲取玩家錢包
-        let mut user_wallet = self.get_or_create_new_one(wallet_info).await?;
-
-        // 創建交易紀錄
-        let wallet_txn = WalletTransaction::create_before_change(
-            &user_wallet,
+        self.change_amount_with_payment(
+            wallet_info,
             parent_wallet_txn_id,
             source_txn_id,
+            amount,
             action,
-            &amount,
+            ChangeAmountOptions::default(),
         )
-        .await;
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn change_payment_amount(
+        &self,
+        wallet_info: &WalletInfo,
+        payment: &PaymentReference,
+        amount: BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        // 金流訂單沒有數字的交易 ID 由系統產生來源交易 ID 讓同一筆訂單的交易串在一起
+        let source_txn_id = infrastructure::snowflake::generate_id().await;
+
+        self.change_amount_with_payment(
+            wallet_info,
+            0,
+            source_txn_id,
+            amount,
+            action,
+            ChangeAmountOptions {
+                payment: Some(payment.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+    }
 
-        // 更新錢包
-        match action {
-            enums::WalletAction::GameDeposit | enums::WalletAction::PaymentDeposit => {
-                user_wallet.deposit(&amount);
+    #[tracing::instrument]
+    async fn change_combined_leg_amount(
+        &self,
+        wallet_info: &WalletInfo,
+        source_txn_id: i64,
+        amount: BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        self.change_amount_with_payment(
+            wallet_info,
+            0,
+            source_txn_id,
+            amount,
+            action,
+            ChangeAmountOptions {
+                combined_leg: true,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn transfer(
+        &self,
+        from_wallet_info: &WalletInfo,
+        to_wallet_info: &WalletInfo,
+        source_txn_id: i64,
+        amount: BigDecimal,
+    ) -> Result<TransferResult, KgsStatus> {
+        // 只能在同一玩家 同幣別的不同錢包之間轉帳
+        if from_wallet_info.client_id != to_wallet_info.client_id
+            || from_wallet_info.user_id != to_wallet_info.user_id
+            || from_wallet_info.currency.id != to_wallet_info.currency.id
+            || from_wallet_info.wallet_source.id == to_wallet_info.wallet_source.id
+        {
+            warn!("轉帳的錢包資訊錯誤");
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 依照錢包來源 ID 的順序鎖定兩個錢包 避免反方向的轉帳互相等待造成死結
+        let (first, second) = if from_wallet_info.wallet_source.id < to_wallet_info.wallet_source.id
+        {
+            (from_wallet_info, to_wallet_info)
+        } else {
+            (to_wallet_info, from_wallet_info)
+        };
+        self.get_or_create_for_update(first).await?;
+        self.get_or_create_for_update(second).await?;
+
+        // 重送的請求 在鎖定範圍內檢查 避免同時送出的重複請求都轉帳
+        if let Some(out_wallet_txn) = self
+            .get_processed_transaction(
+                from_wallet_info,
+                source_txn_id,
+                &amount,
+                &enums::WalletAction::TransferOut,
+            )
+            .await?
+        {
+            let in_wallet_txn = self
+                .wallet_txn_repo
+                .get_child(out_wallet_txn.id)
+                .await?
+                .filter(|in_wallet_txn| {
+                    in_wallet_txn.wallet_source_id == to_wallet_info.wallet_source.id
+                        && in_wallet_txn.action == enums::WalletAction::TransferIn.to_id()
+                })
+                .ok_or_else(|| {
+                    warn!(
+                        "交易單號重複但轉入錢包不同 source_txn_id: {}",
+                        source_txn_id
+                    );
+                    KgsStatus::DuplicateTransactionAmountError
+                })?;
+
+            return Ok(TransferResult {
+                from_wallet: self.get_or_create_new_one(from_wallet_info).await?,
+                out_wallet_txn,
+                to_wallet: self.get_or_create_new_one(to_wallet_info).await?,
+                in_wallet_txn,
+                is_resend: true,
+            });
+        }
+
+        // 檢查轉出錢包餘額是否足夠
+        if !self
+            .is_wallet_amount_enough(from_wallet_info, &amount)
+            .await?
+        {
+            return Err(KgsStatus::WalletAmountNotEnough);
+        }
+
+        // 轉出
+        let (from_wallet, out_wallet_txn) = self
+            .change_amount(
+                from_wallet_info,
+                0,
+                source_txn_id,
+                amount.clone(),
+                &enums::WalletAction::TransferOut,
+            )
+            .await?;
+
+        // 轉入 parent_id 指向轉出的交易紀錄
+        let (to_wallet, in_wallet_txn) = self
+            .change_amount(
+                to_wallet_info,
+                out_wallet_txn.id,
+                source_txn_id,
+                amount,
+                &enums::WalletAction::TransferIn,
+            )
+            .await?;
+
+        Ok(TransferResult {
+            from_wallet,
+            out_wallet_txn,
+            to_wallet,
+            in_wallet_txn,
+            is_resend: false,
+        })
+    }
+
+    #[tracing::instrument]
+    async fn hold(
+        &self,
+        wallet_info: &WalletInfo,
+        hold_id: i64,
+        amount: BigDecimal,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        // 獲取玩家錢包 並鎖定至交易結束 避免併發修改
+        let mut user_wallet = self.get_or_create_for_update(wallet_info).await?;
+
+        // 相同凍結單號重送 回傳原本的結果
+        if let Some(wallet_txn) = self
+            .wallet_txn_repo
+            .get_by_hold_id(wallet_info.client_id, wallet_info.user_id, hold_id)
+            .await?
+        {
+            // 相同的凍結單號 但內容不同 不能視為重送
+            if wallet_txn.currency_id != wallet_info.currency.id
+                || wallet_txn.wallet_source_id != wallet_info.wallet_source.id
+                || wallet_txn.change_amount != amount
+            {
+                warn!(
+                    "凍結單號重複但內容不同 hold_id: {}, origin amount: {}, amount: {}",
+                    hold_id, wallet_txn.change_amount, amount
+                );
+                return Err(KgsStatus::DuplicateTransactionAmountError);
             }
-            enums::WalletAction::GameWithdraw | enums::WalletAction::PaymentWithdraw => {
-                user_wallet.withdraw(&amount);
+
+            // 已確認或已釋放的凍結單號不能再凍結 回傳原本的結果會讓呼叫端誤以為仍在凍結中
+            if enums::WalletStatus::from_i32(wallet_txn.status)? != enums::WalletStatus::Pending {
+                warn!("凍結單號已被處理過 hold_id: {}", hold_id);
+                return Err(KgsStatus::InvalidArgument);
             }
+            return Ok((user_wallet, wallet_txn));
         }
 
+        // 檢查可用金額是否足夠
+        if user_wallet.available_amount() < amount {
+            return Err(KgsStatus::WalletAmountNotEnough);
+        }
+
+        // 創建凍結中的交易紀錄
+        let wallet_txn = WalletTransaction::create_pending(
+            &user_wallet,
+            hold_id,
+            &enums::WalletAction::PaymentWithdraw,
+            &amount,
+        )
+        .await;
+
+        // 凍結金額
+        user_wallet.hold(&amount);
+
         // 更新db
         let updated_wallet = self.wallet_repo.update(user_wallet).await?;
         let wallet_txn = self.wallet_txn_repo.insert(wallet_txn).await?;
 
+        // 錢包金額不變但可用金額減少 下游依照交易紀錄的狀態區分凍結中
+        self.insert_wallet_changed_event(&wallet_txn).await?;
+
+        Ok((updated_wallet, wallet_txn))
+    }
+
+    #[tracing::instrument]
+    async fn commit_hold(
+        &self,
+        wallet_info: &WalletInfo,
+        hold_id: i64,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        // 先鎖定錢包再讀取凍結紀錄 同一錢包的確認與取消不會同時進行
+        let mut user_wallet = self.get_or_create_for_update(wallet_info).await?;
+        let mut wallet_txn = self
+            .get_hold_transaction(wallet_info.client_id, wallet_info.user_id, hold_id)
+            .await?;
+
+        if enums::WalletStatus::from_i32(wallet_txn.status)? != enums::WalletStatus::Pending {
+            warn!("凍結紀錄不是凍結中的狀態 hold_id: {}", hold_id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 以扣款前的錢包更新交易紀錄 再扣除錢包金額
+        wallet_txn.commit(&user_wallet);
+        user_wallet.commit_hold(&wallet_txn.change_amount);
+
+        // 更新db
+        let updated_wallet = self.wallet_repo.update(user_wallet).await?;
+        let wallet_txn = self.wallet_txn_repo.update(wallet_txn).await?;
+
+        self.insert_wallet_changed_event(&wallet_txn).await?;
+
+        Ok((updated_wallet, wallet_txn))
+    }
+
+    #[tracing::instrument]
+    async fn release_hold(
+        &self,
+        wallet_info: &WalletInfo,
+        hold_id: i64,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        // 先鎖定錢包再讀取凍結紀錄 同一錢包的確認與取消不會同時進行
+        let mut user_wallet = self.get_or_create_for_update(wallet_info).await?;
+        let mut wallet_txn = self
+            .get_hold_transaction(wallet_info.client_id, wallet_info.user_id, hold_id)
+            .await?;
+
+        if enums::WalletStatus::from_i32(wallet_txn.status)? != enums::WalletStatus::Pending {
+            warn!("凍結紀錄不是凍結中的狀態 hold_id: {}", hold_id);
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 解除凍結
+        user_wallet.release_hold(&wallet_txn.change_amount);
+        wallet_txn.cancel();
+
+        // 更新db
+        let updated_wallet = self.wallet_repo.update(user_wallet).await?;
+        let wallet_txn = self.wallet_txn_repo.update(wallet_txn).await?;
+
+        self.insert_wallet_changed_event(&wallet_txn).await?;
+
         Ok((updated_wallet, wallet_txn))
     }
 
+    #[tracing::instrument]
+    async fn reject_withdraw(
+        &self,
+        wallet_info: &WalletInfo,
+        withdraw_wallet_txn: &WalletTransaction,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        // 只有成功的出金可以退回
+        if enums::WalletAction::from_i32(withdraw_wallet_txn.action)?
+            != enums::WalletAction::PaymentWithdraw
+            || enums::WalletStatus::from_i32(withdraw_wallet_txn.status)?
+                != enums::WalletStatus::Success
+        {
+            warn!(
+                "交易紀錄不是成功的出金 wallet_txn_id: {}",
+                withdraw_wallet_txn.id
+            );
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        // 先鎖定錢包再檢查 同一筆出金不會被同時退回
+        self.get_or_create_for_update(wallet_info).await?;
+
+        // 已經退回或回滾過的出金 不能再退回
+        if let Some(child) = self
+            .wallet_txn_repo
+            .get_child(withdraw_wallet_txn.id)
+            .await?
+        {
+            warn!(
+                "出金交易已被處理過 wallet_txn_id: {}, child_wallet_txn_id: {}",
+                withdraw_wallet_txn.id, child.id
+            );
+            return Err(KgsStatus::InvalidArgument);
+        }
+
+        self.change_amount(
+            wallet_info,
+            withdraw_wallet_txn.id,
+            withdraw_wallet_txn.transaction_source_id,
+            withdraw_wallet_txn.change_amount.clone(),
+            &enums::WalletAction::PaymentWithdrawReject,
+        )
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn get_hold_transaction(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        hold_id: i64,
+    ) -> Result<WalletTransaction, KgsStatus> {
+        self.wallet_txn_repo
+            .get_by_hold_id(client_id, user_id, hold_id)
+            .await?
+            .ok_or_else(|| {
+                warn!("找不到凍結紀錄 hold_id: {}", hold_id);
+                KgsStatus::DataNotFound
+            })
+    }
+
     #[tracing::instrument]
     async fn is_wallet_amount_enough(
         &self,
         wallet_info: &WalletInfo,
         withdraw_amount: &BigDecimal,
     ) -> Result<bool, KgsStatus> {
-        // 獲取玩家錢包
-        let user_wallet = self.get_or_create_new_one(wallet_info).await?;
+        // 獲取玩家錢包 悲觀鎖模式下鎖定至交易結束 確保檢查後到扣款前餘額不會被其他請求修改
+        // 樂觀鎖模式下不鎖定 扣款時若重新讀取的餘額不足 由 change_amount 回傳餘額不足
+        let user_wallet = self.get_or_create_for_update(wallet_info).await?;
+
+        Ok(&user_wallet.available_amount() >= withdraw_amount)
+    }
+
+    #[tracing::instrument]
+    async fn get_transaction(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        wallet_txn_id: i64,
+    ) -> Result<WalletTransaction, KgsStatus> {
+        let wallet_txn = self
+            .wallet_txn_repo
+            .get(wallet_txn_id)
+            .await?
+            .ok_or_else(|| {
+                warn!("找不到交易紀錄 wallet_txn_id: {}", wallet_txn_id);
+                KgsStatus::DataNotFound
+            })?;
 
-        Ok(&user_wallet.amount >= withdraw_amount)
+        // 不允許查詢其他玩家的交易紀錄
+        if wallet_txn.client_id != client_id || wallet_txn.user_id != user_id {
+            warn!("交易紀錄不屬於此玩家 wallet_txn_id: {}", wallet_txn_id);
+            return Err(KgsStatus::DataNotFound);
+        }
+
+        Ok(wallet_txn)
+    }
+
+    #[tracing::instrument]
+    async fn get_transaction_list(
+        &self,
+        select_query: SelectWalletTransactionsQuery,
+    ) -> Result<WalletTransactionPage, KgsStatus> {
+        // 多取一筆 用來判斷是否有下一頁
+        let mut wallet_txn_list = self
+            .wallet_txn_repo
+            .get_list(&select_query, select_query.page_size + 1)
+            .await?;
+        let next_cursor = if wallet_txn_list.len() as u64 > select_query.page_size {
+            wallet_txn_list.truncate(select_query.page_size as usize);
+            wallet_txn_list.last().map(|wallet_txn| wallet_txn.id)
+        } else {
+            None
+        };
+
+        // 逐層取得上層交易 直到原始交易(parent_id = 0)
+        let mut parent_map: HashMap<i64, i64> = wallet_txn_list
+            .iter()
+            .map(|wallet_txn| (wallet_txn.id, wallet_txn.parent_id))
+            .collect();
+        let mut missing_ids: Vec<i64> = wallet_txn_list
+            .iter()
+            .map(|wallet_txn| wallet_txn.parent_id)
+            .filter(|parent_id| *parent_id != 0 && !parent_map.contains_key(parent_id))
+            .collect();
+        while !missing_ids.is_empty() {
+            missing_ids.sort_unstable();
+            missing_ids.dedup();
+
+            let parent_list = self.wallet_txn_repo.get_list_by_ids(missing_ids).await?;
+            for parent in parent_list.iter() {
+                parent_map.insert(parent.id, parent.parent_id);
+            }
+            missing_ids = parent_list
+                .iter()
+                .map(|parent| parent.parent_id)
+                .filter(|parent_id| *parent_id != 0 && !parent_map.contains_key(parent_id))
+                .collect();
+        }
+
+        // 組裝每筆交易的上層交易 ID 最多走訪已知的交易數量 避免資料異常時無限迴圈
+        let wallet_txn_list = wallet_txn_list
+            .into_iter()
+            .map(|wallet_txn| {
+                let mut parent_ids = Vec::new();
+                let mut parent_id = wallet_txn.parent_id;
+                while parent_id != 0 && parent_ids.len() < parent_map.len() {
+                    parent_ids.push(parent_id);
+                    parent_id = match parent_map.get(&parent_id) {
+                        Some(next_parent_id) => *next_parent_id,
+                        None => break,
+                    };
+                }
+
+                WalletTransactionWithParents {
+                    wallet_txn,
+                    parent_ids,
+                }
+            })
+            .collect();
+
+        Ok(WalletTransactionPage {
+            wallet_txn_list,
+            next_cursor,
+        })
     }
 
     #[tracing::instrument]
@@ -175,13 +815,19 @@ impl WalletServiceTrait for WalletService {
                 // 依照parent_id 排序
                 let mut sorted_index = 0;
                 while sorted_index < list.len() - 1 {
-                    for (i, item) in list[sorted_index + 1..].iter().enumerate() {
-                        if item.parent_id == list[sorted_index].id {
-                            list.swap(sorted_index + 1, sorted_index + 1 + i);
-                            sorted_index += 1;
-                            break;
-                        }
-                    }
+                    // 找不到下一筆 表示交易鏈分岔或斷開 不能一直等待
+                    let next_index = list[sorted_index + 1..]
+                        .iter()
+                        .position(|item| item.parent_id == list[sorted_index].id)
+                        .ok_or_else(|| {
+                            warn!(
+                                "交易單排序發生錯誤 找不到下一筆交易 wallet_txn_id: {}",
+                                list[sorted_index].id
+                            );
+                            KgsStatus::InternalServerError
+                        })?;
+                    list.swap(sorted_index + 1, sorted_index + 1 + next_index);
+                    sorted_index += 1;
                 }
 
                 Ok(list)
@@ -194,11 +840,121 @@ impl WalletServiceTrait for WalletService {
         })
     }
 
+    #[tracing::instrument]
+    async fn get_processed_transaction(
+        &self,
+        wallet_info: &WalletInfo,
+        source_txn_id: i64,
+        amount: &BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<Option<WalletTransaction>, KgsStatus> {
+        let wallet_txn = self
+            .wallet_txn_repo
+            .get_root_by_transaction_source_id(
+                wallet_info.client_id,
+                wallet_info.user_id,
+                source_txn_id,
+                action.to_id(),
+            )
+            .await?;
+
+        match wallet_txn {
+            Some(wallet_txn) => {
+                // 相同的交易單號 但內容不同 不能視為重送
+                if wallet_txn.currency_id != wallet_info.currency.id
+                    || wallet_txn.wallet_source_id != wallet_info.wallet_source.id
+                    || &wallet_txn.change_amount != amount
+                {
+                    warn!(
+                        "交易單號重複但內容不同 source_txn_id: {}, origin amount: {}, amount: {}",
+                        source_txn_id, wallet_txn.change_amount, amount
+                    );
+                    return Err(KgsStatus::DuplicateTransactionAmountError);
+                }
+
+                Ok(Some(wallet_txn))
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[tracing::instrument]
+    async fn get_processed_payment(
+        &self,
+        wallet_info: &WalletInfo,
+        payment_order_id: &str,
+        amount: &BigDecimal,
+        action: &enums::WalletAction,
+    ) -> Result<Option<WalletTransaction>, KgsStatus> {
+        let wallet_txn = self
+            .wallet_txn_repo
+            .get_by_payment_order_id(wallet_info.client_id, payment_order_id)
+            .await?;
+
+        match wallet_txn {
+            Some(wallet_txn) => {
+                // 相同的金流訂單 但內容不同 不能視為重送
+                if wallet_txn.user_id != wallet_info.user_id
+                    || wallet_txn.currency_id != wallet_info.currency.id
+                    || wallet_txn.wallet_source_id != wallet_info.wallet_source.id
+                    || wallet_txn.action != action.to_id()
+                    || &wallet_txn.change_amount != amount
+                {
+                    warn!(
+                        "金流訂單重複但內容不同 payment_order_id: {}, origin amount: {}, amount: {}",
+                        payment_order_id, wallet_txn.change_amount, amount
+                    );
+                    return Err(KgsStatus::DuplicateTransactionAmountError);
+                }
+
+                Ok(Some(wallet_txn))
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[tracing::instrument]
+    async fn get_payment_transaction(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        payment_order_id: &str,
+    ) -> Result<WalletTransaction, KgsStatus> {
+        let wallet_txn = self
+            .wallet_txn_repo
+            .get_by_payment_order_id(client_id, payment_order_id)
+            .await?
+            .ok_or_else(|| {
+                warn!("找不到金流訂單 payment_order_id: {}", payment_order_id);
+                KgsStatus::DataNotFound
+            })?;
+
+        // 不允許查詢其他玩家的金流訂單
+        if wallet_txn.user_id != user_id {
+            warn!(
+                "金流訂單不屬於此玩家 payment_order_id: {}",
+                payment_order_id
+            );
+            return Err(KgsStatus::DataNotFound);
+        }
+
+        Ok(wallet_txn)
+    }
+
     async fn rollback_transaction(
         &self,
         wallet_info: &WalletInfo,
         need_wallet_txn: &WalletTransaction,
     ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        // 凍結中或已取消的交易 錢包金額沒有變動過 不能rollback
+        if enums::WalletStatus::from_i32(need_wallet_txn.status)? != enums::WalletStatus::Success {
+            warn!(
+                "交易紀錄不是成功的狀態 wallet_txn_id: {}",
+                need_wallet_txn.id
+            );
+            return Err(KgsStatus::InvalidArgument);
+        }
+
         // rollback 錢包金額
         let rollback_amount = need_wallet_txn.change_amount.abs();
         let rollback_action = match enums::WalletAction::from_i32(need_wallet_txn.action)? {
@@ -206,14 +962,38 @@ impl WalletServiceTrait for WalletService {
             enums::WalletAction::GameWithdraw => enums::WalletAction::GameDeposit,
             enums::WalletAction::PaymentDeposit => enums::WalletAction::PaymentWithdraw,
             enums::WalletAction::PaymentWithdraw => enums::WalletAction::PaymentDeposit,
+            enums::WalletAction::TransferOut => enums::WalletAction::TransferIn,
+            enums::WalletAction::TransferIn => enums::WalletAction::TransferOut,
+            enums::WalletAction::PaymentWithdrawReject => enums::WalletAction::PaymentWithdraw,
+            enums::WalletAction::BonusGrant => enums::WalletAction::BonusCancel,
+            enums::WalletAction::BonusCancel => enums::WalletAction::BonusGrant,
+            enums::WalletAction::Adjustment => {
+                warn!(
+                    "調整的交易紀錄不能rollback wallet_txn_id: {}",
+                    need_wallet_txn.id
+                );
+                return Err(KgsStatus::InvalidArgument);
+            }
+            enums::WalletAction::BonusForfeit => {
+                warn!(
+                    "獎金沒收的交易紀錄不能rollback wallet_txn_id: {}",
+                    need_wallet_txn.id
+                );
+                return Err(KgsStatus::InvalidArgument);
+            }
         };
 
-        self.change_amount(
+        // 回滾不檢查餘額 已經花掉部分的入金仍要能回滾
+        self.change_amount_with_payment(
             &wallet_info,
             need_wallet_txn.id,
             need_wallet_txn.transaction_source_id,
             rollback_amount,
             &rollback_action,
+            ChangeAmountOptions {
+                check_balance: false,
+                ..Default::default()
+            },
         )
         .await
     }
@@ -231,4 +1011,163 @@ impl WalletServiceTrait for WalletService {
             }
         }
     }
+
+    #[tracing::instrument]
+    async fn get_or_create_for_update(
+        &self,
+        wallet_info: &WalletInfo,
+    ) -> Result<UserWallet, KgsStatus> {
+        // 樂觀鎖模式下不鎖定 由更新時比對版本號避免覆蓋其他交易的修改
+        let user_wallet = match self.lock_strategy {
+            LockStrategy::Pessimistic => self.wallet_repo.get_for_update(wallet_info).await?,
+            LockStrategy::Optimistic { .. } => self.wallet_repo.get(wallet_info).await?,
+        };
+
+        match user_wallet {
+            Some(wallet) => Ok(wallet),
+
+            // 新插入的資料在交易結束前 其他交易無法修改 不需要再額外鎖定
+            None => {
+                let new_wallet = UserWallet::new(wallet_info).await;
+                self.wallet_repo.insert(new_wallet).await
+            }
+        }
+    }
+
+    #[tracing::instrument]
+    async fn get_transaction_list_by_source_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        source_txn_id: i64,
+    ) -> Result<Vec<WalletTransaction>, KgsStatus> {
+        self.wallet_txn_repo
+            .get_list_by_transaction_source_id(client_id, user_id, source_txn_id)
+            .await
+    }
+}
+
+/// 修改錢包金額時的附加選項
+#[derive(Debug)]
+struct ChangeAmountOptions {
+    /// 金流訂單參考 金流出入金時寫入交易紀錄
+    payment: Option<PaymentReference>,
+    /// 扣款前是否檢查可用餘額 回滾時不檢查
+    check_balance: bool,
+    /// 合併錢包的原始交易
+    combined_leg: bool,
+}
+
+impl Default for ChangeAmountOptions {
+    fn default() -> Self {
+        Self {
+            payment: None,
+            check_balance: true,
+            combined_leg: false,
+        }
+    }
+}
+
+impl WalletService {
+    /// 修改錢包金額 並新增交易紀錄 金流出入金時交易紀錄帶有金流訂單參考
+    #[tracing::instrument]
+    async fn change_amount_with_payment(
+        &self,
+        wallet_info: &WalletInfo,
+        parent_wallet_txn_id: i64,
+        source_txn_id: i64,
+        amount: BigDecimal,
+        action: &enums::WalletAction,
+        options: ChangeAmountOptions,
+    ) -> Result<(UserWallet, WalletTransaction), KgsStatus> {
+        let max_retries = match self.lock_strategy {
+            LockStrategy::Pessimistic => 0,
+            LockStrategy::Optimistic { max_retries } => max_retries,
+        };
+        let check_balance = options.check_balance
+            && matches!(
+                action,
+                enums::WalletAction::GameWithdraw
+                    | enums::WalletAction::PaymentWithdraw
+                    | enums::WalletAction::TransferOut
+                    | enums::WalletAction::BonusForfeit
+                    | enums::WalletAction::BonusCancel
+            );
+        let mut retries = 0;
+
+        // 樂觀鎖模式下 版本衝突時重新讀取錢包 以最新的金額重新計算
+        let (updated_wallet, wallet_txn) = loop {
+            // 獲取玩家錢包 悲觀鎖模式下鎖定至交易結束 避免併發修改
+            let mut user_wallet = self.get_or_create_for_update(wallet_info).await?;
+
+            // 樂觀鎖模式下讀取時沒有鎖定 呼叫端的餘額檢查可能已經過時
+            // 每次讀取後都以最新的可用金額再檢查一次 避免併發扣款造成餘額為負
+            if check_balance && user_wallet.available_amount() < amount {
+                warn!("錢包餘額不足 user_wallet_id: {}", user_wallet.id);
+                return Err(KgsStatus::WalletAmountNotEnough);
+            }
+
+            // 創建交易紀錄
+            let mut wallet_txn = WalletTransaction::create_before_change(
+                &user_wallet,
+                parent_wallet_txn_id,
+                source_txn_id,
+                action,
+                &amount,
+            )
+            .await;
+            wallet_txn.payment = options.payment.clone();
+            wallet_txn.combined_leg = options.combined_leg;
+
+            // 更新錢包
+            match action {
+                enums::WalletAction::GameDeposit
+                | enums::WalletAction::PaymentDeposit
+                | enums::WalletAction::TransferIn
+                | enums::WalletAction::PaymentWithdrawReject
+                | enums::WalletAction::BonusGrant => {
+                    user_wallet.deposit(&amount);
+                }
+                enums::WalletAction::GameWithdraw
+                | enums::WalletAction::PaymentWithdraw
+                | enums::WalletAction::TransferOut
+                | enums::WalletAction::BonusForfeit
+                | enums::WalletAction::BonusCancel => {
+                    user_wallet.withdraw(&amount);
+                }
+                // 調整的金額有正負
+                enums::WalletAction::Adjustment => {
+                    user_wallet.deposit(&amount);
+                }
+            }
+
+            // 更新db
+            match self.wallet_repo.update(user_wallet).await {
+                Ok(updated_wallet) => break (updated_wallet, wallet_txn),
+                Err(KgsStatus::ConcurrentUpdateConflict) if retries < max_retries => {
+                    retries += 1;
+                    warn!("錢包版本衝突 第 {} 次重試", retries);
+                }
+                Err(err) => return Err(err),
+            }
+        };
+
+        let wallet_txn = self.wallet_txn_repo.insert(wallet_txn).await?;
+
+        // 在同一個交易內寫入事件 交易回滾時事件也不會發送
+        self.insert_wallet_changed_event(&wallet_txn).await?;
+
+        Ok((updated_wallet, wallet_txn))
+    }
+
+    /// 寫入錢包餘額變動事件 由 outbox relay 在交易提交後發送
+    #[tracing::instrument]
+    async fn insert_wallet_changed_event(
+        &self,
+        wallet_txn: &WalletTransaction,
+    ) -> Result<(), KgsStatus> {
+        let outbox = WalletOutbox::wallet_changed(wallet_txn).await?;
+        self.outbox_repo.insert(outbox).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,445 @@
+//! 以記憶體資料庫測試 WalletService 的金額變動 交易提交與回滾
+
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use kgs_err::models::status::Status as KgsStatus;
+
+use crate::domain::*;
+use crate::enums;
+use crate::infrastructure::memory_impl::*;
+
+fn build_wallet_service(db: &Arc<MemoryDatabase>) -> WalletService {
+    WalletService::new(
+        Arc::new(MemoryWalletTransactionRepository::new(db.clone())),
+        Arc::new(MemoryUserWalletRepository::new(db.clone())),
+        Arc::new(MemoryWalletOutboxRepository::new(db.clone())),
+        LockStrategy::Pessimistic,
+    )
+}
+
+fn wallet_info() -> WalletInfo {
+    WalletInfo {
+        client_id: 1,
+        user_id: 1,
+        currency: Currency {
+            id: 1,
+            name: "TWD".to_string(),
+        },
+        wallet_source: WalletSource {
+            id: enums::WalletSource::Normal.to_id(),
+            name: "normal".to_string(),
+            create_at: chrono::Utc::now().naive_utc(),
+        },
+    }
+}
+
+/// 資料庫中的錢包金額
+fn wallet_amount(db: &MemoryDatabase) -> BigDecimal {
+    db.read(|tables| tables.user_wallets[0].amount.clone())
+}
+
+#[tokio::test]
+async fn change_amount_commit() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    let (user_wallet, wallet_txn) = db
+        .transaction(|| {
+            wallet_service.change_amount(
+                &wallet_info,
+                0,
+                1,
+                BigDecimal::from(100),
+                &enums::WalletAction::GameDeposit,
+            )
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(user_wallet.amount, BigDecimal::from(100));
+    assert_eq!(wallet_txn.before_amount, BigDecimal::from(0));
+    assert_eq!(wallet_txn.after_amount, BigDecimal::from(100));
+    assert!(!db.in_transaction());
+    assert_eq!(wallet_amount(&db), BigDecimal::from(100));
+    db.read(|tables| {
+        assert_eq!(tables.wallet_txns.len(), 1);
+        assert_eq!(tables.wallet_outboxes.len(), 1);
+    });
+}
+
+#[tokio::test]
+async fn change_amount_not_enough_rollback() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    db.transaction(|| {
+        wallet_service.change_amount(
+            &wallet_info,
+            0,
+            1,
+            BigDecimal::from(100),
+            &enums::WalletAction::GameDeposit,
+        )
+    })
+    .await
+    .unwrap();
+
+    let result = db
+        .transaction(|| {
+            wallet_service.change_amount(
+                &wallet_info,
+                0,
+                2,
+                BigDecimal::from(150),
+                &enums::WalletAction::GameWithdraw,
+            )
+        })
+        .await;
+
+    assert!(matches!(result, Err(KgsStatus::WalletAmountNotEnough)));
+    assert!(!db.in_transaction());
+    assert_eq!(wallet_amount(&db), BigDecimal::from(100));
+    db.read(|tables| assert_eq!(tables.wallet_txns.len(), 1));
+}
+
+#[tokio::test]
+async fn change_amount_error_rollback_all_changes() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    // 同一個交易中 前面成功的修改也要一起回滾
+    let result: Result<(), KgsStatus> = db
+        .transaction(|| async move {
+            wallet_service
+                .change_amount(
+                    &wallet_info,
+                    0,
+                    1,
+                    BigDecimal::from(100),
+                    &enums::WalletAction::GameDeposit,
+                )
+                .await?;
+            Err(KgsStatus::InternalServerError)
+        })
+        .await;
+
+    assert!(result.is_err());
+    db.read(|tables| {
+        assert!(tables.user_wallets.is_empty());
+        assert!(tables.wallet_txns.is_empty());
+        assert!(tables.wallet_outboxes.is_empty());
+    });
+}
+
+#[tokio::test]
+async fn change_amount_outside_transaction() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    // 悲觀鎖模式需要在交易中鎖定錢包
+    let result = wallet_service
+        .change_amount(
+            &wallet_info,
+            0,
+            1,
+            BigDecimal::from(100),
+            &enums::WalletAction::GameDeposit,
+        )
+        .await;
+
+    assert!(matches!(result, Err(KgsStatus::InternalServerError)));
+}
+
+#[tokio::test]
+async fn rollback_transaction() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    db.transaction(|| {
+        wallet_service.change_amount(
+            &wallet_info,
+            0,
+            1,
+            BigDecimal::from(100),
+            &enums::WalletAction::GameDeposit,
+        )
+    })
+    .await
+    .unwrap();
+    let (_, withdraw_txn) = db
+        .transaction(|| {
+            wallet_service.change_amount(
+                &wallet_info,
+                0,
+                2,
+                BigDecimal::from(30),
+                &enums::WalletAction::GameWithdraw,
+            )
+        })
+        .await
+        .unwrap();
+
+    let (user_wallet, rollback_txn) = db
+        .transaction(|| wallet_service.rollback_transaction(&wallet_info, &withdraw_txn))
+        .await
+        .unwrap();
+
+    assert_eq!(user_wallet.amount, BigDecimal::from(100));
+    assert_eq!(rollback_txn.parent_id, withdraw_txn.id);
+    assert_eq!(
+        rollback_txn.action,
+        enums::WalletAction::GameDeposit.to_id()
+    );
+    assert_eq!(wallet_amount(&db), BigDecimal::from(100));
+}
+
+#[tokio::test]
+async fn rollback_spent_deposit() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    let (_, deposit_txn) = db
+        .transaction(|| {
+            wallet_service.change_amount(
+                &wallet_info,
+                0,
+                1,
+                BigDecimal::from(100),
+                &enums::WalletAction::GameDeposit,
+            )
+        })
+        .await
+        .unwrap();
+    db.transaction(|| {
+        wallet_service.change_amount(
+            &wallet_info,
+            0,
+            2,
+            BigDecimal::from(70),
+            &enums::WalletAction::GameWithdraw,
+        )
+    })
+    .await
+    .unwrap();
+
+    // 入金已經花掉一部分 回滾時不檢查餘額 與原本的行為相同
+    let (user_wallet, rollback_txn) = db
+        .transaction(|| wallet_service.rollback_transaction(&wallet_info, &deposit_txn))
+        .await
+        .unwrap();
+
+    assert_eq!(user_wallet.amount, BigDecimal::from(-70));
+    assert_eq!(rollback_txn.parent_id, deposit_txn.id);
+    assert_eq!(
+        rollback_txn.action,
+        enums::WalletAction::GameWithdraw.to_id()
+    );
+    assert_eq!(wallet_amount(&db), BigDecimal::from(-70));
+}
+
+#[tokio::test]
+async fn rollback_adjustment_rejected() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    let (_, adjustment_txn) = db
+        .transaction(|| {
+            wallet_service.change_amount(
+                &wallet_info,
+                0,
+                1,
+                BigDecimal::from(50),
+                &enums::WalletAction::Adjustment,
+            )
+        })
+        .await
+        .unwrap();
+
+    let result = db
+        .transaction(|| wallet_service.rollback_transaction(&wallet_info, &adjustment_txn))
+        .await;
+
+    assert!(matches!(result, Err(KgsStatus::InvalidArgument)));
+    assert_eq!(wallet_amount(&db), BigDecimal::from(50));
+    db.read(|tables| assert_eq!(tables.wallet_txns.len(), 1));
+}
+
+/// 獎金錢包的錢包資訊
+fn bonus_wallet_info() -> WalletInfo {
+    let mut wallet_info = wallet_info();
+    wallet_info.wallet_source = WalletSource {
+        id: enums::WalletSource::Bonus.to_id(),
+        name: "bonus".to_string(),
+        create_at: chrono::Utc::now().naive_utc(),
+    };
+    wallet_info
+}
+
+#[tokio::test]
+async fn transfer_resend() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+    let bonus_wallet_info = bonus_wallet_info();
+
+    db.transaction(|| {
+        wallet_service.change_amount(
+            &wallet_info,
+            0,
+            1,
+            BigDecimal::from(100),
+            &enums::WalletAction::PaymentDeposit,
+        )
+    })
+    .await
+    .unwrap();
+    let first = db
+        .transaction(|| {
+            wallet_service.transfer(&wallet_info, &bonus_wallet_info, 2, BigDecimal::from(40))
+        })
+        .await
+        .unwrap();
+
+    // 相同的交易單號與金額 不再轉帳
+    let resend = db
+        .transaction(|| {
+            wallet_service.transfer(&wallet_info, &bonus_wallet_info, 2, BigDecimal::from(40))
+        })
+        .await
+        .unwrap();
+
+    assert!(!first.is_resend);
+    assert!(resend.is_resend);
+    assert_eq!(resend.out_wallet_txn.id, first.out_wallet_txn.id);
+    assert_eq!(resend.in_wallet_txn.id, first.in_wallet_txn.id);
+    assert_eq!(resend.from_wallet.amount, BigDecimal::from(60));
+    assert_eq!(resend.to_wallet.amount, BigDecimal::from(40));
+    db.read(|tables| assert_eq!(tables.wallet_txns.len(), 3));
+
+    // 相同的交易單號 但金額不同
+    let result = db
+        .transaction(|| {
+            wallet_service.transfer(&wallet_info, &bonus_wallet_info, 2, BigDecimal::from(30))
+        })
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(KgsStatus::DuplicateTransactionAmountError)
+    ));
+    db.read(|tables| assert_eq!(tables.wallet_txns.len(), 3));
+}
+
+#[tokio::test]
+async fn combined_leg_unique_per_wallet() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let normal_wallet_info = wallet_info();
+    let bonus_wallet_info = bonus_wallet_info();
+
+    // 合併錢包的同一筆下注 本金與獎金錢包各有一筆原始交易
+    for wallet_info in [&normal_wallet_info, &bonus_wallet_info] {
+        db.transaction(|| {
+            wallet_service.change_amount(
+                wallet_info,
+                0,
+                1,
+                BigDecimal::from(100),
+                &enums::WalletAction::PaymentDeposit,
+            )
+        })
+        .await
+        .unwrap();
+        db.transaction(|| {
+            wallet_service.change_combined_leg_amount(
+                wallet_info,
+                2,
+                BigDecimal::from(10),
+                &enums::WalletAction::GameWithdraw,
+            )
+        })
+        .await
+        .unwrap();
+    }
+
+    // 同一個錢包不能重複
+    let result = db
+        .transaction(|| {
+            wallet_service.change_combined_leg_amount(
+                &bonus_wallet_info,
+                2,
+                BigDecimal::from(10),
+                &enums::WalletAction::GameWithdraw,
+            )
+        })
+        .await;
+    assert!(matches!(result, Err(KgsStatus::DuplicateTransaction)));
+
+    // 一般的遊戲交易不含錢包來源 其他錢包也不能使用相同的來源交易 ID
+    db.transaction(|| {
+        wallet_service.change_amount(
+            &normal_wallet_info,
+            0,
+            3,
+            BigDecimal::from(10),
+            &enums::WalletAction::GameWithdraw,
+        )
+    })
+    .await
+    .unwrap();
+    let result = db
+        .transaction(|| {
+            wallet_service.change_amount(
+                &bonus_wallet_info,
+                0,
+                3,
+                BigDecimal::from(10),
+                &enums::WalletAction::GameWithdraw,
+            )
+        })
+        .await;
+    assert!(matches!(result, Err(KgsStatus::DuplicateTransaction)));
+}
+
+#[tokio::test]
+async fn hold_resend_after_release_rejected() {
+    let db = MemoryDatabase::new();
+    let wallet_service = build_wallet_service(&db);
+    let wallet_info = wallet_info();
+
+    db.transaction(|| {
+        wallet_service.change_amount(
+            &wallet_info,
+            0,
+            1,
+            BigDecimal::from(100),
+            &enums::WalletAction::GameDeposit,
+        )
+    })
+    .await
+    .unwrap();
+    let hold = || wallet_service.hold(&wallet_info, 10, BigDecimal::from(30));
+
+    // 凍結中重送 回傳原本的凍結結果
+    let (_, hold_txn) = db.transaction(hold).await.unwrap();
+    let (_, resend_txn) = db.transaction(hold).await.unwrap();
+    assert_eq!(resend_txn.id, hold_txn.id);
+
+    // 釋放後重送 不能再回傳凍結結果
+    db.transaction(|| wallet_service.release_hold(&wallet_info, 10))
+        .await
+        .unwrap();
+    let result = db.transaction(hold).await;
+
+    assert!(matches!(result, Err(KgsStatus::InvalidArgument)));
+    assert_eq!(wallet_amount(&db), BigDecimal::from(100));
+    db.read(|tables| assert_eq!(tables.user_wallets[0].hold_amount, BigDecimal::from(0)));
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+
+use crate::domain::{BonusGrant, WalletTransaction};
+
+/// 錢包餘額變動事件
+/// 欄位有不相容的修改時 需要提高版本號 讓下游依照版本解析
+/// 凍結與取消凍結時錢包金額不變 status 為凍結中或已取消 以 hold_id 對應同一筆凍結
+#[derive(Debug, Serialize)]
+pub struct WalletChangedEvent {
+    pub event_id: i64,
+    pub event_version: i32,
+    pub wallet_txn_id: i64,
+    pub parent_id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub wallet_source_id: i64,
+    pub action: i32,
+    pub transaction_source_id: i64,
+    pub before_amount: String,
+    pub change_amount: String,
+    pub after_amount: String,
+    pub status: i32,
+    pub hold_id: Option<i64>, // 凍結單號 只有凍結產生的出金有
+    pub create_at: i64,       // unix timestamp 毫秒
+}
+
+impl WalletChangedEvent {
+    pub const EVENT_TYPE: &'static str = "wallet.changed";
+    pub const EVENT_VERSION: i32 = 1;
+
+    pub fn new(event_id: i64, wallet_txn: &WalletTransaction) -> Self {
+        Self {
+            event_id,
+            event_version: Self::EVENT_VERSION,
+            wallet_txn_id: wallet_txn.id,
+            parent_id: wallet_txn.parent_id,
+            client_id: wallet_txn.client_id,
+            user_id: wallet_txn.user_id,
+            currency_id: wallet_txn.currency_id,
+            wallet_source_id: wallet_txn.wallet_source_id,
+            action: wallet_txn.action,
+            transaction_source_id: wallet_txn.transaction_source_id,
+            before_amount: wallet_txn.before_amount.to_string(),
+            change_amount: wallet_txn.change_amount.to_string(),
+            after_amount: wallet_txn.after_amount.to_string(),
+            status: wallet_txn.status,
+            hold_id: wallet_txn.hold_id,
+            create_at: wallet_txn.update_at.and_utc().timestamp_millis(),
+        }
+    }
+}
+
+/// 獎金到期沒收事件
+/// 沒收的錢包異動另外會發送 wallet.changed 事件 這裡通知下游獎金已失效
+#[derive(Debug, Serialize)]
+pub struct BonusForfeitedEvent {
+    pub event_id: i64,
+    pub event_version: i32,
+    pub bonus_grant_id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub grant_amount: String,
+    pub forfeit_amount: String,
+    pub forfeit_txn_id: Option<i64>, // 餘額為零時沒有沒收的交易紀錄
+    pub expire_at: Option<i64>,      // unix timestamp 毫秒
+    pub create_at: i64,              // unix timestamp 毫秒
+}
+
+impl BonusForfeitedEvent {
+    pub const EVENT_TYPE: &'static str = "bonus.forfeited";
+    pub const EVENT_VERSION: i32 = 1;
+
+    pub fn new(event_id: i64, grant: &BonusGrant) -> Self {
+        Self {
+            event_id,
+            event_version: Self::EVENT_VERSION,
+            bonus_grant_id: grant.id,
+            client_id: grant.client_id,
+            user_id: grant.user_id,
+            currency_id: grant.currency_id,
+            grant_amount: grant.amount.to_string(),
+            forfeit_amount: grant
+                .forfeit_amount
+                .as_ref()
+                .map(|amount| amount.to_string())
+                .unwrap_or_default(),
+            forfeit_txn_id: grant.forfeit_txn_id,
+            expire_at: grant
+                .expire_at
+                .map(|expire_at| expire_at.and_utc().timestamp_millis()),
+            create_at: grant.update_at.and_utc().timestamp_millis(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+mod event;
 mod wallet;
 
+pub use event::*;
 pub use wallet::*;
@@ -1,4 +1,7 @@
-use crate::domain::WalletSource;
+use bigdecimal::BigDecimal;
+
+use crate::domain::{UserWallet, WalletSource, WalletTransaction};
+use crate::enums;
 
 /// 錢包基本資訊
 #[derive(Debug)]
@@ -9,6 +12,69 @@ pub struct WalletInfo {
     pub wallet_source: WalletSource,
 }
 
+impl WalletInfo {
+    /// 相同 client 幣別 錢包來源的另一個玩家 批次處理時只需要查詢一次幣別與錢包來源
+    pub fn for_user(&self, user_id: i64) -> WalletInfo {
+        WalletInfo {
+            client_id: self.client_id,
+            user_id,
+            currency: self.currency.clone(),
+            wallet_source: self.wallet_source.clone(),
+        }
+    }
+}
+
+/// 金流訂單的外部參考 記錄在出入金的原始交易上
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentReference {
+    pub order_id: String, // 金流訂單 ID 同一個 client 不可重複
+    pub channel: String,  // 金流渠道
+    pub operator: String, // 操作人員
+}
+
+/// 發放獎金的條件
+#[derive(Debug, Clone)]
+pub struct BonusTerms {
+    pub campaign_id: Option<String>, // 活動 ID 由入金發放的獎金沒有活動 ID
+    pub rollover_rate: BigDecimal,   // 流水倍率
+    pub expire_at: Option<chrono::NaiveDateTime>, // 到期時間 未指定時使用預設的有效期限
+    pub create_by: i64,              // 發放者 ID
+}
+
+/// 遊戲上分的遊戲資訊 用來取得遊戲流水貢獻比例
+#[derive(Debug, Clone)]
+pub struct GameInfo {
+    pub game_category: String, // 遊戲類別
+    pub game_id: String,       // 遊戲 ID 未提供時為空字串
+}
+
+/// 合併錢包下注時的扣款順序
+#[derive(Debug, Clone)]
+pub enum DeductOrder {
+    BonusFirst,
+    NormalFirst,
+}
+
+impl DeductOrder {
+    /// 依照扣款順序排列的錢包來源
+    pub fn wallet_sources(&self) -> [enums::WalletSource; 2] {
+        match self {
+            DeductOrder::BonusFirst => [enums::WalletSource::Bonus, enums::WalletSource::Normal],
+            DeductOrder::NormalFirst => [enums::WalletSource::Normal, enums::WalletSource::Bonus],
+        }
+    }
+}
+
+/// 修改錢包時的鎖定方式 只適用於 user_wallet
+/// 流水主表在兩種模式下都以 `SELECT ... FOR UPDATE` 鎖定 鎖定期間版本號不會衝突 不需要重試
+#[derive(Debug, Clone)]
+pub enum LockStrategy {
+    /// 讀取錢包時鎖定到交易結束
+    Pessimistic,
+    /// 讀取錢包時不鎖定 更新時比對版本號 版本衝突時重新讀取錢包再修改 最多重試 `max_retries` 次
+    Optimistic { max_retries: u32 },
+}
+
 #[derive(Debug, Clone)]
 pub struct Currency {
     pub id: i64,
@@ -24,3 +90,50 @@ pub struct SelectWalletsQuery {
     pub page: u64,
     pub page_size: u64,
 }
+
+/// 交易紀錄查詢條件 空的列表表示不篩選
+#[derive(Debug)]
+pub struct SelectWalletTransactionsQuery {
+    pub client_id: i64,
+    pub player_ids: Vec<i64>,
+    pub currency_ids: Vec<i64>,
+    pub wallet_source_ids: Vec<i64>,
+    pub actions: Vec<i32>,
+    pub statuses: Vec<i32>,
+    pub start_time: Option<chrono::NaiveDateTime>, // 建立時間 包含
+    pub end_time: Option<chrono::NaiveDateTime>,   // 建立時間 不包含
+    pub cursor: Option<i64>,                       // 只取交易 ID 小於游標的資料
+    pub page_size: u64,
+}
+
+/// 流水紀錄查詢條件
+#[derive(Debug)]
+pub struct SelectRolloverRecordsQuery {
+    pub client_id: i64,
+    pub rollover_main: RolloverMainFilter,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+/// 指定查詢的流水主表
+#[derive(Debug)]
+pub enum RolloverMainFilter {
+    /// 依照流水主表 ID
+    MainId(i64),
+    /// 依照玩家 幣別 錢包來源
+    Wallet {
+        user_id: i64,
+        currency_id: i64,
+        wallet_source_id: i64,
+    },
+}
+
+/// 轉帳結果
+#[derive(Debug)]
+pub struct TransferResult {
+    pub from_wallet: UserWallet,           // 轉出後的錢包
+    pub out_wallet_txn: WalletTransaction, // 轉出的交易紀錄
+    pub to_wallet: UserWallet,             // 轉入後的錢包
+    pub in_wallet_txn: WalletTransaction,  // 轉入的交易紀錄
+    pub is_resend: bool,                   // 重送的請求 交易已處理過 錢包沒有修改
+}
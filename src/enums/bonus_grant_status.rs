@@ -0,0 +1,30 @@
+use kgs_err::models::status::Status as KgsStatus;
+
+#[derive(Debug, PartialEq)]
+pub enum BonusGrantStatus {
+    Active = 0,    // 有效
+    Released = 1,  // 流水達成 已轉入本金錢包
+    Forfeited = 2, // 到期沒收
+    Cancelled = 3, // 已取消
+}
+
+impl BonusGrantStatus {
+    pub fn from_i32(value: i32) -> Result<BonusGrantStatus, KgsStatus> {
+        match value {
+            0 => Ok(BonusGrantStatus::Active),
+            1 => Ok(BonusGrantStatus::Released),
+            2 => Ok(BonusGrantStatus::Forfeited),
+            3 => Ok(BonusGrantStatus::Cancelled),
+            _ => Err(KgsStatus::InvalidArgument),
+        }
+    }
+
+    pub fn to_id(&self) -> i32 {
+        match self {
+            BonusGrantStatus::Active => 0,
+            BonusGrantStatus::Released => 1,
+            BonusGrantStatus::Forfeited => 2,
+            BonusGrantStatus::Cancelled => 3,
+        }
+    }
+}
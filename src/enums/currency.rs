@@ -1,8 +1,8 @@
-// use kgs_err::models::status::Status as KgsStatus;
+use kgs_err::models::status::Status as KgsStatus;
 
 #[derive(Debug, PartialEq)]
 pub enum CurrencyStatus {
-    // Disable = 0,
+    Disable = 0,
     Enable = 1,
 }
 
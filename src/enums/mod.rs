@@ -1,10 +1,16 @@
+mod bonus_grant_status;
 mod currency;
+mod outbox_status;
+mod reconciliation_status;
 mod rollover;
 mod wallet_action;
 mod wallet_source;
 mod wallet_status;
 
+pub use bonus_grant_status::*;
 pub use currency::*;
+pub use outbox_status::*;
+pub use reconciliation_status::*;
 pub use rollover::*;
 pub use wallet_action::*;
 pub use wallet_source::*;
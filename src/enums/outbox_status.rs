@@ -0,0 +1,24 @@
+use kgs_err::models::status::Status as KgsStatus;
+
+#[derive(Debug, PartialEq)]
+pub enum OutboxStatus {
+    Pending = 0,
+    Published = 1,
+}
+
+impl OutboxStatus {
+    pub fn from_i32(value: i32) -> Result<OutboxStatus, KgsStatus> {
+        match value {
+            0 => Ok(OutboxStatus::Pending),
+            1 => Ok(OutboxStatus::Published),
+            _ => Err(KgsStatus::InvalidArgument),
+        }
+    }
+
+    pub fn to_id(&self) -> i32 {
+        match self {
+            OutboxStatus::Pending => 0,
+            OutboxStatus::Published => 1,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use kgs_err::models::status::Status as KgsStatus;
+
+#[derive(Debug, PartialEq)]
+pub enum ReconciliationStatus {
+    Pending = 0,  // 待審核
+    Adjusted = 1, // 已調整
+}
+
+impl ReconciliationStatus {
+    pub fn from_i32(value: i32) -> Result<ReconciliationStatus, KgsStatus> {
+        match value {
+            0 => Ok(ReconciliationStatus::Pending),
+            1 => Ok(ReconciliationStatus::Adjusted),
+            _ => Err(KgsStatus::InvalidArgument),
+        }
+    }
+
+    pub fn to_id(&self) -> i32 {
+        match self {
+            ReconciliationStatus::Pending => 0,
+            ReconciliationStatus::Adjusted => 1,
+        }
+    }
+}
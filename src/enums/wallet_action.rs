@@ -7,6 +7,12 @@ pub enum WalletAction {
     PaymentDeposit = 3,
     PaymentWithdraw = 4,
     PaymentWithdrawReject = 5,
+    TransferOut = 6,
+    TransferIn = 7,
+    Adjustment = 8,   // 對帳調整 金額有正負
+    BonusForfeit = 9, // 獎金到期沒收
+    BonusGrant = 10,  // 發放獎金
+    BonusCancel = 11, // 取消獎金
 }
 
 impl WalletAction {
@@ -16,7 +22,13 @@ impl WalletAction {
             2 => Ok(WalletAction::GameWithdraw),
             3 => Ok(WalletAction::PaymentDeposit),
             4 => Ok(WalletAction::PaymentWithdraw),
-            5 => Ok(Action::PaymentWithdrawReject),
+            5 => Ok(WalletAction::PaymentWithdrawReject),
+            6 => Ok(WalletAction::TransferOut),
+            7 => Ok(WalletAction::TransferIn),
+            8 => Ok(WalletAction::Adjustment),
+            9 => Ok(WalletAction::BonusForfeit),
+            10 => Ok(WalletAction::BonusGrant),
+            11 => Ok(WalletAction::BonusCancel),
             _ => Err(KgsStatus::InvalidArgument),
         }
     }
@@ -27,7 +39,13 @@ impl WalletAction {
             WalletAction::GameWithdraw => 2,
             WalletAction::PaymentDeposit => 3,
             WalletAction::PaymentWithdraw => 4,
-            Action::PaymentWithdrawReject => 5,
+            WalletAction::PaymentWithdrawReject => 5,
+            WalletAction::TransferOut => 6,
+            WalletAction::TransferIn => 7,
+            WalletAction::Adjustment => 8,
+            WalletAction::BonusForfeit => 9,
+            WalletAction::BonusGrant => 10,
+            WalletAction::BonusCancel => 11,
         }
     }
 }
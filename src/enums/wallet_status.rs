@@ -1,7 +1,10 @@
+use kgs_err::models::status::Status as KgsStatus;
+
+#[derive(Debug, PartialEq)]
 pub enum WalletStatus {
-    // Pending = 0,
+    Pending = 0,
     Success = 1,
-    // Cancel = 2,
+    Cancel = 2,
 }
 
 impl WalletStatus {
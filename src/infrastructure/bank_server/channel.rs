@@ -0,0 +1,20 @@
+use once_cell::sync::Lazy;
+use tonic::transport::{Channel, Endpoint};
+
+use crate::config;
+
+/// 共用的 bank server 連線
+/// 使用 connect_lazy 第一次呼叫時才建立連線 斷線時會自動重連
+/// Channel 可以直接 clone 共用同一條 HTTP/2 連線 不需要每次呼叫都重新連線
+static BANK_SERVER_CHANNEL: Lazy<Channel> = Lazy::new(|| {
+    let config = config::get_bank_server();
+    let addr = format!("{}:{}", config.bank_server_host, config.bank_server_port);
+
+    Endpoint::from_shared(addr)
+        .expect("Bank_Server_Host 格式錯誤")
+        .connect_lazy()
+});
+
+pub fn get_channel() -> Channel {
+    BANK_SERVER_CHANNEL.clone()
+}
@@ -5,18 +5,9 @@ use protos::client_currency::CurrencyModel;
 use protos::client_currency::GetByIdRequest;
 use protos::client_currency::GetByNameRequest;
 
-use crate::config;
-
 #[tracing::instrument]
 async fn get_client() -> Result<ClientCurrencyClient<tonic::transport::Channel>, KgsStatus> {
-    let config = config::get_bank_server();
-    let addr = format!("{}:{}", config.bank_server_host, config.bank_server_port);
-    let client = ClientCurrencyClient::connect(addr).await.map_err(|e| {
-        warn!("connect bank server err:{:#?}", e);
-        KgsStatus::InternalServerError
-    })?;
-
-    Ok(client)
+    Ok(ClientCurrencyClient::new(super::channel::get_channel()))
 }
 
 #[tracing::instrument]
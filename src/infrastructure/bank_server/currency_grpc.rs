@@ -1,20 +1,12 @@
 use kgs_err::models::status::Status as KgsStatus;
 use kgs_tracing::{tracing, warn};
 
-use crate::config;
 use crate::enums;
 use protos::currency::{currency_client::CurrencyClient, GetRequest, GetResponse};
 
 #[tracing::instrument]
 async fn get_client() -> Result<CurrencyClient<tonic::transport::Channel>, KgsStatus> {
-    let config = config::get_bank_server();
-    let addr = format!("{}:{}", config.bank_server_host, config.bank_server_port);
-    let client = CurrencyClient::connect(addr).await.map_err(|e| {
-        warn!("connect bank server err:{:#?}", e);
-        KgsStatus::InternalServerError
-    })?;
-
-    Ok(client)
+    Ok(CurrencyClient::new(super::channel::get_channel()))
 }
 
 #[tracing::instrument]
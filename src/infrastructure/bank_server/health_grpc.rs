@@ -0,0 +1,26 @@
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+use tonic::Code;
+use tonic_health::pb::{health_client::HealthClient, HealthCheckRequest};
+
+/// 確認 bank server 可以連線
+/// bank server 沒有提供 health 服務時會回傳 Unimplemented 仍表示連線正常
+/// ### 回傳
+/// - `Result<(), KgsStatus>` - 無法連線時回傳錯誤
+#[tracing::instrument]
+pub async fn check_bank_server_health() -> Result<(), KgsStatus> {
+    let mut client = HealthClient::new(super::channel::get_channel());
+
+    let request = kgs_tracing::tonic::create_request_with_span(HealthCheckRequest {
+        service: String::new(),
+    });
+
+    match client.check(request).await {
+        Ok(_) => Ok(()),
+        Err(e) if e.code() == Code::Unimplemented => Ok(()),
+        Err(e) => {
+            warn!("check bank server health err:{:#?}", e);
+            Err(KgsStatus::InternalServerError)
+        }
+    }
+}
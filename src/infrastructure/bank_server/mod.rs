@@ -1,5 +1,8 @@
+mod channel;
 mod client_currency_grpc;
 mod currency_grpc;
+mod health_grpc;
 
 pub use client_currency_grpc::*;
 pub use currency_grpc::*;
+pub use health_grpc::*;
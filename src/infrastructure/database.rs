@@ -0,0 +1,221 @@
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Duration;
+
+use context::common::context::{Context, FutureExt};
+use context::common::db_impl::{LogLevel, SeaPostgres, SeaPostgresBuilder};
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{info, tracing, warn};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions, PgSslMode};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DatabaseTransaction, SqlxPostgresConnector};
+
+use crate::config::{MigrationMode, WalletDb};
+
+/// 讀取用的資料庫
+///
+/// 放在 Context 中 只有查詢類的 RPC 會透過 `with_read_replica` 切換過去
+#[derive(Clone)]
+pub struct ReadReplica(pub SeaPostgres);
+
+/// 建立錢包資料庫連線
+///
+/// 建立連線池前會先連一次資料庫 連不上就直接回傳錯誤 避免服務啟動後才在請求中失敗
+///
+/// ### 參數
+/// - `config`: 錢包資料庫設定
+/// - `host`: 資料庫位置 主庫或讀取用的副本
+/// - `port`: 資料庫 port
+///
+/// ### 回傳
+/// - `Result<SeaPostgres, String>`: 資料庫連線 或連線失敗的原因
+#[tracing::instrument(skip(config))]
+pub async fn build_wallet_db(
+    config: &WalletDb,
+    host: &str,
+    port: &str,
+) -> Result<SeaPostgres, String> {
+    let connect_timeout = Duration::from_secs(config.wallet_db_connect_timeout_secs);
+    let ssl_mode = config.wallet_db_ssl_mode.as_deref().unwrap_or("prefer");
+
+    ping(config, host, port, ssl_mode, connect_timeout).await?;
+
+    let db = SeaPostgresBuilder::default()
+        .db_host(host)
+        .db_port(port)
+        .db_user(&config.wallet_db_user)
+        .db_password(&config.wallet_db_password)
+        .db_name(&config.wallet_db_name)
+        .ssl_mode(ssl_mode)
+        .max_connections(config.wallet_db_max_connection)
+        .min_connections(config.wallet_db_min_connection)
+        .connect_timeout(connect_timeout)
+        .idle_timeout(Duration::from_secs(config.wallet_db_idle_timeout_secs))
+        .sqlx_logging(true)
+        .sqlx_logging_level(LogLevel::Info)
+        .build()
+        .await;
+
+    info!(
+        "wallet db connected: {}:{}/{}",
+        host, port, config.wallet_db_name
+    );
+    Ok(db)
+}
+
+/// 用單一連線確認資料庫可以連線
+async fn ping(
+    config: &WalletDb,
+    host: &str,
+    port: &str,
+    ssl_mode: &str,
+    connect_timeout: Duration,
+) -> Result<(), String> {
+    let pool = connect_pool(config, host, port, ssl_mode, connect_timeout).await?;
+    pool.close().await;
+
+    Ok(())
+}
+
+/// 建立只有單一連線的連線池 給啟動檢查與 migration 使用
+async fn connect_pool(
+    config: &WalletDb,
+    host: &str,
+    port: &str,
+    ssl_mode: &str,
+    connect_timeout: Duration,
+) -> Result<PgPool, String> {
+    let port = port
+        .parse::<u16>()
+        .map_err(|err| format!("wallet db port 設定錯誤 {}: {:?}", port, err))?;
+    let ssl_mode = PgSslMode::from_str(ssl_mode)
+        .map_err(|err| format!("wallet db ssl mode 設定錯誤 {}: {:?}", ssl_mode, err))?;
+
+    let options = PgConnectOptions::new()
+        .host(host)
+        .port(port)
+        .username(&config.wallet_db_user)
+        .password(&config.wallet_db_password)
+        .database(&config.wallet_db_name)
+        .ssl_mode(ssl_mode);
+
+    PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(connect_timeout)
+        .connect_with(options)
+        .await
+        .map_err(|err| {
+            format!(
+                "無法連線 wallet db {}:{}/{}: {}",
+                host, port, config.wallet_db_name, err
+            )
+        })
+}
+
+/// 建立 migration 使用的連線 一律連到主庫
+///
+/// ### 參數
+/// - `config`: 錢包資料庫設定
+///
+/// ### 回傳
+/// - `Result<DatabaseConnection, String>`: 資料庫連線 或連線失敗的原因
+pub async fn connect_migration(config: &WalletDb) -> Result<DatabaseConnection, String> {
+    let ssl_mode = config.wallet_db_ssl_mode.as_deref().unwrap_or("prefer");
+    let pool = connect_pool(
+        config,
+        &config.wallet_db_host,
+        &config.wallet_db_port,
+        ssl_mode,
+        Duration::from_secs(config.wallet_db_connect_timeout_secs),
+    )
+    .await?;
+
+    Ok(SqlxPostgresConnector::from_sqlx_postgres_pool(pool))
+}
+
+/// 啟動時依設定處理尚未執行的 migration
+///
+/// - `Apply`: 執行所有尚未執行的 migration
+/// - `Check`: 有尚未執行的 migration 時回傳錯誤 不啟動服務
+/// - `Off`: 不檢查
+///
+/// ### 參數
+/// - `config`: 錢包資料庫設定
+/// - `mode`: migration 模式
+///
+/// ### 回傳
+/// - `Result<(), String>`: 失敗的原因
+#[tracing::instrument(skip(config))]
+pub async fn migrate_on_startup(config: &WalletDb, mode: &MigrationMode) -> Result<(), String> {
+    if *mode == MigrationMode::Off {
+        return Ok(());
+    }
+
+    let db = connect_migration(config).await?;
+    let pending = Migrator::get_pending_migrations(&db)
+        .await
+        .map_err(|err| format!("取得尚未執行的 migration 失敗: {}", err))?;
+
+    if pending.is_empty() {
+        info!("wallet db schema is up to date");
+    } else if *mode == MigrationMode::Apply {
+        info!("apply {} pending migrations", pending.len());
+        Migrator::up(&db, None)
+            .await
+            .map_err(|err| format!("執行 migration 失敗: {}", err))?;
+    } else {
+        let names = pending
+            .iter()
+            .map(|migration| migration.name().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!(
+            "wallet db schema 尚未更新 尚未執行的 migration: {}",
+            names
+        ));
+    }
+
+    db.close()
+        .await
+        .map_err(|err| format!("關閉 migration 連線失敗: {}", err))
+}
+
+/// 將查詢切換到讀取用的資料庫執行
+///
+/// 沒有設定讀取用的資料庫時 仍使用主庫
+/// 副本會有複寫延遲 只能用在不需要讀到最新資料的查詢
+///
+/// ### 參數
+/// - `f`: 要執行的查詢
+///
+/// ### 回傳
+/// - `F::Output`: 查詢結果
+pub async fn with_read_replica<F: Future>(f: F) -> F::Output {
+    let cx = Context::current();
+    match cx.get::<ReadReplica>() {
+        Some(replica) => {
+            let read_cx = cx.with_value(replica.0.clone());
+            f.with_context(read_cx).await
+        }
+        None => f.await,
+    }
+}
+
+/// 在目前的交易中執行簡單的查詢 確認資料庫可以使用
+/// ### 回傳
+/// - `Result<(), KgsStatus>` - 資料庫無法使用時回傳錯誤
+#[tracing::instrument]
+pub async fn ping_current() -> Result<(), KgsStatus> {
+    let cx = Context::current();
+    let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+        warn!("get database transaction error");
+        KgsStatus::InternalServerError
+    })?;
+
+    txn.execute_unprepared("SELECT 1").await.map_err(|err| {
+        warn!("ping wallet db failed: {:?}", err);
+        KgsStatus::InternalServerError
+    })?;
+
+    Ok(())
+}
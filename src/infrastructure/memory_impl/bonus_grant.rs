@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+use crate::enums;
+
+#[derive(Debug)]
+pub struct MemoryBonusGrantRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryBonusGrantRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::BonusGrantRepositoryTrait for MemoryBonusGrantRepository {
+    async fn insert(&self, grant: domain::BonusGrant) -> Result<domain::BonusGrant, KgsStatus> {
+        self.db.write(|tables| {
+            tables.bonus_grants.push(grant.clone());
+            Ok(grant)
+        })
+    }
+
+    async fn get(&self, client_id: i64, id: i64) -> Result<Option<domain::BonusGrant>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .bonus_grants
+                .iter()
+                .find(|grant| grant.id == id && grant.client_id == client_id)
+                .cloned()
+        }))
+    }
+
+    async fn get_for_update(&self, id: i64) -> Result<Option<domain::BonusGrant>, KgsStatus> {
+        self.db.check_for_update()?;
+        Ok(self.db.read(|tables| {
+            tables
+                .bonus_grants
+                .iter()
+                .find(|grant| grant.id == id)
+                .cloned()
+        }))
+    }
+
+    async fn get_active_list(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        currency_id: i64,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus> {
+        let active = enums::BonusGrantStatus::Active.to_id();
+
+        Ok(self.db.read(|tables| {
+            tables
+                .bonus_grants
+                .iter()
+                .filter(|grant| {
+                    grant.client_id == client_id
+                        && grant.user_id == user_id
+                        && grant.currency_id == currency_id
+                        && grant.status == active
+                })
+                .cloned()
+                .collect()
+        }))
+    }
+
+    async fn get_expired_list(
+        &self,
+        now: chrono::NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            let mut grants: Vec<domain::BonusGrant> = tables
+                .bonus_grants
+                .iter()
+                .filter(|grant| {
+                    grant.is_expired(now)
+                        && grant
+                            .next_forfeit_at
+                            .map_or(true, |next_forfeit_at| next_forfeit_at <= now)
+                })
+                .cloned()
+                .collect();
+            grants.sort_by_key(|grant| grant.expire_at);
+            grants.truncate(limit as usize);
+            grants
+        }))
+    }
+
+    async fn defer_forfeit(
+        &self,
+        id: i64,
+        next_forfeit_at: chrono::NaiveDateTime,
+    ) -> Result<(), KgsStatus> {
+        self.db.write(|tables| {
+            tables
+                .bonus_grants
+                .iter_mut()
+                .filter(|grant| grant.id == id)
+                .for_each(|grant| {
+                    grant.forfeit_retry_count += 1;
+                    grant.next_forfeit_at = Some(next_forfeit_at);
+                });
+        });
+        Ok(())
+    }
+
+    async fn update(&self, grant: domain::BonusGrant) -> Result<domain::BonusGrant, KgsStatus> {
+        self.db.write(|tables| {
+            let row = tables
+                .bonus_grants
+                .iter_mut()
+                .find(|row| row.id == grant.id)
+                .ok_or(KgsStatus::InternalServerError)?;
+            *row = grant.clone();
+            Ok(grant)
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use kgs_err::models::status::Status as KgsStatus;
+
+use crate::domain;
+
+/// 假的 CurrencyService 只回傳建立時給定的幣別 不區分 client
+#[derive(Debug)]
+pub struct MemoryCurrencyService {
+    currencies: Vec<domain::Currency>,
+}
+
+impl MemoryCurrencyService {
+    pub fn new(currencies: Vec<domain::Currency>) -> Self {
+        Self { currencies }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::CurrencyServiceTrait for MemoryCurrencyService {
+    async fn get_enable_currencies(
+        &self,
+        _client_id: i64,
+        currency_names: Vec<String>,
+    ) -> Result<Vec<domain::Currency>, KgsStatus> {
+        Ok(self
+            .currencies
+            .iter()
+            .filter(|currency| currency_names.is_empty() || currency_names.contains(&currency.name))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_enable_currency(
+        &self,
+        _client_id: i64,
+        currency_name: &str,
+    ) -> Result<domain::Currency, KgsStatus> {
+        self.currencies
+            .iter()
+            .find(|currency| currency.name == currency_name)
+            .cloned()
+            .ok_or(KgsStatus::NotFound)
+    }
+
+    async fn get_enable_currency_by_id(
+        &self,
+        _client_id: i64,
+        currency_id: i64,
+    ) -> Result<domain::Currency, KgsStatus> {
+        self.currencies
+            .iter()
+            .find(|currency| currency.id == currency_id)
+            .cloned()
+            .ok_or(KgsStatus::NotFound)
+    }
+
+    /// 沒有快取 不需要處理
+    fn invalidate(&self, _client_id: Option<i64>) {}
+}
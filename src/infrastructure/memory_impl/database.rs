@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use crate::domain;
+
+/// 記憶體中的資料表
+#[derive(Debug, Default, Clone)]
+pub struct MemoryTables {
+    pub user_wallets: Vec<domain::UserWallet>,
+    pub wallet_txns: Vec<domain::WalletTransaction>,
+    pub rollover_mains: Vec<domain::RolloverMain>,
+    pub rollover_records: Vec<domain::RolloverRecord>,
+    pub wallet_sources: Vec<domain::WalletSource>,
+    pub wallet_outboxes: Vec<domain::WalletOutbox>,
+    pub reconciliation_reports: Vec<domain::ReconciliationReport>,
+    pub wallet_limits: Vec<domain::WalletLimit>,
+    pub wallet_action_limits: Vec<domain::WalletActionLimit>,
+    pub bonus_grants: Vec<domain::BonusGrant>,
+    pub rollover_contributions: Vec<domain::RolloverContribution>,
+}
+
+/// 記憶體資料庫
+/// 開始交易時保存資料表的快照 回滾時還原快照
+/// 只模擬單一交易 `get_for_update` 不會真的鎖定資料 不適合測試併發
+///
+/// 應用層的 `#[transactional(SeaOrmPostgres)]` 需要真的資料庫連線
+/// 使用記憶體資料庫的測試直接呼叫 domain service 並以 `transaction` 包住 模擬提交與回滾
+#[derive(Debug, Default)]
+pub struct MemoryDatabase {
+    tables: Mutex<MemoryTables>,
+    snapshot: Mutex<Option<MemoryTables>>,
+}
+
+impl MemoryDatabase {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 新增錢包來源 測試前需要先建立本金與獎金錢包來源
+    pub fn insert_wallet_source(&self, wallet_source: domain::WalletSource) {
+        self.write(|tables| tables.wallet_sources.push(wallet_source));
+    }
+
+    /// 新增錢包限額 限額由營運直接寫入資料表 repository 只提供查詢
+    pub fn insert_wallet_limit(&self, wallet_limit: domain::WalletLimit) {
+        self.write(|tables| tables.wallet_limits.push(wallet_limit));
+    }
+
+    /// 新增單筆交易限額
+    pub fn insert_wallet_action_limit(&self, action_limit: domain::WalletActionLimit) {
+        self.write(|tables| tables.wallet_action_limits.push(action_limit));
+    }
+
+    /// 新增遊戲流水貢獻比例 與限額相同由營運直接寫入資料表
+    pub fn insert_rollover_contribution(&self, contribution: domain::RolloverContribution) {
+        self.write(|tables| tables.rollover_contributions.push(contribution));
+    }
+
+    /// 開始交易 保存目前資料表的快照
+    pub fn begin(&self) {
+        let tables = self.read(|tables| tables.clone());
+        *self.snapshot.lock().unwrap() = Some(tables);
+    }
+
+    /// 是否在交易中
+    pub fn in_transaction(&self) -> bool {
+        self.snapshot.lock().unwrap().is_some()
+    }
+
+    /// 與 `SELECT ... FOR UPDATE` 相同 只能在交易中使用
+    /// 在交易外呼叫時回傳錯誤 與 sea_orm 實作拿不到 `DatabaseTransaction` 時的行為一致
+    pub fn check_for_update(&self) -> Result<(), KgsStatus> {
+        if !self.in_transaction() {
+            return Err(KgsStatus::InternalServerError);
+        }
+        Ok(())
+    }
+
+    /// 提交交易 丟棄快照
+    pub fn commit(&self) {
+        self.snapshot.lock().unwrap().take();
+    }
+
+    /// 回滾交易 還原成開始交易時的資料表
+    pub fn rollback(&self) {
+        if let Some(tables) = self.snapshot.lock().unwrap().take() {
+            *self.tables.lock().unwrap() = tables;
+        }
+    }
+
+    /// 在交易中執行 與 `#[transactional]` 相同 回傳錯誤時回滾 成功時提交
+    pub async fn transaction<T, F, Fut>(&self, f: F) -> Result<T, KgsStatus>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, KgsStatus>>,
+    {
+        self.begin();
+        let result = f().await;
+        match result {
+            Ok(_) => self.commit(),
+            Err(_) => self.rollback(),
+        }
+        result
+    }
+
+    /// 讀取資料表
+    pub fn read<R>(&self, f: impl FnOnce(&MemoryTables) -> R) -> R {
+        f(&self.tables.lock().unwrap())
+    }
+
+    /// 修改資料表
+    pub fn write<R>(&self, f: impl FnOnce(&mut MemoryTables) -> R) -> R {
+        f(&mut self.tables.lock().unwrap())
+    }
+}
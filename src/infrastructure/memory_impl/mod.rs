@@ -0,0 +1,27 @@
+//! 以記憶體實作的 repository 與 CurrencyService 供單元測試使用
+//! 所有 repository 共用同一個 `MemoryDatabase` 以快照模擬交易的提交與回滾
+mod bonus_grant;
+mod currency;
+mod database;
+mod reconciliation_report;
+mod rollover_contribution;
+mod rollover_main;
+mod rollover_record;
+mod user_wallet;
+mod wallet_limit;
+mod wallet_outbox;
+mod wallet_source;
+mod wallet_transaction;
+
+pub use bonus_grant::MemoryBonusGrantRepository;
+pub use currency::MemoryCurrencyService;
+pub use database::MemoryDatabase;
+pub use reconciliation_report::MemoryReconciliationReportRepository;
+pub use rollover_contribution::MemoryRolloverContributionRepository;
+pub use rollover_main::MemoryRolloverMainRepository;
+pub use rollover_record::MemoryRolloverRecordRepository;
+pub use user_wallet::MemoryUserWalletRepository;
+pub use wallet_limit::MemoryWalletLimitRepository;
+pub use wallet_outbox::MemoryWalletOutboxRepository;
+pub use wallet_source::MemoryWalletSourceRepository;
+pub use wallet_transaction::MemoryWalletTransactionRepository;
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+use crate::enums;
+
+#[derive(Debug)]
+pub struct MemoryReconciliationReportRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryReconciliationReportRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::ReconciliationReportRepositoryTrait for MemoryReconciliationReportRepository {
+    async fn insert(
+        &self,
+        report: domain::ReconciliationReport,
+    ) -> Result<domain::ReconciliationReport, KgsStatus> {
+        self.db.write(|tables| {
+            tables.reconciliation_reports.push(report.clone());
+            Ok(report)
+        })
+    }
+
+    async fn get_for_update(
+        &self,
+        client_id: i64,
+        id: i64,
+    ) -> Result<Option<domain::ReconciliationReport>, KgsStatus> {
+        self.db.check_for_update()?;
+        Ok(self.db.read(|tables| {
+            tables
+                .reconciliation_reports
+                .iter()
+                .find(|report| report.id == id && report.client_id == client_id)
+                .cloned()
+        }))
+    }
+
+    async fn get_pending_list(
+        &self,
+        client_id: i64,
+    ) -> Result<Vec<domain::ReconciliationReport>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .reconciliation_reports
+                .iter()
+                .filter(|report| {
+                    report.client_id == client_id
+                        && report.status == enums::ReconciliationStatus::Pending.to_id()
+                })
+                .cloned()
+                .collect()
+        }))
+    }
+
+    async fn update(
+        &self,
+        report: domain::ReconciliationReport,
+    ) -> Result<domain::ReconciliationReport, KgsStatus> {
+        self.db.write(|tables| {
+            let row = tables
+                .reconciliation_reports
+                .iter_mut()
+                .find(|row| row.id == report.id)
+                .ok_or(KgsStatus::InternalServerError)?;
+            *row = report.clone();
+            Ok(report)
+        })
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+
+#[derive(Debug)]
+pub struct MemoryRolloverContributionRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryRolloverContributionRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::RolloverContributionRepositoryTrait for MemoryRolloverContributionRepository {
+    async fn get(
+        &self,
+        client_id: i64,
+        wallet_source_id: i64,
+        game: &domain::GameInfo,
+    ) -> Result<Option<domain::RolloverContribution>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            let find = |game_id: &str| {
+                tables
+                    .rollover_contributions
+                    .iter()
+                    .find(|contribution| {
+                        contribution.client_id == client_id
+                            && contribution.wallet_source_id == wallet_source_id
+                            && contribution.game_category == game.game_category
+                            && contribution.game_id == game_id
+                    })
+                    .cloned()
+            };
+
+            // 優先使用遊戲的設定 沒有時使用遊戲類別的設定
+            find(&game.game_id).or_else(|| find(""))
+        }))
+    }
+}
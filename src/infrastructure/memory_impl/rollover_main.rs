@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+
+#[derive(Debug)]
+pub struct MemoryRolloverMainRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryRolloverMainRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::RolloverMainRepositoryTrait for MemoryRolloverMainRepository {
+    async fn get(
+        &self,
+        wallet_info: &domain::WalletInfo,
+    ) -> Result<Option<domain::RolloverMain>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .rollover_mains
+                .iter()
+                .find(|rollover_main| {
+                    rollover_main.client_id == wallet_info.client_id
+                        && rollover_main.user_id == wallet_info.user_id
+                        && rollover_main.currency_id == wallet_info.currency.id
+                        && rollover_main.wallet_source_id == wallet_info.wallet_source.id
+                })
+                .cloned()
+        }))
+    }
+
+    async fn get_for_update(
+        &self,
+        wallet_info: &domain::WalletInfo,
+    ) -> Result<Option<domain::RolloverMain>, KgsStatus> {
+        self.db.check_for_update()?;
+        self.get(wallet_info).await
+    }
+
+    async fn update(
+        &self,
+        rollover_main: domain::RolloverMain,
+    ) -> Result<domain::RolloverMain, KgsStatus> {
+        self.db.write(|tables| {
+            let row = tables
+                .rollover_mains
+                .iter_mut()
+                .find(|row| row.id == rollover_main.id)
+                .ok_or(KgsStatus::InternalServerError)?;
+            if row.version != rollover_main.version {
+                return Err(KgsStatus::ConcurrentUpdateConflict);
+            }
+            let mut rollover_main = rollover_main;
+            rollover_main.version += 1;
+            *row = rollover_main.clone();
+            Ok(rollover_main)
+        })
+    }
+
+    async fn insert(
+        &self,
+        rollover_main: domain::RolloverMain,
+    ) -> Result<domain::RolloverMain, KgsStatus> {
+        self.db.write(|tables| {
+            if tables
+                .rollover_mains
+                .iter()
+                .any(|row| row.id == rollover_main.id)
+            {
+                return Err(KgsStatus::InternalServerError);
+            }
+            tables.rollover_mains.push(rollover_main.clone());
+            Ok(rollover_main)
+        })
+    }
+}
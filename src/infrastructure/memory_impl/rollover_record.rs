@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+
+#[derive(Debug)]
+pub struct MemoryRolloverRecordRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryRolloverRecordRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::RolloverRecordRepositoryTrait for MemoryRolloverRecordRepository {
+    async fn get(&self, id: i64) -> Result<Option<domain::RolloverRecord>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .rollover_records
+                .iter()
+                .find(|record| record.id == id)
+                .cloned()
+        }))
+    }
+
+    async fn get_opt_by_wallet_transaction_id(
+        &self,
+        wallet_txn_id: i64,
+    ) -> Result<Option<domain::RolloverRecord>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .rollover_records
+                .iter()
+                .find(|record| record.wallet_txn_id == wallet_txn_id)
+                .cloned()
+        }))
+    }
+
+    async fn insert(
+        &self,
+        rollover_record: domain::RolloverRecord,
+    ) -> Result<domain::RolloverRecord, KgsStatus> {
+        self.db.write(|tables| {
+            tables.rollover_records.push(rollover_record.clone());
+            Ok(rollover_record)
+        })
+    }
+
+    async fn get_list_with_transaction(
+        &self,
+        select_query: domain::SelectRolloverRecordsQuery,
+    ) -> Result<Vec<domain::RolloverRecordWithTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            let main_ids: Vec<i64> = tables
+                .rollover_mains
+                .iter()
+                .filter(|main| main.client_id == select_query.client_id)
+                .filter(|main| match &select_query.rollover_main {
+                    domain::RolloverMainFilter::MainId(main_id) => main.id == *main_id,
+                    domain::RolloverMainFilter::Wallet {
+                        user_id,
+                        currency_id,
+                        wallet_source_id,
+                    } => {
+                        main.user_id == *user_id
+                            && main.currency_id == *currency_id
+                            && main.wallet_source_id == *wallet_source_id
+                    }
+                })
+                .map(|main| main.id)
+                .collect();
+
+            let mut records: Vec<&domain::RolloverRecord> = tables
+                .rollover_records
+                .iter()
+                .filter(|record| main_ids.contains(&record.main_id))
+                .collect();
+            records.sort_by_key(|record| (record.main_id, record.id));
+
+            // 依照流水紀錄 ID 由舊到新累計流水
+            let mut result = Vec::with_capacity(records.len());
+            let mut current_main_id = None;
+            let mut total_requirement_rollover = BigDecimal::zero();
+            let mut total_achievement_rollover = BigDecimal::zero();
+            for record in records {
+                if current_main_id != Some(record.main_id) {
+                    current_main_id = Some(record.main_id);
+                    total_requirement_rollover = BigDecimal::zero();
+                    total_achievement_rollover = BigDecimal::zero();
+                }
+                total_requirement_rollover += &record.requirement_rollover;
+                total_achievement_rollover += &record.achievement_rollover;
+
+                let wallet_txn = tables
+                    .wallet_txns
+                    .iter()
+                    .find(|wallet_txn| wallet_txn.id == record.wallet_txn_id);
+                result.push(domain::RolloverRecordWithTransaction {
+                    rollover_record: record.clone(),
+                    wallet_txn_action: wallet_txn.map(|wallet_txn| wallet_txn.action),
+                    wallet_txn_status: wallet_txn.map(|wallet_txn| wallet_txn.status),
+                    transaction_source_id: wallet_txn
+                        .map(|wallet_txn| wallet_txn.transaction_source_id),
+                    wallet_txn_change_amount: wallet_txn
+                        .map(|wallet_txn| wallet_txn.change_amount.clone()),
+                    total_requirement_rollover: total_requirement_rollover.clone(),
+                    total_achievement_rollover: total_achievement_rollover.clone(),
+                });
+            }
+
+            result.sort_by(|a, b| b.rollover_record.id.cmp(&a.rollover_record.id));
+            result
+                .into_iter()
+                .skip(((select_query.page - 1) * select_query.page_size) as usize)
+                .take(select_query.page_size as usize)
+                .collect()
+        }))
+    }
+}
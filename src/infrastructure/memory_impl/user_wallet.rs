@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+use crate::enums;
+
+#[derive(Debug)]
+pub struct MemoryUserWalletRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryUserWalletRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::UserWalletRepositoryTrait for MemoryUserWalletRepository {
+    async fn get_user_wallets_with_rollover(
+        &self,
+        select_query: domain::SelectWalletsQuery,
+    ) -> Result<Vec<domain::UserWalletWithRollover>, KgsStatus> {
+        // 空的列表表示不篩選
+        fn matches(filter: &[i64], value: i64) -> bool {
+            filter.is_empty() || filter.contains(&value)
+        }
+
+        Ok(self.db.read(|tables| {
+            tables
+                .user_wallets
+                .iter()
+                .rev()
+                .filter(|user_wallet| {
+                    user_wallet.client_id == select_query.client_id
+                        && matches(&select_query.player_ids, user_wallet.user_id)
+                        && matches(&select_query.currency_ids, user_wallet.currency_id)
+                        && matches(
+                            &select_query.wallet_source_ids,
+                            user_wallet.wallet_source_id,
+                        )
+                })
+                .filter_map(|user_wallet| {
+                    tables
+                        .rollover_mains
+                        .iter()
+                        .find(|main| main.user_wallet_id == user_wallet.id)
+                        .map(|main| domain::UserWalletWithRollover {
+                            id: user_wallet.id,
+                            client_id: user_wallet.client_id,
+                            user_id: user_wallet.user_id,
+                            currency_id: user_wallet.currency_id,
+                            currency_name: user_wallet.currency_name.clone(),
+                            wallet_source_id: user_wallet.wallet_source_id,
+                            amount: user_wallet.amount.clone(),
+                            hold_amount: user_wallet.hold_amount.clone(),
+                            wallet_source_name: user_wallet.wallet_source_name.clone(),
+                            requirement_rollover: main.requirement_rollover.clone(),
+                            achievement_rollover: main.achievement_rollover.clone(),
+                        })
+                })
+                .skip(((select_query.page - 1) * select_query.page_size) as usize)
+                .take(select_query.page_size as usize)
+                .collect()
+        }))
+    }
+
+    async fn get(
+        &self,
+        wallet_info: &domain::WalletInfo,
+    ) -> Result<Option<domain::UserWallet>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .user_wallets
+                .iter()
+                .find(|user_wallet| {
+                    user_wallet.client_id == wallet_info.client_id
+                        && user_wallet.user_id == wallet_info.user_id
+                        && user_wallet.currency_id == wallet_info.currency.id
+                        && user_wallet.wallet_source_id == wallet_info.wallet_source.id
+                })
+                .cloned()
+        }))
+    }
+
+    async fn get_for_update(
+        &self,
+        wallet_info: &domain::WalletInfo,
+    ) -> Result<Option<domain::UserWallet>, KgsStatus> {
+        self.db.check_for_update()?;
+        self.get(wallet_info).await
+    }
+
+    async fn insert(
+        &self,
+        user_wallet: domain::UserWallet,
+    ) -> Result<domain::UserWallet, KgsStatus> {
+        self.db.write(|tables| {
+            if tables
+                .user_wallets
+                .iter()
+                .any(|row| row.id == user_wallet.id)
+            {
+                return Err(KgsStatus::InternalServerError);
+            }
+            tables.user_wallets.push(user_wallet.clone());
+            Ok(user_wallet)
+        })
+    }
+
+    async fn update(
+        &self,
+        user_wallet: domain::UserWallet,
+    ) -> Result<domain::UserWallet, KgsStatus> {
+        self.db.write(|tables| {
+            let row = tables
+                .user_wallets
+                .iter_mut()
+                .find(|row| row.id == user_wallet.id)
+                .ok_or(KgsStatus::InternalServerError)?;
+            if row.version != user_wallet.version {
+                return Err(KgsStatus::ConcurrentUpdateConflict);
+            }
+            let mut user_wallet = user_wallet;
+            user_wallet.version += 1;
+            *row = user_wallet.clone();
+            Ok(user_wallet)
+        })
+    }
+
+    async fn get_client_id_list(&self) -> Result<Vec<i64>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            let mut client_ids: Vec<i64> = tables
+                .user_wallets
+                .iter()
+                .map(|user_wallet| user_wallet.client_id)
+                .collect();
+            client_ids.sort();
+            client_ids.dedup();
+            client_ids
+        }))
+    }
+
+    async fn get_drift_list(
+        &self,
+        client_id: i64,
+        user_id: Option<i64>,
+    ) -> Result<Vec<domain::WalletDrift>, KgsStatus> {
+        self.db.read(|tables| {
+            let mut drift_list = Vec::new();
+            for user_wallet in tables.user_wallets.iter().filter(|user_wallet| {
+                user_wallet.client_id == client_id
+                    && user_id.map_or(true, |user_id| user_wallet.user_id == user_id)
+            }) {
+                // 與 sea_orm 實作相同 只計算成功的交易 依照 update_at 排序檢查前後金額
+                // 對帳調整的交易紀錄視為重新起算 之前不連續的紀錄已經審核過
+                let mut wallet_txns: Vec<&domain::WalletTransaction> = tables
+                    .wallet_txns
+                    .iter()
+                    .filter(|wallet_txn| {
+                        wallet_txn.client_id == user_wallet.client_id
+                            && wallet_txn.user_id == user_wallet.user_id
+                            && wallet_txn.currency_id == user_wallet.currency_id
+                            && wallet_txn.wallet_source_id == user_wallet.wallet_source_id
+                            && wallet_txn.status == enums::WalletStatus::Success.to_id()
+                    })
+                    .collect();
+                wallet_txns.sort_by_key(|wallet_txn| (wallet_txn.update_at, wallet_txn.id));
+
+                let mut ledger_amount = BigDecimal::zero();
+                let mut prev_after_amount = BigDecimal::zero();
+                let mut broken_chain_count = 0;
+                let mut first_broken_txn_id: Option<i64> = None;
+                for wallet_txn in wallet_txns {
+                    match enums::WalletAction::from_i32(wallet_txn.action)? {
+                        enums::WalletAction::GameDeposit
+                        | enums::WalletAction::PaymentDeposit
+                        | enums::WalletAction::PaymentWithdrawReject
+                        | enums::WalletAction::TransferIn
+                        | enums::WalletAction::BonusGrant => {
+                            ledger_amount += &wallet_txn.change_amount;
+                        }
+                        enums::WalletAction::GameWithdraw
+                        | enums::WalletAction::PaymentWithdraw
+                        | enums::WalletAction::TransferOut
+                        | enums::WalletAction::BonusForfeit
+                        | enums::WalletAction::BonusCancel => {
+                            ledger_amount -= &wallet_txn.change_amount;
+                        }
+                        enums::WalletAction::Adjustment => {
+                            broken_chain_count = 0;
+                            first_broken_txn_id = None;
+                            prev_after_amount = wallet_txn.after_amount.clone();
+                            continue;
+                        }
+                    }
+
+                    if wallet_txn.before_amount != prev_after_amount {
+                        broken_chain_count += 1;
+                        first_broken_txn_id = Some(
+                            first_broken_txn_id.map_or(wallet_txn.id, |id| id.min(wallet_txn.id)),
+                        );
+                    }
+                    prev_after_amount = wallet_txn.after_amount.clone();
+                }
+
+                if user_wallet.amount != ledger_amount || broken_chain_count > 0 {
+                    drift_list.push(domain::WalletDrift {
+                        user_wallet_id: user_wallet.id,
+                        client_id: user_wallet.client_id,
+                        user_id: user_wallet.user_id,
+                        currency_id: user_wallet.currency_id,
+                        wallet_source_id: user_wallet.wallet_source_id,
+                        wallet_amount: user_wallet.amount.clone(),
+                        ledger_amount,
+                        broken_chain_count,
+                        first_broken_txn_id,
+                    });
+                }
+            }
+
+            drift_list.sort_by_key(|drift| drift.user_wallet_id);
+            Ok(drift_list)
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+
+#[derive(Debug)]
+pub struct MemoryWalletLimitRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryWalletLimitRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::WalletLimitRepositoryTrait for MemoryWalletLimitRepository {
+    async fn get(
+        &self,
+        client_id: i64,
+        currency_id: i64,
+    ) -> Result<Option<domain::WalletLimit>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_limits
+                .iter()
+                .find(|limit| limit.client_id == client_id && limit.currency_id == currency_id)
+                .cloned()
+        }))
+    }
+
+    async fn get_action_limit(
+        &self,
+        client_id: i64,
+        currency_id: i64,
+        action: i32,
+    ) -> Result<Option<domain::WalletActionLimit>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_action_limits
+                .iter()
+                .find(|limit| {
+                    limit.client_id == client_id
+                        && limit.currency_id == currency_id
+                        && limit.action == action
+                })
+                .cloned()
+        }))
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+use crate::enums;
+
+#[derive(Debug)]
+pub struct MemoryWalletOutboxRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryWalletOutboxRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::WalletOutboxRepositoryTrait for MemoryWalletOutboxRepository {
+    async fn insert(
+        &self,
+        outbox: domain::WalletOutbox,
+    ) -> Result<domain::WalletOutbox, KgsStatus> {
+        self.db.write(|tables| {
+            tables.wallet_outboxes.push(outbox.clone());
+            Ok(outbox)
+        })
+    }
+
+    async fn get_pending_for_update(
+        &self,
+        limit: u64,
+    ) -> Result<Vec<domain::WalletOutbox>, KgsStatus> {
+        self.db.check_for_update()?;
+        Ok(self.db.read(|tables| {
+            let mut outbox_list: Vec<domain::WalletOutbox> = tables
+                .wallet_outboxes
+                .iter()
+                .filter(|outbox| outbox.status == enums::OutboxStatus::Pending.to_id())
+                .cloned()
+                .collect();
+            outbox_list.sort_by_key(|outbox| outbox.id);
+            outbox_list.truncate(limit as usize);
+            outbox_list
+        }))
+    }
+
+    async fn mark_published(&self, ids: Vec<i64>) -> Result<(), KgsStatus> {
+        let now = chrono::Utc::now().naive_utc();
+        self.db.write(|tables| {
+            tables
+                .wallet_outboxes
+                .iter_mut()
+                .filter(|outbox| ids.contains(&outbox.id))
+                .for_each(|outbox| {
+                    outbox.status = enums::OutboxStatus::Published.to_id();
+                    outbox.publish_at = Some(now);
+                });
+        });
+        Ok(())
+    }
+
+    async fn increase_retry_count(&self, id: i64) -> Result<(), KgsStatus> {
+        self.db.write(|tables| {
+            tables
+                .wallet_outboxes
+                .iter_mut()
+                .filter(|outbox| outbox.id == id)
+                .for_each(|outbox| outbox.retry_count += 1);
+        });
+        Ok(())
+    }
+}
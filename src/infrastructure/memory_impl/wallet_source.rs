@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+
+#[derive(Debug)]
+pub struct MemoryWalletSourceRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryWalletSourceRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::WalletSourceRepositoryTrait for MemoryWalletSourceRepository {
+    async fn get(&self, id: i64) -> Result<domain::WalletSource, KgsStatus> {
+        self.db
+            .read(|tables| {
+                tables
+                    .wallet_sources
+                    .iter()
+                    .find(|wallet_source| wallet_source.id == id)
+                    .cloned()
+            })
+            .ok_or(KgsStatus::DataNotFound)
+    }
+}
@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use kgs_err::models::status::Status as KgsStatus;
+
+use super::MemoryDatabase;
+use crate::domain;
+use crate::enums;
+
+#[derive(Debug)]
+pub struct MemoryWalletTransactionRepository {
+    db: Arc<MemoryDatabase>,
+}
+
+impl MemoryWalletTransactionRepository {
+    pub fn new(db: Arc<MemoryDatabase>) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl domain::WalletTransactionRepositoryTrait for MemoryWalletTransactionRepository {
+    async fn get(&self, id: i64) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .find(|wallet_txn| wallet_txn.id == id)
+                .cloned()
+        }))
+    }
+
+    async fn insert(
+        &self,
+        wallet_txn: domain::WalletTransaction,
+    ) -> Result<domain::WalletTransaction, KgsStatus> {
+        // 與 idx_wallet_transaction_game_source_unique 相同 遊戲與轉帳的原始交易不能重複
+        // 合併錢包的交易與 idx_wallet_transaction_combined_leg_unique 相同 同一個錢包內不能重複
+        let is_game_root = wallet_txn.parent_id == 0
+            && (wallet_txn.action == enums::WalletAction::GameDeposit.to_id()
+                || wallet_txn.action == enums::WalletAction::GameWithdraw.to_id()
+                || wallet_txn.action == enums::WalletAction::TransferOut.to_id()
+                || wallet_txn.action == enums::WalletAction::TransferIn.to_id());
+
+        self.db.write(|tables| {
+            let is_duplicate = is_game_root
+                && tables.wallet_txns.iter().any(|row| {
+                    row.parent_id == 0
+                        && row.combined_leg == wallet_txn.combined_leg
+                        && (!wallet_txn.combined_leg
+                            || row.wallet_source_id == wallet_txn.wallet_source_id)
+                        && row.client_id == wallet_txn.client_id
+                        && row.user_id == wallet_txn.user_id
+                        && row.transaction_source_id == wallet_txn.transaction_source_id
+                        && row.action == wallet_txn.action
+                });
+            if is_duplicate {
+                return Err(KgsStatus::DuplicateTransaction);
+            }
+
+            // 與 idx_wallet_transaction_payment_order_unique 相同 同一個 client 的金流訂單不能重複
+            if let Some(payment) = &wallet_txn.payment {
+                if tables.wallet_txns.iter().any(|row| {
+                    row.client_id == wallet_txn.client_id
+                        && row
+                            .payment
+                            .as_ref()
+                            .is_some_and(|row_payment| row_payment.order_id == payment.order_id)
+                }) {
+                    return Err(KgsStatus::DuplicateTransaction);
+                }
+            }
+
+            // 與 idx_wallet_transaction_hold_unique 相同 同一個玩家的凍結單號不能重複
+            if wallet_txn.hold_id.is_some()
+                && tables.wallet_txns.iter().any(|row| {
+                    row.client_id == wallet_txn.client_id
+                        && row.user_id == wallet_txn.user_id
+                        && row.hold_id == wallet_txn.hold_id
+                })
+            {
+                return Err(KgsStatus::DuplicateTransaction);
+            }
+
+            tables.wallet_txns.push(wallet_txn.clone());
+            Ok(wallet_txn)
+        })
+    }
+
+    async fn update(
+        &self,
+        wallet_txn: domain::WalletTransaction,
+    ) -> Result<domain::WalletTransaction, KgsStatus> {
+        self.db.write(|tables| {
+            let row = tables
+                .wallet_txns
+                .iter_mut()
+                .find(|row| row.id == wallet_txn.id)
+                .ok_or(KgsStatus::InternalServerError)?;
+            *row = wallet_txn.clone();
+            Ok(wallet_txn)
+        })
+    }
+
+    async fn get_child(
+        &self,
+        parent_id: i64,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .find(|wallet_txn| wallet_txn.parent_id == parent_id)
+                .cloned()
+        }))
+    }
+
+    async fn get_list_by_transaction_source_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        source_txn_id: i64,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .filter(|wallet_txn| {
+                    wallet_txn.client_id == client_id
+                        && wallet_txn.user_id == user_id
+                        && wallet_txn.transaction_source_id == source_txn_id
+                })
+                .cloned()
+                .collect()
+        }))
+    }
+
+    async fn get_list(
+        &self,
+        select_query: &domain::SelectWalletTransactionsQuery,
+        limit: u64,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus> {
+        // 空的列表表示不篩選
+        fn matches(filter: &[i64], value: i64) -> bool {
+            filter.is_empty() || filter.contains(&value)
+        }
+
+        Ok(self.db.read(|tables| {
+            let mut wallet_txn_list: Vec<domain::WalletTransaction> = tables
+                .wallet_txns
+                .iter()
+                .filter(|wallet_txn| {
+                    wallet_txn.client_id == select_query.client_id
+                        && matches(&select_query.player_ids, wallet_txn.user_id)
+                        && matches(&select_query.currency_ids, wallet_txn.currency_id)
+                        && matches(&select_query.wallet_source_ids, wallet_txn.wallet_source_id)
+                        && (select_query.actions.is_empty()
+                            || select_query.actions.contains(&wallet_txn.action))
+                        && (select_query.statuses.is_empty()
+                            || select_query.statuses.contains(&wallet_txn.status))
+                        && select_query
+                            .start_time
+                            .map_or(true, |start_time| wallet_txn.create_at >= start_time)
+                        && select_query
+                            .end_time
+                            .map_or(true, |end_time| wallet_txn.create_at < end_time)
+                        && select_query
+                            .cursor
+                            .map_or(true, |cursor| wallet_txn.id < cursor)
+                })
+                .cloned()
+                .collect();
+            wallet_txn_list.sort_by(|a, b| b.id.cmp(&a.id));
+            wallet_txn_list.truncate(limit as usize);
+            wallet_txn_list
+        }))
+    }
+
+    async fn get_list_by_ids(
+        &self,
+        ids: Vec<i64>,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .filter(|wallet_txn| ids.contains(&wallet_txn.id))
+                .cloned()
+                .collect()
+        }))
+    }
+
+    async fn get_root_by_transaction_source_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        source_txn_id: i64,
+        action: i32,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .find(|wallet_txn| {
+                    wallet_txn.client_id == client_id
+                        && wallet_txn.user_id == user_id
+                        && wallet_txn.transaction_source_id == source_txn_id
+                        && wallet_txn.action == action
+                        && wallet_txn.parent_id == 0
+                })
+                .cloned()
+        }))
+    }
+
+    async fn get_by_hold_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        hold_id: i64,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .find(|wallet_txn| {
+                    wallet_txn.client_id == client_id
+                        && wallet_txn.user_id == user_id
+                        && wallet_txn.hold_id == Some(hold_id)
+                })
+                .cloned()
+        }))
+    }
+
+    async fn get_by_payment_order_id(
+        &self,
+        client_id: i64,
+        payment_order_id: &str,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .find(|wallet_txn| {
+                    wallet_txn.client_id == client_id
+                        && wallet_txn
+                            .payment
+                            .as_ref()
+                            .is_some_and(|payment| payment.order_id == payment_order_id)
+                })
+                .cloned()
+        }))
+    }
+
+    async fn get_withdraw_total(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        currency_id: i64,
+        since: chrono::NaiveDateTime,
+    ) -> Result<BigDecimal, KgsStatus> {
+        let withdraw = enums::WalletAction::PaymentWithdraw.to_id();
+        let reject = enums::WalletAction::PaymentWithdrawReject.to_id();
+        let rollback = enums::WalletAction::PaymentDeposit.to_id();
+        let cancel = enums::WalletStatus::Cancel.to_id();
+
+        Ok(self.db.read(|tables| {
+            tables
+                .wallet_txns
+                .iter()
+                .filter(|wallet_txn| {
+                    wallet_txn.client_id == client_id
+                        && wallet_txn.user_id == user_id
+                        && wallet_txn.currency_id == currency_id
+                        && wallet_txn.action == withdraw
+                        && wallet_txn.status != cancel
+                        && wallet_txn.create_at >= since
+                        && !tables.wallet_txns.iter().any(|child| {
+                            child.parent_id == wallet_txn.id
+                                && (child.action == reject || child.action == rollback)
+                        })
+                })
+                .map(|wallet_txn| wallet_txn.change_amount.abs())
+                .sum()
+        }))
+    }
+}
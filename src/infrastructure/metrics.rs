@@ -0,0 +1,18 @@
+use once_cell::sync::Lazy;
+use opentelemetry::metrics::Counter;
+use opentelemetry::KeyValue;
+
+use crate::config;
+
+/// 幣別快取的查詢次數 以 result=hit/miss 區分 命中率 = hit / (hit + miss)
+static CURRENCY_CACHE_COUNTER: Lazy<Counter<u64>> = Lazy::new(|| {
+    opentelemetry::global::meter(config::get_host().service_name.clone())
+        .u64_counter("currency_cache_requests")
+        .with_description("幣別快取查詢次數")
+        .init()
+});
+
+pub fn record_currency_cache(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    CURRENCY_CACHE_COUNTER.add(1, &[KeyValue::new("result", result)]);
+}
@@ -1,4 +1,9 @@
 pub mod bank_server;
+pub mod database;
+#[cfg(test)]
+pub mod memory_impl;
+pub mod metrics;
+pub mod rabbitmq;
 pub mod sea_orm_impl;
 pub mod snowflake;
 pub mod transaction_manager;
@@ -0,0 +1,91 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use kgs_tracing::{info, tracing, warn};
+use serde::Deserialize;
+use tokio::sync::watch;
+
+use crate::domain::CurrencyServiceTrait;
+
+/// bank server 發送的幣別變更事件
+#[derive(Deserialize, Debug)]
+struct CurrencyChangedEvent {
+    client_id: Option<i64>, // 不帶 client_id 表示全部的 client 都需要更新
+}
+
+/// 接收幣別變更事件 清除幣別快取
+#[derive(Debug)]
+pub struct CurrencyChangedConsumer {
+    currency_service: Arc<dyn CurrencyServiceTrait>,
+    exchange: String,
+    queue: String,
+}
+
+impl CurrencyChangedConsumer {
+    const ROUTING_KEY: &'static str = "currency.changed";
+
+    pub fn new(
+        currency_service: Arc<dyn CurrencyServiceTrait>,
+        exchange: &str,
+        queue: &str,
+    ) -> Self {
+        Self {
+            currency_service,
+            exchange: exchange.to_string(),
+            queue: queue.to_string(),
+        }
+    }
+
+    /// 持續接收事件 連線中斷或收到關閉通知時結束
+    /// 每個服務需要使用不同的 queue 才能每個服務都收到事件
+    #[tracing::instrument]
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) {
+        let pool = super::build_pool().await;
+
+        let mut consumer = match pool
+            .consume(&self.exchange, &self.queue, Self::ROUTING_KEY)
+            .await
+        {
+            Ok(consumer) => consumer,
+            Err(err) => {
+                warn!("consume currency changed event failed: {:?}", err);
+                return;
+            }
+        };
+        info!("currency changed consumer start, queue: {}", self.queue);
+
+        loop {
+            let delivery = tokio::select! {
+                delivery = consumer.next() => delivery,
+                _ = shutdown.changed() => break,
+            };
+            let Some(delivery) = delivery else {
+                warn!("currency changed consumer stopped");
+                return;
+            };
+            let delivery = match delivery {
+                Ok(delivery) => delivery,
+                Err(err) => {
+                    warn!("receive currency changed event failed: {:?}", err);
+                    continue;
+                }
+            };
+
+            // 無法解析的事件也清除全部快取 寧可多查詢一次 bank server
+            match serde_json::from_slice::<CurrencyChangedEvent>(&delivery.data) {
+                Ok(event) => self.currency_service.invalidate(event.client_id),
+                Err(err) => {
+                    warn!("parse currency changed event failed: {:?}", err);
+                    self.currency_service.invalidate(None);
+                }
+            }
+
+            if let Err(err) = delivery.ack().await {
+                warn!("ack currency changed event failed: {:?}", err);
+            }
+        }
+
+        info!("currency changed consumer stopped");
+    }
+}
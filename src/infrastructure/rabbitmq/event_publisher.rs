@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+
+use crate::domain::EventPublisherTrait;
+
+/// 將事件發送到 RabbitMQ 的 topic exchange
+#[derive(Debug)]
+pub struct RabbitMQEventPublisher {
+    exchange: String,
+    pool: rabbitmq_manager::Pool,
+}
+
+impl RabbitMQEventPublisher {
+    pub async fn new(exchange: &str) -> Self {
+        let pool = super::build_pool().await;
+
+        pool.declare_topic_exchange(exchange)
+            .await
+            .expect("宣告 RabbitMQ exchange 失敗");
+
+        Self {
+            exchange: exchange.to_string(),
+            pool,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl EventPublisherTrait for RabbitMQEventPublisher {
+    #[tracing::instrument(skip(payload))]
+    async fn publish(&self, routing_key: &str, payload: &str) -> Result<(), KgsStatus> {
+        // 等待 broker 確認後才回傳 確保事件已寫入 RabbitMQ
+        self.pool
+            .publish(&self.exchange, routing_key, payload.as_bytes())
+            .await
+            .map_err(|err| {
+                warn!("publish event to rabbitmq failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+}
@@ -0,0 +1,22 @@
+mod currency_consumer;
+mod event_publisher;
+
+pub use currency_consumer::CurrencyChangedConsumer;
+pub use event_publisher::RabbitMQEventPublisher;
+
+use crate::config;
+
+/// 依照 RabbitMQ 環境變數建立連線池
+pub async fn build_pool() -> rabbitmq_manager::Pool {
+    let config = config::get_rabbit();
+    rabbitmq_manager::Builder::new()
+        .host(&config.rabbitmq_host)
+        .port(config.rabbitmq_port)
+        .user(&config.rabbitmq_user)
+        .password(&config.rabbitmq_password)
+        .max_connections(config.rabbitmq_max_connection)
+        .min_connections(config.rabbitmq_min_connection)
+        .connection_timeout(config.rabbitmq_connection_timeout)
+        .build()
+        .await
+}
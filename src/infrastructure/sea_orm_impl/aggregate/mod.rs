@@ -1,3 +1,7 @@
+mod rollover_record;
 mod user_wallet;
+mod wallet_drift;
 
+pub use rollover_record::*;
 pub use user_wallet::*;
+pub use wallet_drift::*;
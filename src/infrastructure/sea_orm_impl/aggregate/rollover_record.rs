@@ -0,0 +1,54 @@
+use bigdecimal::BigDecimal;
+use sea_orm::FromQueryResult;
+
+#[derive(FromQueryResult)]
+pub struct RolloverRecordWithTransaction {
+    // RolloverRecord
+    pub id: i64,
+    pub main_id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub requirement_rollover: BigDecimal,
+    pub requirement_rollover_rate: BigDecimal,
+    pub achievement_rollover: BigDecimal,
+    pub achievement_rollover_rate: BigDecimal,
+    pub contribution_weight: BigDecimal,
+    pub create_by: i64,
+    pub wallet_txn_id: i64,
+    pub create_at: chrono::NaiveDateTime,
+    // WalletTransaction
+    pub wallet_txn_action: Option<i32>,               // 錢包操作
+    pub wallet_txn_status: Option<i32>,               // 交易狀態
+    pub transaction_source_id: Option<i64>,           // 來源交易 ID
+    pub wallet_txn_change_amount: Option<BigDecimal>, // 交易金額
+    // 累計流水
+    pub total_requirement_rollover: BigDecimal, // 累計到此筆的需求流水
+    pub total_achievement_rollover: BigDecimal, // 累計到此筆的達成流水
+}
+
+impl Into<crate::domain::RolloverRecordWithTransaction> for RolloverRecordWithTransaction {
+    fn into(self) -> crate::domain::RolloverRecordWithTransaction {
+        crate::domain::RolloverRecordWithTransaction {
+            rollover_record: crate::domain::RolloverRecord {
+                id: self.id,
+                main_id: self.main_id,
+                client_id: self.client_id,
+                user_id: self.user_id,
+                requirement_rollover: self.requirement_rollover,
+                requirement_rollover_rate: self.requirement_rollover_rate,
+                achievement_rollover: self.achievement_rollover,
+                achievement_rollover_rate: self.achievement_rollover_rate,
+                contribution_weight: self.contribution_weight,
+                create_by: self.create_by,
+                wallet_txn_id: self.wallet_txn_id,
+                create_at: self.create_at,
+            },
+            wallet_txn_action: self.wallet_txn_action,
+            wallet_txn_status: self.wallet_txn_status,
+            transaction_source_id: self.transaction_source_id,
+            wallet_txn_change_amount: self.wallet_txn_change_amount,
+            total_requirement_rollover: self.total_requirement_rollover,
+            total_achievement_rollover: self.total_achievement_rollover,
+        }
+    }
+}
@@ -4,13 +4,14 @@ use sea_orm::FromQueryResult;
 #[derive(FromQueryResult)]
 pub struct UserWalletWithRollover {
     // UserWallet
-    pub id: i64,               // UserWallet ID
-    pub client_id: i64,        // 客戶ID
-    pub user_id: i64,          // 用戶ID
-    pub currency_id: i64,      // 幣別ID
-    pub currency_name: String, // 貨幣名稱
-    pub wallet_source_id: i64, // 錢包來源
-    pub amount: BigDecimal,    // 錢包金額
+    pub id: i64,                 // UserWallet ID
+    pub client_id: i64,          // 客戶ID
+    pub user_id: i64,            // 用戶ID
+    pub currency_id: i64,        // 幣別ID
+    pub currency_name: String,   // 貨幣名稱
+    pub wallet_source_id: i64,   // 錢包來源
+    pub amount: BigDecimal,      // 錢包金額
+    pub hold_amount: BigDecimal, // 凍結金額
     // WalletSource
     pub wallet_source_name: String, // 錢包來源名稱
     // RolloverMain
@@ -28,6 +29,7 @@ impl Into<crate::domain::UserWalletWithRollover> for UserWalletWithRollover {
             currency_name: self.currency_name,
             wallet_source_id: self.wallet_source_id,
             amount: self.amount,
+            hold_amount: self.hold_amount,
             wallet_source_name: self.wallet_source_name,
             requirement_rollover: self.requirement_rollover,
             achievement_rollover: self.achievement_rollover,
@@ -0,0 +1,31 @@
+use bigdecimal::BigDecimal;
+use sea_orm::FromQueryResult;
+
+#[derive(FromQueryResult)]
+pub struct WalletDrift {
+    pub user_wallet_id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub wallet_source_id: i64,
+    pub wallet_amount: BigDecimal,
+    pub ledger_amount: BigDecimal,
+    pub broken_chain_count: i64,
+    pub first_broken_txn_id: Option<i64>,
+}
+
+impl Into<crate::domain::WalletDrift> for WalletDrift {
+    fn into(self) -> crate::domain::WalletDrift {
+        crate::domain::WalletDrift {
+            user_wallet_id: self.user_wallet_id,
+            client_id: self.client_id,
+            user_id: self.user_id,
+            currency_id: self.currency_id,
+            wallet_source_id: self.wallet_source_id,
+            wallet_amount: self.wallet_amount,
+            ledger_amount: self.ledger_amount,
+            broken_chain_count: self.broken_chain_count,
+            first_broken_txn_id: self.first_broken_txn_id,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use sea_orm::{entity::prelude::*, Set};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "bonus_grant")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub campaign_id: Option<String>,
+    pub wallet_txn_id: i64,
+    pub rollover_record_id: Option<i64>,
+    pub amount: BigDecimal,
+    pub rollover_rate: BigDecimal,
+    pub expire_at: Option<chrono::NaiveDateTime>,
+    pub status: i32,
+    pub forfeit_amount: Option<BigDecimal>,
+    pub forfeit_txn_id: Option<i64>,
+    pub forfeit_retry_count: i32,
+    pub next_forfeit_at: Option<chrono::NaiveDateTime>,
+    pub cancel_txn_id: Option<i64>,
+    pub create_by: i64,
+    pub cancel_by: Option<i64>,
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Into<crate::domain::BonusGrant> for Model {
+    fn into(self) -> crate::domain::BonusGrant {
+        crate::domain::BonusGrant {
+            id: self.id,
+            client_id: self.client_id,
+            user_id: self.user_id,
+            currency_id: self.currency_id,
+            campaign_id: self.campaign_id,
+            wallet_txn_id: self.wallet_txn_id,
+            rollover_record_id: self.rollover_record_id,
+            amount: self.amount,
+            rollover_rate: self.rollover_rate,
+            expire_at: self.expire_at,
+            status: self.status,
+            forfeit_amount: self.forfeit_amount,
+            forfeit_txn_id: self.forfeit_txn_id,
+            forfeit_retry_count: self.forfeit_retry_count,
+            next_forfeit_at: self.next_forfeit_at,
+            cancel_txn_id: self.cancel_txn_id,
+            create_by: self.create_by,
+            cancel_by: self.cancel_by,
+            create_at: self.create_at,
+            update_at: self.update_at,
+        }
+    }
+}
+
+impl From<crate::domain::BonusGrant> for ActiveModel {
+    fn from(domain: crate::domain::BonusGrant) -> Self {
+        Self {
+            id: Set(domain.id),
+            client_id: Set(domain.client_id),
+            user_id: Set(domain.user_id),
+            currency_id: Set(domain.currency_id),
+            campaign_id: Set(domain.campaign_id),
+            wallet_txn_id: Set(domain.wallet_txn_id),
+            rollover_record_id: Set(domain.rollover_record_id),
+            amount: Set(domain.amount),
+            rollover_rate: Set(domain.rollover_rate),
+            expire_at: Set(domain.expire_at),
+            status: Set(domain.status),
+            forfeit_amount: Set(domain.forfeit_amount),
+            forfeit_txn_id: Set(domain.forfeit_txn_id),
+            forfeit_retry_count: Set(domain.forfeit_retry_count),
+            next_forfeit_at: Set(domain.next_forfeit_at),
+            cancel_txn_id: Set(domain.cancel_txn_id),
+            create_by: Set(domain.create_by),
+            cancel_by: Set(domain.cancel_by),
+            create_at: Set(domain.create_at),
+            update_at: Set(domain.update_at),
+        }
+    }
+}
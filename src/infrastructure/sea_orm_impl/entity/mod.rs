@@ -1,5 +1,11 @@
+pub mod bonus_grant;
+pub mod reconciliation_report;
+pub mod rollover_contribution;
 pub mod rollover_main;
 pub mod rollover_record;
 pub mod user_wallet;
+pub mod wallet_action_limit;
+pub mod wallet_limit;
+pub mod wallet_outbox;
 pub mod wallet_source;
 pub mod wallet_transaction;
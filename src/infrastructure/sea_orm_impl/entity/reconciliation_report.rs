@@ -0,0 +1,71 @@
+use sea_orm::{entity::prelude::*, Set};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "reconciliation_report")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub client_id: i64,
+    pub user_id: i64,
+    pub currency_id: i64,
+    pub wallet_source_id: i64,
+    pub wallet_amount: BigDecimal,
+    pub ledger_amount: BigDecimal,
+    pub drift_amount: BigDecimal,
+    pub broken_chain_count: i32,
+    pub first_broken_txn_id: Option<i64>,
+    pub status: i32,
+    pub adjustment_txn_id: Option<i64>,
+    pub approve_by: Option<i64>,
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Into<crate::domain::ReconciliationReport> for Model {
+    fn into(self) -> crate::domain::ReconciliationReport {
+        crate::domain::ReconciliationReport {
+            id: self.id,
+            client_id: self.client_id,
+            user_id: self.user_id,
+            currency_id: self.currency_id,
+            wallet_source_id: self.wallet_source_id,
+            wallet_amount: self.wallet_amount,
+            ledger_amount: self.ledger_amount,
+            drift_amount: self.drift_amount,
+            broken_chain_count: self.broken_chain_count,
+            first_broken_txn_id: self.first_broken_txn_id,
+            status: self.status,
+            adjustment_txn_id: self.adjustment_txn_id,
+            approve_by: self.approve_by,
+            create_at: self.create_at,
+            update_at: self.update_at,
+        }
+    }
+}
+
+impl From<crate::domain::ReconciliationReport> for ActiveModel {
+    fn from(domain: crate::domain::ReconciliationReport) -> Self {
+        Self {
+            id: Set(domain.id),
+            client_id: Set(domain.client_id),
+            user_id: Set(domain.user_id),
+            currency_id: Set(domain.currency_id),
+            wallet_source_id: Set(domain.wallet_source_id),
+            wallet_amount: Set(domain.wallet_amount),
+            ledger_amount: Set(domain.ledger_amount),
+            drift_amount: Set(domain.drift_amount),
+            broken_chain_count: Set(domain.broken_chain_count),
+            first_broken_txn_id: Set(domain.first_broken_txn_id),
+            status: Set(domain.status),
+            adjustment_txn_id: Set(domain.adjustment_txn_id),
+            approve_by: Set(domain.approve_by),
+            create_at: Set(domain.create_at),
+            update_at: Set(domain.update_at),
+        }
+    }
+}
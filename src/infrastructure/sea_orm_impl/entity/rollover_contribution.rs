@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "rollover_contribution")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub client_id: i64,
+    pub wallet_source_id: i64,
+    pub game_category: String,
+    pub game_id: String,
+    pub weight: BigDecimal,
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Into<crate::domain::RolloverContribution> for Model {
+    fn into(self) -> crate::domain::RolloverContribution {
+        crate::domain::RolloverContribution {
+            id: self.id,
+            client_id: self.client_id,
+            wallet_source_id: self.wallet_source_id,
+            game_category: self.game_category,
+            game_id: self.game_id,
+            weight: self.weight,
+            create_at: self.create_at,
+            update_at: self.update_at,
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub struct Model {
     pub wallet_source_id: i64,                              // 錢包來源id
     pub requirement_rollover: sea_orm::prelude::BigDecimal, // 需求流水(有正負號)
     pub achievement_rollover: sea_orm::prelude::BigDecimal, // 達成流水(有正負號)
+    pub version: i64,                                       // 版本號
     pub create_at: chrono::NaiveDateTime,
     pub update_at: chrono::NaiveDateTime,
 }
@@ -72,6 +73,7 @@ impl Into<crate::domain::RolloverMain> for Model {
             wallet_source_id: self.wallet_source_id,
             requirement_rollover: self.requirement_rollover,
             achievement_rollover: self.achievement_rollover,
+            version: self.version,
             create_at: self.create_at,
             update_at: self.update_at,
         }
@@ -90,6 +92,7 @@ impl From<crate::domain::RolloverMain> for ActiveModel {
             wallet_source_id: Set(domain.wallet_source_id),
             requirement_rollover: Set(domain.requirement_rollover),
             achievement_rollover: Set(domain.achievement_rollover),
+            version: Set(domain.version),
             create_at: Set(domain.create_at),
             update_at: Set(domain.update_at),
         }
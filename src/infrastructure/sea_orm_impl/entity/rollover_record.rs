@@ -12,6 +12,7 @@ pub struct Model {
     pub requirement_rollover_rate: BigDecimal, // 因為流水倍率是可被動的 所以需要紀錄計算當下的流水倍率
     pub achievement_rollover: BigDecimal,      // 達成流水(有正負號)
     pub achievement_rollover_rate: BigDecimal, // 因為流水倍率是可被動的 所以需要紀錄計算當下的流水倍率
+    pub contribution_weight: BigDecimal,       // 計算當下的遊戲流水貢獻比例
     pub create_by: i64,                        // 創建此筆紀錄的user_id
     pub wallet_txn_id: i64,                    // 對應的wallet txn id
     pub create_at: chrono::NaiveDateTime,      // 創建時間
@@ -63,6 +64,7 @@ impl Into<crate::domain::RolloverRecord> for Model {
             requirement_rollover_rate: self.requirement_rollover_rate,
             achievement_rollover: self.achievement_rollover,
             achievement_rollover_rate: self.achievement_rollover_rate,
+            contribution_weight: self.contribution_weight,
             create_by: self.create_by,
             wallet_txn_id: self.wallet_txn_id,
             create_at: self.create_at,
@@ -81,6 +83,7 @@ impl From<crate::domain::RolloverRecord> for ActiveModel {
             requirement_rollover_rate: Set(value.requirement_rollover_rate),
             achievement_rollover: Set(value.achievement_rollover),
             achievement_rollover_rate: Set(value.achievement_rollover_rate),
+            contribution_weight: Set(value.contribution_weight),
             create_by: Set(value.create_by),
             wallet_txn_id: Set(value.wallet_txn_id),
             create_at: Set(value.create_at),
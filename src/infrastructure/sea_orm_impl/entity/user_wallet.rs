@@ -14,6 +14,8 @@ pub struct Model {
     pub wallet_source_id: i64,
     pub wallet_source_name: String,
     pub amount: BigDecimal,
+    pub hold_amount: BigDecimal,
+    pub version: i64,
     pub create_at: chrono::NaiveDateTime,
     pub update_at: chrono::NaiveDateTime,
 }
@@ -64,6 +66,8 @@ impl Into<crate::domain::UserWallet> for Model {
             wallet_source_id: self.wallet_source_id,
             wallet_source_name: self.wallet_source_name,
             amount: self.amount,
+            hold_amount: self.hold_amount,
+            version: self.version,
         }
     }
 }
@@ -79,6 +83,8 @@ impl From<crate::domain::UserWallet> for ActiveModel {
             wallet_source_id: Set(user_wallet.wallet_source_id),
             wallet_source_name: Set(user_wallet.wallet_source_name),
             amount: Set(user_wallet.amount),
+            hold_amount: Set(user_wallet.hold_amount),
+            version: Set(user_wallet.version),
             create_at: NotSet,
             update_at: Set(chrono::Utc::now().naive_utc()),
         }
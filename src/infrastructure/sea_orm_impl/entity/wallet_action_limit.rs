@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "wallet_action_limit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub client_id: i64,
+    pub currency_id: i64,
+    pub action: i32,
+    pub min_amount: Option<BigDecimal>,
+    pub max_amount: Option<BigDecimal>,
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Into<crate::domain::WalletActionLimit> for Model {
+    fn into(self) -> crate::domain::WalletActionLimit {
+        crate::domain::WalletActionLimit {
+            id: self.id,
+            client_id: self.client_id,
+            currency_id: self.currency_id,
+            action: self.action,
+            min_amount: self.min_amount,
+            max_amount: self.max_amount,
+            create_at: self.create_at,
+            update_at: self.update_at,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "wallet_limit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub client_id: i64,
+    pub currency_id: i64,
+    pub max_balance: Option<BigDecimal>,
+    pub daily_withdraw_limit: Option<BigDecimal>,
+    pub weekly_withdraw_limit: Option<BigDecimal>,
+    pub create_at: chrono::NaiveDateTime,
+    pub update_at: chrono::NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Into<crate::domain::WalletLimit> for Model {
+    fn into(self) -> crate::domain::WalletLimit {
+        crate::domain::WalletLimit {
+            id: self.id,
+            client_id: self.client_id,
+            currency_id: self.currency_id,
+            max_balance: self.max_balance,
+            daily_withdraw_limit: self.daily_withdraw_limit,
+            weekly_withdraw_limit: self.weekly_withdraw_limit,
+            create_at: self.create_at,
+            update_at: self.update_at,
+        }
+    }
+}
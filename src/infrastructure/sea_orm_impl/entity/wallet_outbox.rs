@@ -0,0 +1,59 @@
+use sea_orm::{entity::prelude::*, Set};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "wallet_outbox")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64, // 同時作為事件 ID
+    pub client_id: i64,
+    pub user_id: i64,
+    pub aggregate_id: i64, // 事件對應的資料 ID
+    pub event_type: String,
+    pub event_version: i32,
+    pub payload: String, // 事件內容(JSON)
+    pub status: i32,
+    pub retry_count: i32, // 發送失敗次數
+    pub create_at: chrono::NaiveDateTime,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Into<crate::domain::WalletOutbox> for Model {
+    fn into(self) -> crate::domain::WalletOutbox {
+        crate::domain::WalletOutbox {
+            id: self.id,
+            client_id: self.client_id,
+            user_id: self.user_id,
+            aggregate_id: self.aggregate_id,
+            event_type: self.event_type,
+            event_version: self.event_version,
+            payload: self.payload,
+            status: self.status,
+            retry_count: self.retry_count,
+            create_at: self.create_at,
+            publish_at: self.publish_at,
+        }
+    }
+}
+
+impl From<crate::domain::WalletOutbox> for ActiveModel {
+    fn from(domain: crate::domain::WalletOutbox) -> Self {
+        Self {
+            id: Set(domain.id),
+            client_id: Set(domain.client_id),
+            user_id: Set(domain.user_id),
+            aggregate_id: Set(domain.aggregate_id),
+            event_type: Set(domain.event_type),
+            event_version: Set(domain.event_version),
+            payload: Set(domain.payload),
+            status: Set(domain.status),
+            retry_count: Set(domain.retry_count),
+            create_at: Set(domain.create_at),
+            publish_at: Set(domain.publish_at),
+        }
+    }
+}
@@ -17,6 +17,11 @@ pub struct Model {
     pub change_amount: BigDecimal,
     pub after_amount: BigDecimal,
     pub status: i32,
+    pub payment_order_id: Option<String>, // 金流訂單 ID
+    pub payment_channel: Option<String>,  // 金流渠道
+    pub payment_operator: Option<String>, // 操作人員
+    pub hold_id: Option<i64>,             // 凍結單號
+    pub combined_leg: bool,               // 合併錢包的交易
     pub create_at: chrono::NaiveDateTime,
     pub update_at: chrono::NaiveDateTime,
 }
@@ -71,6 +76,15 @@ impl Into<crate::domain::WalletTransaction> for Model {
             change_amount: self.change_amount,
             after_amount: self.after_amount,
             status: self.status,
+            payment: self
+                .payment_order_id
+                .map(|order_id| crate::domain::PaymentReference {
+                    order_id,
+                    channel: self.payment_channel.unwrap_or_default(),
+                    operator: self.payment_operator.unwrap_or_default(),
+                }),
+            hold_id: self.hold_id,
+            combined_leg: self.combined_leg,
             create_at: self.create_at,
             update_at: self.update_at,
         }
@@ -92,6 +106,17 @@ impl From<crate::domain::WalletTransaction> for ActiveModel {
             change_amount: Set(domain.change_amount),
             after_amount: Set(domain.after_amount),
             status: Set(domain.status),
+            payment_order_id: Set(domain
+                .payment
+                .as_ref()
+                .map(|payment| payment.order_id.clone())),
+            payment_channel: Set(domain
+                .payment
+                .as_ref()
+                .map(|payment| payment.channel.clone())),
+            payment_operator: Set(domain.payment.map(|payment| payment.operator)),
+            hold_id: Set(domain.hold_id),
+            combined_leg: Set(domain.combined_leg),
             create_at: Set(domain.create_at),
             update_at: Set(domain.update_at),
         }
@@ -0,0 +1,185 @@
+use std::fmt::Debug;
+
+use database_manager::Context;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+use sea_orm::*;
+
+use crate::domain;
+use crate::domain::BonusGrantRepositoryTrait;
+use crate::enums;
+use crate::infrastructure::sea_orm_impl::entity::bonus_grant;
+
+#[derive(Debug)]
+pub struct BonusGrantRepository;
+
+#[tonic::async_trait]
+impl BonusGrantRepositoryTrait for BonusGrantRepository {
+    #[tracing::instrument]
+    async fn insert(&self, grant: domain::BonusGrant) -> Result<domain::BonusGrant, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let active_model = bonus_grant::ActiveModel::from(grant);
+
+        active_model
+            .insert(txn)
+            .await
+            .map(|model| model.into())
+            .map_err(|err| {
+                warn!("insert bonus grant failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get(&self, client_id: i64, id: i64) -> Result<Option<domain::BonusGrant>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        bonus_grant::Entity::find_by_id(id)
+            .filter(bonus_grant::Column::ClientId.eq(client_id))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get bonus grant failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_for_update(&self, id: i64) -> Result<Option<domain::BonusGrant>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        bonus_grant::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get bonus grant for update failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_active_list(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        currency_id: i64,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        bonus_grant::Entity::find()
+            .filter(bonus_grant::Column::ClientId.eq(client_id))
+            .filter(bonus_grant::Column::UserId.eq(user_id))
+            .filter(bonus_grant::Column::CurrencyId.eq(currency_id))
+            .filter(bonus_grant::Column::Status.eq(enums::BonusGrantStatus::Active.to_id()))
+            .order_by_asc(bonus_grant::Column::Id)
+            .all(txn)
+            .await
+            .map(|models| models.into_iter().map(|model| model.into()).collect())
+            .map_err(|err| {
+                warn!("get active bonus grant list failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_expired_list(
+        &self,
+        now: chrono::NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<domain::BonusGrant>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        bonus_grant::Entity::find()
+            .filter(bonus_grant::Column::Status.eq(enums::BonusGrantStatus::Active.to_id()))
+            .filter(bonus_grant::Column::ExpireAt.lte(now))
+            .filter(
+                Condition::any()
+                    .add(bonus_grant::Column::NextForfeitAt.is_null())
+                    .add(bonus_grant::Column::NextForfeitAt.lte(now)),
+            )
+            .order_by_asc(bonus_grant::Column::ExpireAt)
+            .limit(limit)
+            .all(txn)
+            .await
+            .map(|models| models.into_iter().map(|model| model.into()).collect())
+            .map_err(|err| {
+                warn!("get expired bonus grant list failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn defer_forfeit(
+        &self,
+        id: i64,
+        next_forfeit_at: chrono::NaiveDateTime,
+    ) -> Result<(), KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        bonus_grant::Entity::update_many()
+            .col_expr(
+                bonus_grant::Column::ForfeitRetryCount,
+                Expr::col(bonus_grant::Column::ForfeitRetryCount).add(1),
+            )
+            .col_expr(
+                bonus_grant::Column::NextForfeitAt,
+                Expr::value(next_forfeit_at),
+            )
+            .filter(bonus_grant::Column::Id.eq(id))
+            .exec(txn)
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                warn!("defer bonus grant forfeit failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn update(&self, grant: domain::BonusGrant) -> Result<domain::BonusGrant, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let active_model = bonus_grant::ActiveModel::from(grant);
+
+        active_model
+            .update(txn)
+            .await
+            .map(|model| model.into())
+            .map_err(|err| {
+                warn!("update bonus grant failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+}
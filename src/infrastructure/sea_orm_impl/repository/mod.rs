@@ -1,11 +1,21 @@
+mod bonus_grant;
+mod reconciliation_report;
+mod rollover_contribution;
 mod rollover_main;
 mod rollover_record;
 mod user_wallet;
+mod wallet_limit;
+mod wallet_outbox;
 mod wallet_source;
 mod wallet_transaction;
 
+pub use bonus_grant::BonusGrantRepository;
+pub use reconciliation_report::ReconciliationReportRepository;
+pub use rollover_contribution::RolloverContributionRepository;
 pub use rollover_main::RolloverMainRepository;
 pub use rollover_record::RolloverRecordRepository;
 pub use user_wallet::UserWalletRepository;
+pub use wallet_limit::WalletLimitRepository;
+pub use wallet_outbox::WalletOutboxRepository;
 pub use wallet_source::WalletSourceRepository;
 pub use wallet_transaction::WalletTransactionRepository;
@@ -0,0 +1,113 @@
+use std::fmt::Debug;
+
+use database_manager::Context;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+use sea_orm::*;
+
+use crate::domain;
+use crate::domain::ReconciliationReportRepositoryTrait;
+use crate::enums;
+use crate::infrastructure::sea_orm_impl::entity::reconciliation_report;
+
+#[derive(Debug)]
+pub struct ReconciliationReportRepository;
+
+#[tonic::async_trait]
+impl ReconciliationReportRepositoryTrait for ReconciliationReportRepository {
+    #[tracing::instrument]
+    async fn insert(
+        &self,
+        report: domain::ReconciliationReport,
+    ) -> Result<domain::ReconciliationReport, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let active_model = reconciliation_report::ActiveModel::from(report);
+
+        active_model
+            .insert(txn)
+            .await
+            .map(|model| model.into())
+            .map_err(|err| {
+                warn!("insert reconciliation report failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_for_update(
+        &self,
+        client_id: i64,
+        id: i64,
+    ) -> Result<Option<domain::ReconciliationReport>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        reconciliation_report::Entity::find_by_id(id)
+            .filter(reconciliation_report::Column::ClientId.eq(client_id))
+            .lock_exclusive()
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get reconciliation report for update failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_pending_list(
+        &self,
+        client_id: i64,
+    ) -> Result<Vec<domain::ReconciliationReport>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        reconciliation_report::Entity::find()
+            .filter(reconciliation_report::Column::ClientId.eq(client_id))
+            .filter(
+                reconciliation_report::Column::Status
+                    .eq(enums::ReconciliationStatus::Pending.to_id()),
+            )
+            .all(txn)
+            .await
+            .map(|models| models.into_iter().map(|model| model.into()).collect())
+            .map_err(|err| {
+                warn!("get pending reconciliation report list failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn update(
+        &self,
+        report: domain::ReconciliationReport,
+    ) -> Result<domain::ReconciliationReport, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let active_model = reconciliation_report::ActiveModel::from(report);
+
+        active_model
+            .update(txn)
+            .await
+            .map(|model| model.into())
+            .map_err(|err| {
+                warn!("update reconciliation report failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt::Debug;
+
+use database_manager::Context;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+use sea_orm::*;
+
+use crate::domain::{self, RolloverContributionRepositoryTrait};
+use crate::infrastructure::sea_orm_impl::entity::rollover_contribution;
+
+#[derive(Debug)]
+pub struct RolloverContributionRepository;
+
+#[tonic::async_trait]
+impl RolloverContributionRepositoryTrait for RolloverContributionRepository {
+    #[tracing::instrument]
+    async fn get(
+        &self,
+        client_id: i64,
+        wallet_source_id: i64,
+        game: &domain::GameInfo,
+    ) -> Result<Option<domain::RolloverContribution>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        // 遊戲的設定與遊戲類別的設定 (game_id 為空字串) 同時查詢 遊戲的設定排在前面
+        rollover_contribution::Entity::find()
+            .filter(rollover_contribution::Column::ClientId.eq(client_id))
+            .filter(rollover_contribution::Column::WalletSourceId.eq(wallet_source_id))
+            .filter(rollover_contribution::Column::GameCategory.eq(game.game_category.as_str()))
+            .filter(rollover_contribution::Column::GameId.is_in([game.game_id.as_str(), ""]))
+            .order_by_desc(rollover_contribution::Column::GameId)
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get rollover contribution failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+}
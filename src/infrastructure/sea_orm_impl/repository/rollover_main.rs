@@ -36,19 +36,63 @@ impl RolloverMainRepositoryTrait for RolloverMainRepository {
     }
 
     #[tracing::instrument]
-    async fn update(&self, rollover_main: RolloverMain) -> Result<RolloverMain, KgsStatus> {
+    async fn get_for_update(
+        &self,
+        wallet_info: &WalletInfo,
+    ) -> Result<Option<RolloverMain>, KgsStatus> {
         let cx = Context::current();
         let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
             warn!("get database transaction error");
             KgsStatus::InternalServerError
         })?;
 
-        let active_model = rollover_main::ActiveModel::from(rollover_main);
-        let entity = active_model.update(txn).await.map_err(|e| {
-            warn!("update rollover_main error: {:?}", e);
+        rollover_main::Entity::find()
+            .filter(rollover_main::Column::ClientId.eq(wallet_info.client_id))
+            .filter(rollover_main::Column::UserId.eq(wallet_info.user_id))
+            .filter(rollover_main::Column::CurrencyId.eq(wallet_info.currency.id))
+            .filter(rollover_main::Column::WalletSourceId.eq(wallet_info.wallet_source.id))
+            .lock_exclusive()
+            .one(txn)
+            .await
+            .map(|entity| entity.map(|entity| entity.into()))
+            .map_err(|e| {
+                warn!("get rollover_main for update error: {:?}", e);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn update(&self, rollover_main: RolloverMain) -> Result<RolloverMain, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
             KgsStatus::InternalServerError
         })?;
 
+        // 只更新版本號與讀取時相同的資料 版本號不同代表已被其他交易修改
+        let id = rollover_main.id;
+        let version = rollover_main.version;
+        let mut active_model = rollover_main::ActiveModel::from(rollover_main);
+        active_model.version = Set(version + 1);
+
+        let entity = rollover_main::Entity::update(active_model)
+            .filter(rollover_main::Column::Version.eq(version))
+            .exec(txn)
+            .await
+            .map_err(|e| match e {
+                DbErr::RecordNotUpdated => {
+                    warn!(
+                        "update rollover_main version conflict id: {}, version: {}",
+                        id, version
+                    );
+                    KgsStatus::ConcurrentUpdateConflict
+                }
+                e => {
+                    warn!("update rollover_main error: {:?}", e);
+                    KgsStatus::InternalServerError
+                }
+            })?;
+
         Ok(entity.into())
     }
 
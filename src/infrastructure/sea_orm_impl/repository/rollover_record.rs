@@ -70,4 +70,86 @@ impl RolloverRecordRepositoryTrait for RolloverRecordRepository {
                 KgsStatus::InternalServerError
             })
     }
+
+    #[tracing::instrument]
+    async fn get_list_with_transaction(
+        &self,
+        select_query: SelectRolloverRecordsQuery,
+    ) -> Result<Vec<RolloverRecordWithTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let mut values: Vec<Value> = vec![select_query.client_id.into()];
+        let main_condition = match select_query.rollover_main {
+            RolloverMainFilter::MainId(main_id) => {
+                values.push(main_id.into());
+                "m.id = $2"
+            }
+            RolloverMainFilter::Wallet {
+                user_id,
+                currency_id,
+                wallet_source_id,
+            } => {
+                values.push(user_id.into());
+                values.push(currency_id.into());
+                values.push(wallet_source_id.into());
+                "m.user_id = $2 AND m.currency_id = $3 AND m.wallet_source_id = $4"
+            }
+        };
+        let limit_index = values.len() + 1;
+        values.push((select_query.page_size as i64).into());
+        values.push((((select_query.page - 1) * select_query.page_size) as i64).into());
+
+        // 先計算累計流水 再分頁
+        let sql = format!(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    r.id,
+                    r.main_id,
+                    r.client_id,
+                    r.user_id,
+                    r.requirement_rollover,
+                    r.requirement_rollover_rate,
+                    r.achievement_rollover,
+                    r.achievement_rollover_rate,
+                    r.contribution_weight,
+                    r.create_by,
+                    r.wallet_txn_id,
+                    r.create_at,
+                    t.action AS wallet_txn_action,
+                    t.status AS wallet_txn_status,
+                    t.transaction_source_id,
+                    t.change_amount AS wallet_txn_change_amount,
+                    SUM(r.requirement_rollover) OVER (PARTITION BY r.main_id ORDER BY r.id)
+                        AS total_requirement_rollover,
+                    SUM(r.achievement_rollover) OVER (PARTITION BY r.main_id ORDER BY r.id)
+                        AS total_achievement_rollover
+                FROM rollover_record r
+                INNER JOIN rollover_main m ON m.id = r.main_id
+                LEFT JOIN wallet_transaction t ON t.id = r.wallet_txn_id
+                WHERE m.client_id = $1 AND {main_condition}
+            ) records
+            ORDER BY records.id DESC
+            LIMIT ${limit_index} OFFSET ${offset_index}
+            "#,
+            main_condition = main_condition,
+            limit_index = limit_index,
+            offset_index = limit_index + 1,
+        );
+
+        sea_orm_impl::aggregate::RolloverRecordWithTransaction::find_by_statement(
+            Statement::from_sql_and_values(DbBackend::Postgres, sql, values),
+        )
+        .all(txn)
+        .await
+        .map(|entities| entities.into_iter().map(|entity| entity.into()).collect())
+        .map_err(|e| {
+            warn!("get rollover_record list error: {:?}", e);
+            KgsStatus::InternalServerError
+        })
+    }
 }
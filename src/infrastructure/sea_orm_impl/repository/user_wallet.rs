@@ -7,6 +7,7 @@ use sea_orm::sea_query::PgFunc;
 use sea_orm::*;
 
 use crate::domain::{self, *};
+use crate::infrastructure::sea_orm_impl;
 use crate::infrastructure::sea_orm_impl::aggregate::UserWalletWithRollover;
 use crate::infrastructure::sea_orm_impl::entity::rollover_main;
 use crate::infrastructure::sea_orm_impl::entity::user_wallet;
@@ -38,6 +39,32 @@ impl UserWalletRepositoryTrait for UserWalletRepository {
             })
     }
 
+    #[tracing::instrument]
+    async fn get_for_update(
+        &self,
+        wallet_info: &WalletInfo,
+    ) -> Result<Option<UserWallet>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        user_wallet::Entity::find()
+            .filter(user_wallet::Column::ClientId.eq(wallet_info.client_id))
+            .filter(user_wallet::Column::UserId.eq(wallet_info.user_id))
+            .filter(user_wallet::Column::CurrencyId.eq(wallet_info.currency.id))
+            .filter(user_wallet::Column::WalletSourceId.eq(wallet_info.wallet_source.id))
+            .lock_exclusive()
+            .one(txn)
+            .await
+            .map(|entity| entity.map(|entity| entity.into()))
+            .map_err(|e| {
+                warn!("get user_wallet for update error: {:?}", e);
+                KgsStatus::InternalServerError
+            })
+    }
+
     #[tracing::instrument]
     async fn get_user_wallets_with_rollover(
         &self,
@@ -59,6 +86,7 @@ impl UserWalletRepositoryTrait for UserWalletRepository {
             .column(user_wallet::Column::WalletSourceId)
             .column(user_wallet::Column::WalletSourceName)
             .column(user_wallet::Column::Amount)
+            .column(user_wallet::Column::HoldAmount)
             .column(rollover_main::Column::RequirementRollover)
             .column(rollover_main::Column::AchievementRollover)
             .filter(user_wallet::Column::ClientId.eq(select_query.client_id));
@@ -131,15 +159,156 @@ impl UserWalletRepositoryTrait for UserWalletRepository {
             KgsStatus::InternalServerError
         })?;
 
-        let active_model = user_wallet::ActiveModel::from(user_wallet);
+        // 只更新版本號與讀取時相同的資料 版本號不同代表已被其他交易修改
+        let id = user_wallet.id;
+        let version = user_wallet.version;
+        let mut active_model = user_wallet::ActiveModel::from(user_wallet);
+        active_model.version = Set(version + 1);
 
-        active_model
-            .update(txn)
+        user_wallet::Entity::update(active_model)
+            .filter(user_wallet::Column::Version.eq(version))
+            .exec(txn)
             .await
             .map(|entity| entity.into())
+            .map_err(|e| match e {
+                DbErr::RecordNotUpdated => {
+                    warn!(
+                        "update user_wallet version conflict id: {}, version: {}",
+                        id, version
+                    );
+                    KgsStatus::ConcurrentUpdateConflict
+                }
+                e => {
+                    warn!("update user_wallet error: {:?}", e);
+                    KgsStatus::InternalServerError
+                }
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_client_id_list(&self) -> Result<Vec<i64>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        user_wallet::Entity::find()
+            .select_only()
+            .column(user_wallet::Column::ClientId)
+            .distinct()
+            .order_by_asc(user_wallet::Column::ClientId)
+            .into_tuple::<i64>()
+            .all(txn)
+            .await
             .map_err(|e| {
-                warn!("update user_wallet error: {:?}", e);
+                warn!("get user_wallet client_id list error: {:?}", e);
                 KgsStatus::InternalServerError
             })
     }
+
+    #[tracing::instrument]
+    async fn get_drift_list(
+        &self,
+        client_id: i64,
+        user_id: Option<i64>,
+    ) -> Result<Vec<domain::WalletDrift>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        // 入帳: 1 GameDeposit, 3 PaymentDeposit, 5 PaymentWithdrawReject, 7 TransferIn, 10 BonusGrant
+        // 出帳: 2 GameWithdraw, 4 PaymentWithdraw, 6 TransferOut, 9 BonusForfeit, 11 BonusCancel
+        // 8 Adjustment 是修正錢包金額的紀錄 不計入交易紀錄金額
+        // 審核通過的調整視為重新起算 只檢查最後一筆調整之後的交易紀錄前後金額是否連續
+        // 凍結中的交易在確認時才更新 update_at 所以依照 update_at 排序
+        let sql = r#"
+            WITH txn AS (
+                SELECT
+                    t.id,
+                    t.user_id,
+                    t.currency_id,
+                    t.wallet_source_id,
+                    t.action,
+                    t.before_amount,
+                    t.change_amount,
+                    LAG(t.after_amount, 1, 0) OVER (
+                        PARTITION BY t.user_id, t.currency_id, t.wallet_source_id
+                        ORDER BY t.update_at, t.id
+                    ) AS prev_after_amount,
+                    COUNT(*) FILTER (WHERE t.action = 8) OVER (
+                        PARTITION BY t.user_id, t.currency_id, t.wallet_source_id
+                        ORDER BY t.update_at, t.id
+                    ) AS adjustment_seq
+                FROM wallet_transaction t
+                WHERE t.client_id = $1
+                    AND t.status = 1
+                    AND ($2::BIGINT IS NULL OR t.user_id = $2)
+            ),
+            chain AS (
+                SELECT
+                    txn.*,
+                    action <> 8
+                        AND before_amount <> prev_after_amount
+                        AND adjustment_seq = MAX(adjustment_seq) OVER (
+                            PARTITION BY user_id, currency_id, wallet_source_id
+                        ) AS is_broken
+                FROM txn
+            ),
+            ledger AS (
+                SELECT
+                    user_id,
+                    currency_id,
+                    wallet_source_id,
+                    SUM(
+                        CASE
+                            WHEN action IN (1, 3, 5, 7, 10) THEN change_amount
+                            WHEN action IN (2, 4, 6, 9, 11) THEN -change_amount
+                            ELSE 0
+                        END
+                    ) AS ledger_amount,
+                    COUNT(*) FILTER (WHERE is_broken) AS broken_chain_count,
+                    MIN(id) FILTER (WHERE is_broken) AS first_broken_txn_id
+                FROM chain
+                GROUP BY user_id, currency_id, wallet_source_id
+            )
+            SELECT
+                w.id AS user_wallet_id,
+                w.client_id,
+                w.user_id,
+                w.currency_id,
+                w.wallet_source_id,
+                w.amount AS wallet_amount,
+                COALESCE(l.ledger_amount, 0) AS ledger_amount,
+                COALESCE(l.broken_chain_count, 0) AS broken_chain_count,
+                l.first_broken_txn_id
+            FROM user_wallet w
+            LEFT JOIN ledger l
+                ON l.user_id = w.user_id
+                AND l.currency_id = w.currency_id
+                AND l.wallet_source_id = w.wallet_source_id
+            WHERE w.client_id = $1
+                AND ($2::BIGINT IS NULL OR w.user_id = $2)
+                AND (
+                    w.amount <> COALESCE(l.ledger_amount, 0)
+                    OR COALESCE(l.broken_chain_count, 0) > 0
+                )
+            ORDER BY w.id
+        "#;
+
+        sea_orm_impl::aggregate::WalletDrift::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [client_id.into(), user_id.into()],
+        ))
+        .all(txn)
+        .await
+        .map(|entities| entities.into_iter().map(|entity| entity.into()).collect())
+        .map_err(|e| {
+            warn!("get user_wallet drift list error: {:?}", e);
+            KgsStatus::InternalServerError
+        })
+    }
 }
@@ -0,0 +1,65 @@
+use std::fmt::Debug;
+
+use database_manager::Context;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+use sea_orm::*;
+
+use crate::domain::{self, WalletLimitRepositoryTrait};
+use crate::infrastructure::sea_orm_impl::entity::{wallet_action_limit, wallet_limit};
+
+#[derive(Debug)]
+pub struct WalletLimitRepository;
+
+#[tonic::async_trait]
+impl WalletLimitRepositoryTrait for WalletLimitRepository {
+    #[tracing::instrument]
+    async fn get(
+        &self,
+        client_id: i64,
+        currency_id: i64,
+    ) -> Result<Option<domain::WalletLimit>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_limit::Entity::find()
+            .filter(wallet_limit::Column::ClientId.eq(client_id))
+            .filter(wallet_limit::Column::CurrencyId.eq(currency_id))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get wallet limit failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_action_limit(
+        &self,
+        client_id: i64,
+        currency_id: i64,
+        action: i32,
+    ) -> Result<Option<domain::WalletActionLimit>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_action_limit::Entity::find()
+            .filter(wallet_action_limit::Column::ClientId.eq(client_id))
+            .filter(wallet_action_limit::Column::CurrencyId.eq(currency_id))
+            .filter(wallet_action_limit::Column::Action.eq(action))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get wallet action limit failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::Debug;
+
+use database_manager::Context;
+use kgs_err::models::status::Status as KgsStatus;
+use kgs_tracing::{tracing, warn};
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::{LockBehavior, LockType};
+use sea_orm::*;
+
+use crate::domain;
+use crate::domain::WalletOutboxRepositoryTrait;
+use crate::enums;
+use crate::infrastructure::sea_orm_impl::entity::wallet_outbox;
+
+#[derive(Debug)]
+pub struct WalletOutboxRepository;
+
+#[tonic::async_trait]
+impl WalletOutboxRepositoryTrait for WalletOutboxRepository {
+    #[tracing::instrument]
+    async fn insert(
+        &self,
+        outbox: domain::WalletOutbox,
+    ) -> Result<domain::WalletOutbox, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let active_model = wallet_outbox::ActiveModel::from(outbox);
+
+        active_model
+            .insert(txn)
+            .await
+            .map(|model| model.into())
+            .map_err(|err| {
+                warn!("insert wallet outbox failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_pending_for_update(
+        &self,
+        limit: u64,
+    ) -> Result<Vec<domain::WalletOutbox>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_outbox::Entity::find()
+            .filter(wallet_outbox::Column::Status.eq(enums::OutboxStatus::Pending.to_id()))
+            .order_by_asc(wallet_outbox::Column::Id)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(txn)
+            .await
+            .map(|models| models.into_iter().map(|model| model.into()).collect())
+            .map_err(|err| {
+                warn!("get pending wallet outbox failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn mark_published(&self, ids: Vec<i64>) -> Result<(), KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_outbox::Entity::update_many()
+            .col_expr(
+                wallet_outbox::Column::Status,
+                Expr::value(enums::OutboxStatus::Published.to_id()),
+            )
+            .col_expr(
+                wallet_outbox::Column::PublishAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(wallet_outbox::Column::Id.is_in(ids))
+            .exec(txn)
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                warn!("mark wallet outbox published failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn increase_retry_count(&self, id: i64) -> Result<(), KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_outbox::Entity::update_many()
+            .col_expr(
+                wallet_outbox::Column::RetryCount,
+                Expr::col(wallet_outbox::Column::RetryCount).add(1),
+            )
+            .filter(wallet_outbox::Column::Id.eq(id))
+            .exec(txn)
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                warn!("increase wallet outbox retry count failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+}
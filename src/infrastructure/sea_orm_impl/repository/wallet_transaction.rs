@@ -1,12 +1,15 @@
 use std::fmt::Debug;
 
+use bigdecimal::BigDecimal;
 use database_manager::Context;
 use kgs_err::models::status::Status as KgsStatus;
 use kgs_tracing::{tracing, warn};
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::PgFunc;
 use sea_orm::*;
 
 use crate::domain;
-use crate::domain::WalletTransactionRepositoryTrait;
+use crate::domain::{SelectWalletTransactionsQuery, WalletTransactionRepositoryTrait};
 use crate::infrastructure::sea_orm_impl::entity::wallet_transaction;
 
 #[derive(Debug)]
@@ -14,6 +17,24 @@ pub struct WalletTransactionRepository;
 
 #[tonic::async_trait]
 impl WalletTransactionRepositoryTrait for WalletTransactionRepository {
+    #[tracing::instrument]
+    async fn get(&self, id: i64) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_transaction::Entity::find_by_id(id)
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get wallet transaction failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
     #[tracing::instrument]
     async fn insert(
         &self,
@@ -33,6 +54,54 @@ impl WalletTransactionRepositoryTrait for WalletTransactionRepository {
             .map(|model| model.into())
             .map_err(|err| {
                 warn!("insert wallet transaction failed: {:?}", err);
+                match err.sql_err() {
+                    Some(SqlErr::UniqueConstraintViolation(_)) => KgsStatus::DuplicateTransaction,
+                    _ => KgsStatus::InternalServerError,
+                }
+            })
+    }
+
+    #[tracing::instrument]
+    async fn update(
+        &self,
+        wallet_txn: domain::WalletTransaction,
+    ) -> Result<domain::WalletTransaction, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let active_model = wallet_transaction::ActiveModel::from(wallet_txn);
+
+        active_model
+            .update(txn)
+            .await
+            .map(|model| model.into())
+            .map_err(|err| {
+                warn!("update wallet transaction failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_child(
+        &self,
+        parent_id: i64,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_transaction::Entity::find()
+            .filter(wallet_transaction::Column::ParentId.eq(parent_id))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get child wallet transaction failed: {:?}", err);
                 KgsStatus::InternalServerError
             })
     }
@@ -62,4 +131,232 @@ impl WalletTransactionRepositoryTrait for WalletTransactionRepository {
                 KgsStatus::InternalServerError
             })
     }
+
+    #[tracing::instrument]
+    async fn get_list(
+        &self,
+        select_query: &SelectWalletTransactionsQuery,
+        limit: u64,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        let mut query = wallet_transaction::Entity::find()
+            .filter(wallet_transaction::Column::ClientId.eq(select_query.client_id));
+
+        if !select_query.player_ids.is_empty() {
+            query = query.filter(
+                Expr::col(wallet_transaction::Column::UserId)
+                    .eq(PgFunc::any(select_query.player_ids.clone())),
+            );
+        }
+        if !select_query.currency_ids.is_empty() {
+            query = query.filter(
+                Expr::col(wallet_transaction::Column::CurrencyId)
+                    .eq(PgFunc::any(select_query.currency_ids.clone())),
+            );
+        }
+        if !select_query.wallet_source_ids.is_empty() {
+            query = query.filter(
+                Expr::col(wallet_transaction::Column::WalletSourceId)
+                    .eq(PgFunc::any(select_query.wallet_source_ids.clone())),
+            );
+        }
+        if !select_query.actions.is_empty() {
+            query = query.filter(
+                Expr::col(wallet_transaction::Column::Action)
+                    .eq(PgFunc::any(select_query.actions.clone())),
+            );
+        }
+        if !select_query.statuses.is_empty() {
+            query = query.filter(
+                Expr::col(wallet_transaction::Column::Status)
+                    .eq(PgFunc::any(select_query.statuses.clone())),
+            );
+        }
+        if let Some(start_time) = select_query.start_time {
+            query = query.filter(wallet_transaction::Column::CreateAt.gte(start_time));
+        }
+        if let Some(end_time) = select_query.end_time {
+            query = query.filter(wallet_transaction::Column::CreateAt.lt(end_time));
+        }
+        if let Some(cursor) = select_query.cursor {
+            query = query.filter(wallet_transaction::Column::Id.lt(cursor));
+        }
+
+        query
+            .order_by_desc(wallet_transaction::Column::Id)
+            .limit(limit)
+            .all(txn)
+            .await
+            .map(|models| models.into_iter().map(|model| model.into()).collect())
+            .map_err(|err| {
+                warn!("get wallet transaction list failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_list_by_ids(
+        &self,
+        ids: Vec<i64>,
+    ) -> Result<Vec<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_transaction::Entity::find()
+            .filter(Expr::col(wallet_transaction::Column::Id).eq(PgFunc::any(ids)))
+            .all(txn)
+            .await
+            .map(|models| models.into_iter().map(|model| model.into()).collect())
+            .map_err(|err| {
+                warn!("get wallet transaction list by ids failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_root_by_transaction_source_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        source_txn_id: i64,
+        action: i32,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_transaction::Entity::find()
+            .filter(wallet_transaction::Column::ClientId.eq(client_id))
+            .filter(wallet_transaction::Column::UserId.eq(user_id))
+            .filter(wallet_transaction::Column::TransactionSourceId.eq(source_txn_id))
+            .filter(wallet_transaction::Column::Action.eq(action))
+            .filter(wallet_transaction::Column::ParentId.eq(0))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get root wallet transaction failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_by_hold_id(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        hold_id: i64,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_transaction::Entity::find()
+            .filter(wallet_transaction::Column::ClientId.eq(client_id))
+            .filter(wallet_transaction::Column::UserId.eq(user_id))
+            .filter(wallet_transaction::Column::HoldId.eq(hold_id))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!("get wallet transaction by hold id failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_by_payment_order_id(
+        &self,
+        client_id: i64,
+        payment_order_id: &str,
+    ) -> Result<Option<domain::WalletTransaction>, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        wallet_transaction::Entity::find()
+            .filter(wallet_transaction::Column::ClientId.eq(client_id))
+            .filter(wallet_transaction::Column::PaymentOrderId.eq(payment_order_id))
+            .one(txn)
+            .await
+            .map(|model| model.map(|model| model.into()))
+            .map_err(|err| {
+                warn!(
+                    "get wallet transaction by payment order id failed: {:?}",
+                    err
+                );
+                KgsStatus::InternalServerError
+            })
+    }
+
+    #[tracing::instrument]
+    async fn get_withdraw_total(
+        &self,
+        client_id: i64,
+        user_id: i64,
+        currency_id: i64,
+        since: chrono::NaiveDateTime,
+    ) -> Result<BigDecimal, KgsStatus> {
+        let cx = Context::current();
+        let txn = cx.get::<DatabaseTransaction>().ok_or_else(|| {
+            warn!("get database transaction error");
+            KgsStatus::InternalServerError
+        })?;
+
+        // 4 PaymentWithdraw 狀態為凍結中(0)或成功(1)
+        // 排除被 5 PaymentWithdrawReject 退回 或被 3 PaymentDeposit 回滾的出金
+        let sql = r#"
+            SELECT COALESCE(SUM(ABS(t.change_amount)), 0) AS total
+            FROM wallet_transaction t
+            WHERE t.client_id = $1
+                AND t.user_id = $2
+                AND t.currency_id = $3
+                AND t.action = 4
+                AND t.status IN (0, 1)
+                AND t.create_at >= $4
+                AND NOT EXISTS (
+                    SELECT 1 FROM wallet_transaction r
+                    WHERE r.parent_id = t.id AND r.action IN (3, 5)
+                )
+        "#;
+
+        let row = txn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [
+                    client_id.into(),
+                    user_id.into(),
+                    currency_id.into(),
+                    since.into(),
+                ],
+            ))
+            .await
+            .map_err(|err| {
+                warn!("get withdraw total failed: {:?}", err);
+                KgsStatus::InternalServerError
+            })?;
+
+        match row {
+            Some(row) => row.try_get::<BigDecimal>("", "total").map_err(|err| {
+                warn!("get withdraw total failed: {:?}", err);
+                KgsStatus::InternalServerError
+            }),
+            None => Ok(BigDecimal::from(0)),
+        }
+    }
 }
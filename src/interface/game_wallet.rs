@@ -55,6 +55,18 @@ impl game_wallet_server::GameWallet for GameWalletService {
             .map_err(|e| kgs_err::grpc::error::error_response(e))
     }
 
+    #[tracing::instrument]
+    async fn batch_settle(
+        &self,
+        request: tonic::Request<BatchSettleRequest>,
+    ) -> Result<tonic::Response<BatchSettleResponse>, tonic::Status> {
+        self.game_wallet_service
+            .batch_settle(request.into_inner())
+            .await
+            .map(|res| tonic::Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
     #[tracing::instrument]
     async fn update(
         &self,
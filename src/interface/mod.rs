@@ -1,5 +1,7 @@
 mod game_wallet;
 mod player_wallet;
+mod reconciliation;
 
 pub use game_wallet::*;
 pub use player_wallet::*;
+pub use reconciliation::*;
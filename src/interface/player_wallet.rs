@@ -2,7 +2,7 @@ use kgs_tracing::tracing;
 use protos::player_wallet;
 use tonic::Response;
 
-use crate::application;
+use crate::{application, infrastructure};
 
 #[derive(Debug)]
 pub struct PlayerWalletService {
@@ -58,11 +58,43 @@ impl player_wallet::player_wallet_server::PlayerWallet for PlayerWalletService {
         &self,
         request: tonic::Request<player_wallet::GetPlayerWalletListRequest>,
     ) -> Result<tonic::Response<player_wallet::GetPlayerWalletListResponse>, tonic::Status> {
-        self.player_wallet_app
-            .get_list(request.into_inner())
-            .await
-            .map(|res| Response::new(res))
-            .map_err(|e| kgs_err::grpc::error::error_response(e))
+        // 查詢使用讀取用的資料庫
+        infrastructure::database::with_read_replica(
+            self.player_wallet_app.get_list(request.into_inner()),
+        )
+        .await
+        .map(|res| Response::new(res))
+        .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn get_transaction_list(
+        &self,
+        request: tonic::Request<player_wallet::GetTransactionListRequest>,
+    ) -> Result<tonic::Response<player_wallet::GetTransactionListResponse>, tonic::Status> {
+        // 查詢使用讀取用的資料庫
+        infrastructure::database::with_read_replica(
+            self.player_wallet_app
+                .get_transaction_list(request.into_inner()),
+        )
+        .await
+        .map(|res| Response::new(res))
+        .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn get_rollover_record_list(
+        &self,
+        request: tonic::Request<player_wallet::GetRolloverRecordListRequest>,
+    ) -> Result<tonic::Response<player_wallet::GetRolloverRecordListResponse>, tonic::Status> {
+        // 查詢使用讀取用的資料庫
+        infrastructure::database::with_read_replica(
+            self.player_wallet_app
+                .get_rollover_record_list(request.into_inner()),
+        )
+        .await
+        .map(|res| Response::new(res))
+        .map_err(|e| kgs_err::grpc::error::error_response(e))
     }
 
     #[tracing::instrument]
@@ -82,11 +114,82 @@ impl player_wallet::player_wallet_server::PlayerWallet for PlayerWalletService {
         &self,
         request: tonic::Request<player_wallet::PlayerTransferRequest>,
     ) -> Result<tonic::Response<player_wallet::WalletModel>, tonic::Status> {
-        // self.player_wallet_app
-        //     .transfer(request.into_inner())
-        //     .await
-        //     .map(|res| Response::new(res))
-        //     .map_err(|e| kgs_err::grpc::error::error_response(e))
-        Err(tonic::Status::new(tonic::Code::NotFound, "尚未實作"))
+        self.player_wallet_app
+            .transfer(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn hold(
+        &self,
+        request: tonic::Request<player_wallet::PlayerHoldRequest>,
+    ) -> Result<tonic::Response<player_wallet::WalletModel>, tonic::Status> {
+        self.player_wallet_app
+            .hold(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn commit_hold(
+        &self,
+        request: tonic::Request<player_wallet::PlayerHoldActionRequest>,
+    ) -> Result<tonic::Response<player_wallet::WalletModel>, tonic::Status> {
+        self.player_wallet_app
+            .commit_hold(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn release_hold(
+        &self,
+        request: tonic::Request<player_wallet::PlayerHoldActionRequest>,
+    ) -> Result<tonic::Response<player_wallet::WalletModel>, tonic::Status> {
+        self.player_wallet_app
+            .release_hold(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn withdraw_reject(
+        &self,
+        request: tonic::Request<player_wallet::WithdrawRejectRequest>,
+    ) -> Result<tonic::Response<player_wallet::WalletModel>, tonic::Status> {
+        self.player_wallet_app
+            .withdraw_reject(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn grant_bonus(
+        &self,
+        request: tonic::Request<player_wallet::GrantBonusRequest>,
+    ) -> Result<tonic::Response<player_wallet::BonusGrantResponse>, tonic::Status> {
+        self.player_wallet_app
+            .grant_bonus(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn cancel_bonus(
+        &self,
+        request: tonic::Request<player_wallet::CancelBonusRequest>,
+    ) -> Result<tonic::Response<player_wallet::BonusGrantResponse>, tonic::Status> {
+        self.player_wallet_app
+            .cancel_bonus(request.into_inner())
+            .await
+            .map(|res| Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
     }
 }
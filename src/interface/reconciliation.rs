@@ -0,0 +1,49 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::application;
+use kgs_tracing::tracing;
+use protos::reconciliation::*;
+
+#[derive(Debug)]
+pub struct ReconciliationService {
+    // 與背景對帳排程共用
+    reconciliation_service: Arc<application::ReconciliationService>,
+}
+
+impl ReconciliationService {
+    pub fn new(
+        reconciliation_service: Arc<application::ReconciliationService>,
+    ) -> ReconciliationService {
+        ReconciliationService {
+            reconciliation_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl reconciliation_server::Reconciliation for ReconciliationService {
+    #[tracing::instrument]
+    async fn reconcile(
+        &self,
+        request: tonic::Request<ReconcileRequest>,
+    ) -> Result<tonic::Response<ReconcileResponse>, tonic::Status> {
+        self.reconciliation_service
+            .reconcile(request.into_inner())
+            .await
+            .map(|res| tonic::Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+
+    #[tracing::instrument]
+    async fn approve(
+        &self,
+        request: tonic::Request<ApproveReconciliationRequest>,
+    ) -> Result<tonic::Response<ReconciliationReportModel>, tonic::Status> {
+        self.reconciliation_service
+            .approve(request.into_inner())
+            .await
+            .map(|res| tonic::Response::new(res))
+            .map_err(|e| kgs_err::grpc::error::error_response(e))
+    }
+}
@@ -1,7 +1,8 @@
-use std::{sync::Arc, time::Duration};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
-use context::common::context::Context;
-use kgs_tracing::{info, tracing};
+use bigdecimal::BigDecimal;
+use context::common::context::{Context, FutureExt};
+use kgs_tracing::{info, tracing, warn};
 use tokio;
 use tonic::transport::Server;
 
@@ -14,12 +15,51 @@ mod interface;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 管理 schema 的子指令 執行完就結束 不啟動服務
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        return migration_command(command, args.get(1)).await;
+    }
+
     init_telemetry();
-    // init_wallet_db().await;
 
     wallet_grpc_server().await?;
 
-    tokio::signal::ctrl_c().await.unwrap();
+    info!("wallet grpc server stopped");
+    Ok(())
+}
+
+/// 管理資料庫 schema 的子指令
+///
+/// - `status`: 列出已執行與尚未執行的 migration
+/// - `up [n]`: 執行尚未執行的 migration 不指定 n 表示全部執行
+/// - `down [n]`: 還原最後 n 個 migration 不指定 n 表示還原 1 個
+async fn migration_command(
+    command: &str,
+    steps: Option<&String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use migration::{Migrator, MigratorTrait};
+
+    if !["status", "up", "down"].contains(&command) {
+        return Err(format!("未知的指令 {} 可用的指令: status / up / down", command).into());
+    }
+    let steps = steps.map(|steps| steps.parse::<u32>()).transpose()?;
+    let db = infrastructure::database::connect_migration(config::get_wallet_db()).await?;
+
+    match command {
+        "status" => {
+            for migration in Migrator::get_applied_migrations(&db).await? {
+                println!("applied  {}", migration.name());
+            }
+            for migration in Migrator::get_pending_migrations(&db).await? {
+                println!("pending  {}", migration.name());
+            }
+        }
+        "up" => Migrator::up(&db, steps).await?,
+        _ => Migrator::down(&db, Some(steps.unwrap_or(1))).await?,
+    }
+
+    db.close().await?;
     Ok(())
 }
 
@@ -40,26 +80,14 @@ fn init_telemetry() {
 }
 
 #[tracing::instrument]
-async fn init_wallet_db() {
-    let config = config::get_wallet_db();
-    let _db = database_manager::sea_orm::Builder::new()
-        .db_user(&config.wallet_db_user)
-        .db_password(&config.wallet_db_password)
-        .db_host(&config.wallet_db_host)
-        .db_port(&config.wallet_db_port)
-        .db_name(&config.wallet_db_name)
-        .max_connections(config.wallet_db_max_connection)
-        .min_connections(config.wallet_db_min_connection)
-        .logging(true)
-        .logging_level(database_manager::sea_orm::LogLevel::Info)
-        .build()
-        .await;
-}
-
-#[tracing::instrument]
-fn declare_service() -> (
+fn declare_service(
+    currency_service: Arc<dyn domain::CurrencyServiceTrait>,
+) -> (
     Arc<interface::PlayerWalletService>,
     Arc<interface::GameWalletService>,
+    Arc<interface::ReconciliationService>,
+    Arc<application::ReconciliationService>,
+    Arc<application::BonusExpiryService>,
 ) {
     use crate::domain::*;
     use crate::infrastructure::sea_orm_impl::repository::*;
@@ -72,17 +100,70 @@ fn declare_service() -> (
     let main_rollover_repo: Arc<dyn RolloverMainRepositoryTrait> = Arc::new(RolloverMainRepository);
     let rollover_record_repo: Arc<dyn RolloverRecordRepositoryTrait> =
         Arc::new(RolloverRecordRepository);
+    let outbox_repo: Arc<dyn WalletOutboxRepositoryTrait> = Arc::new(WalletOutboxRepository);
+    let reconciliation_report_repo: Arc<dyn ReconciliationReportRepositoryTrait> =
+        Arc::new(ReconciliationReportRepository);
+    let wallet_limit_repo: Arc<dyn WalletLimitRepositoryTrait> = Arc::new(WalletLimitRepository);
+    let bonus_grant_repo: Arc<dyn BonusGrantRepositoryTrait> = Arc::new(BonusGrantRepository);
+    let rollover_contribution_repo: Arc<dyn RolloverContributionRepositoryTrait> =
+        Arc::new(RolloverContributionRepository);
 
     // domain service
-    let currency_service: Arc<dyn CurrencyServiceTrait> = Arc::new(CurrencyService);
+    let wallet_lock_config = config::get_wallet_lock();
+    let lock_strategy = match wallet_lock_config.wallet_lock_mode {
+        config::WalletLockMode::Pessimistic => LockStrategy::Pessimistic,
+        config::WalletLockMode::Optimistic => LockStrategy::Optimistic {
+            max_retries: wallet_lock_config.wallet_lock_max_retries,
+        },
+    };
     let wallet_service: Arc<dyn WalletServiceTrait> = Arc::new(WalletService::new(
         wallet_txn_repo.clone(),
         user_wallet_repo.clone(),
+        outbox_repo.clone(),
+        lock_strategy,
+    ));
+    let wallet_limit_service: Arc<dyn WalletLimitServiceTrait> = Arc::new(WalletLimitService::new(
+        wallet_limit_repo.clone(),
+        wallet_txn_repo.clone(),
     ));
     let rollover_service: Arc<dyn RolloverServiceTrait> = Arc::new(RolloverService::new(
         main_rollover_repo.clone(),
         rollover_record_repo.clone(),
+        rollover_contribution_repo.clone(),
+    ));
+    let max_release_amount = config::get_bonus_release()
+        .bonus_release_max_amount
+        .as_ref()
+        .map(|amount| BigDecimal::from_str(amount).expect("Bonus_Release_Max_Amount 格式錯誤"));
+    let bonus_release_service: Arc<dyn BonusReleaseServiceTrait> =
+        Arc::new(BonusReleaseService::new(
+            wallet_service.clone(),
+            rollover_service.clone(),
+            main_rollover_repo.clone(),
+            wallet_source_repo.clone(),
+            bonus_grant_repo.clone(),
+            max_release_amount,
+        ));
+    let bonus_expiry_config = config::get_bonus_expiry();
+    let bonus_grant_service: Arc<dyn BonusGrantServiceTrait> = Arc::new(BonusGrantService::new(
+        bonus_grant_repo.clone(),
+        wallet_source_repo.clone(),
+        outbox_repo.clone(),
+        wallet_service.clone(),
+        rollover_service.clone(),
+        currency_service.clone(),
+        bonus_expiry_config
+            .bonus_expiry_default_secs
+            .map(|secs| chrono::Duration::seconds(secs as i64)),
     ));
+    let reconciliation_service: Arc<dyn ReconciliationServiceTrait> =
+        Arc::new(ReconciliationService::new(
+            user_wallet_repo.clone(),
+            reconciliation_report_repo.clone(),
+            wallet_source_repo.clone(),
+            wallet_service.clone(),
+            currency_service.clone(),
+        ));
 
     // application mapper
     let wallet_mapper: Arc<dyn application::WalletMapperTrait> = Arc::new(
@@ -94,58 +175,214 @@ fn declare_service() -> (
     // application service
     let player_wallet_service = application::UserWalletService::new(
         user_wallet_repo.clone(),
+        rollover_record_repo.clone(),
         wallet_source_repo.clone(),
         wallet_service.clone(),
         rollover_service.clone(),
+        bonus_release_service.clone(),
+        bonus_grant_service.clone(),
         wallet_mapper.clone(),
         query_mapper.clone(),
         currency_service.clone(),
+        wallet_limit_service.clone(),
     );
+    let game_wallet_config = config::get_game_wallet();
+    let deduct_order = match game_wallet_config.game_wallet_mode {
+        config::GameWalletMode::Single => None,
+        config::GameWalletMode::Combined => {
+            Some(match game_wallet_config.game_wallet_deduct_order {
+                config::GameWalletDeductOrder::BonusFirst => DeductOrder::BonusFirst,
+                config::GameWalletDeductOrder::NormalFirst => DeductOrder::NormalFirst,
+            })
+        }
+    };
     let game_wallet_service = application::GameWalletService::new(
         wallet_source_repo.clone(),
         wallet_service.clone(),
         rollover_service.clone(),
+        bonus_release_service.clone(),
         wallet_mapper.clone(),
+        wallet_limit_service.clone(),
+        deduct_order,
     );
+    let reconciliation_app = Arc::new(application::ReconciliationService::new(
+        user_wallet_repo.clone(),
+        reconciliation_service.clone(),
+    ));
+    let bonus_expiry_app = Arc::new(application::BonusExpiryService::new(
+        bonus_grant_service.clone(),
+        bonus_expiry_config.bonus_expiry_batch_size,
+    ));
 
     // api
     let user_wallet_api = Arc::new(interface::PlayerWalletService::new(player_wallet_service));
     let game_wallet_api = Arc::new(interface::GameWalletService::new(game_wallet_service));
+    let reconciliation_api = Arc::new(interface::ReconciliationService::new(
+        reconciliation_app.clone(),
+    ));
+
+    (
+        user_wallet_api,
+        game_wallet_api,
+        reconciliation_api,
+        reconciliation_app,
+        bonus_expiry_app,
+    )
+}
+
+#[tracing::instrument]
+async fn declare_outbox_relay() -> Arc<application::OutboxRelayService> {
+    use crate::domain::*;
+    use crate::infrastructure::rabbitmq::RabbitMQEventPublisher;
+    use crate::infrastructure::sea_orm_impl::repository::*;
+
+    let outbox_config = config::get_outbox();
+
+    let outbox_repo: Arc<dyn WalletOutboxRepositoryTrait> = Arc::new(WalletOutboxRepository);
+    let event_publisher: Arc<dyn EventPublisherTrait> =
+        Arc::new(RabbitMQEventPublisher::new(&outbox_config.outbox_exchange).await);
 
-    (user_wallet_api, game_wallet_api)
+    Arc::new(application::OutboxRelayService::new(
+        outbox_repo,
+        event_publisher,
+        outbox_config.outbox_relay_batch_size,
+    ))
 }
 
 #[tracing::instrument]
-async fn wallet_grpc_server() -> Result<(), tonic::transport::Error> {
+async fn wallet_grpc_server() -> Result<(), Box<dyn std::error::Error>> {
     use protos::game_wallet::game_wallet_server::GameWalletServer;
     use protos::player_wallet::player_wallet_server::PlayerWalletServer;
+    use protos::reconciliation::reconciliation_server::ReconciliationServer;
+    use tonic::server::NamedService;
 
     let host_config = config::get_host();
     let addr = format!("{}:{}", host_config.service_host, host_config.service_port)
         .parse()
         .unwrap();
 
-    let (player_wallet_api, game_wallet_api) = declare_service();
+    // 背景工作收到關閉通知後 處理完目前的工作就結束
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let mut workers = Vec::new();
+
+    // 幣別快取與清除快取的事件共用同一個 CurrencyService
+    let currency_cache_config = config::get_currency_cache();
+    let currency_service: Arc<dyn domain::CurrencyServiceTrait> =
+        Arc::new(domain::CurrencyService::new(Duration::from_secs(
+            currency_cache_config.currency_cache_ttl_secs,
+        )));
+    if let Some(exchange) = &currency_cache_config.currency_event_exchange {
+        let consumer = infrastructure::rabbitmq::CurrencyChangedConsumer::new(
+            currency_service.clone(),
+            exchange,
+            &currency_cache_config.currency_event_queue,
+        );
+        workers.push(tokio::spawn(consumer.run(shutdown_rx.clone())));
+    }
+
+    let (
+        player_wallet_api,
+        game_wallet_api,
+        reconciliation_api,
+        reconciliation_app,
+        bonus_expiry_app,
+    ) = declare_service(currency_service);
 
     info!("wallet grpc server start on {:?}", addr);
 
-    let db = context::common::db_impl::SeaPostgresBuilder::default()
-        .db_host("localhost")
-        .db_port("5432")
-        .db_user("admin")
-        .db_password("admin")
-        .db_name("wallet")
-        .max_connections(100)
-        .min_connections(2)
-        .connect_timeout(Duration::from_secs(60))
-        .sqlx_logging(true)
-        .sqlx_logging_level(context::common::db_impl::LogLevel::Info)
-        .build()
-        .await;
-
-    let context = Context::current().with_value(db);
-
-    Server::builder()
+    // 資料庫連不上時直接結束 不啟動服務
+    let wallet_db_config = config::get_wallet_db();
+    let db = infrastructure::database::build_wallet_db(
+        wallet_db_config,
+        &wallet_db_config.wallet_db_host,
+        &wallet_db_config.wallet_db_port,
+    )
+    .await?;
+    infrastructure::database::migrate_on_startup(
+        wallet_db_config,
+        &config::get_migration().migration_mode,
+    )
+    .await?;
+
+    let mut context = Context::current().with_value(db);
+    if let Some(read_host) = &wallet_db_config.wallet_db_read_host {
+        let read_port = wallet_db_config
+            .wallet_db_read_port
+            .as_ref()
+            .unwrap_or(&wallet_db_config.wallet_db_port);
+        let read_db =
+            infrastructure::database::build_wallet_db(wallet_db_config, read_host, read_port)
+                .await?;
+        context = context.with_value(infrastructure::database::ReadReplica(read_db));
+    }
+
+    // 背景發送 outbox 中的錢包事件
+    let outbox_relay = declare_outbox_relay().await;
+    let relay_interval = Duration::from_millis(config::get_outbox().outbox_relay_interval_ms);
+    workers.push(tokio::spawn(
+        outbox_relay
+            .run(relay_interval, shutdown_rx.clone())
+            .with_context(context.clone()),
+    ));
+
+    // 背景對帳
+    if let Some(secs) = config::get_reconciliation().reconciliation_interval_secs {
+        workers.push(tokio::spawn(
+            reconciliation_app
+                .run(Duration::from_secs(secs), shutdown_rx.clone())
+                .with_context(context.clone()),
+        ));
+    }
+
+    // 背景沒收到期的獎金
+    let bonus_expiry_interval =
+        Duration::from_secs(config::get_bonus_expiry().bonus_expiry_interval_secs);
+    workers.push(tokio::spawn(
+        bonus_expiry_app
+            .run(bonus_expiry_interval, shutdown_rx.clone())
+            .with_context(context.clone()),
+    ));
+
+    // grpc.health.v1 依照資料庫與 bank server 的狀態更新
+    let (health_reporter, health_server) = tonic_health::server::health_reporter();
+    let health_app = Arc::new(application::HealthService::new(
+        health_reporter,
+        vec![
+            <PlayerWalletServer<interface::PlayerWalletService> as NamedService>::NAME,
+            <GameWalletServer<interface::GameWalletService> as NamedService>::NAME,
+        ],
+        vec![<ReconciliationServer<interface::ReconciliationService> as NamedService>::NAME],
+    ));
+    let health_interval = Duration::from_secs(config::get_health().health_check_interval_secs);
+    workers.push(tokio::spawn(
+        health_app
+            .clone()
+            .run(health_interval, shutdown_rx.clone())
+            .with_context(context.clone()),
+    ));
+
+    // 讓 grpcurl ghz 等工具不需要 proto 檔就能查詢服務
+    let reflection_server = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(protos::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build()?;
+
+    // 收到關閉訊號時 先將 health 設為 NOT_SERVING 等待負載平衡停止轉送後
+    // 再通知背景工作結束 最後停止接收新的請求
+    let grace_period = Duration::from_secs(config::get_host().shutdown_grace_period_secs);
+    let shutdown = async move {
+        shutdown_signal().await;
+        health_app.set_not_serving().await;
+        info!(
+            "shutdown signal received, wait {:?} before draining",
+            grace_period
+        );
+        tokio::time::sleep(grace_period).await;
+        info!("draining in-flight requests");
+        let _ = shutdown_tx.send(true);
+    };
+
+    let server = Server::builder()
         .layer(kgs_tracing::middlewares::tonic::root_span_builder())
         .layer(kgs_tracing::middlewares::tonic::TracingRecord::default())
         .layer(context::common::context_middleware::ContextHolder::new(
@@ -153,6 +390,51 @@ async fn wallet_grpc_server() -> Result<(), tonic::transport::Error> {
         ))
         .add_service(PlayerWalletServer::from_arc(player_wallet_api))
         .add_service(GameWalletServer::from_arc(game_wallet_api))
-        .serve(addr)
+        .add_service(ReconciliationServer::from_arc(reconciliation_api))
+        .add_service(health_server)
+        .add_service(reflection_server)
+        .serve_with_shutdown(addr, shutdown);
+
+    // 處理中的請求超過等待時間仍未完成時 直接結束 未提交的交易會由資料庫回滾
+    let drain_timeout = Duration::from_secs(config::get_host().shutdown_drain_timeout_secs);
+    let mut drain_rx = shutdown_rx.clone();
+    let drain_deadline = async move {
+        let _ = drain_rx.changed().await;
+        tokio::time::sleep(drain_timeout).await;
+    };
+    tokio::select! {
+        res = server => res?,
+        _ = drain_deadline => warn!("drain timeout {:?}, abort in-flight requests", drain_timeout),
+    }
+
+    if tokio::time::timeout(drain_timeout, futures::future::join_all(workers))
         .await
+        .is_err()
+    {
+        warn!("background workers did not stop in {:?}", drain_timeout);
+    }
+
+    Ok(())
+}
+
+/// 等待 SIGINT 或 SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("監聽 SIGINT 失敗");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("監聽 SIGTERM 失敗")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
 }